@@ -0,0 +1,158 @@
+/*!
+Derive macros for [parsa](https://docs.rs/parsa). Use them through parsa's `derive` feature rather
+than depending on this crate directly.
+*/
+
+use proc_macro2::TokenStream;
+use quote::{quote, format_ident};
+use syn::{parse_macro_input, Attribute, Data, DeriveInput, Fields, LitInt, Type};
+
+#[derive(Clone, Copy)]
+enum Endian {
+    Big,
+    Little,
+}
+
+//the options set with `#[parsa(...)]` on a struct or field
+struct Options {
+    endian: Option<Endian>,
+    pad: usize,
+}
+
+fn options(attrs: &[Attribute]) -> syn::Result<Options> {
+    let mut out = Options { endian: None, pad: 0 };
+    for attr in attrs.iter().filter(|a| a.path().is_ident("parsa")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("big_endian") {
+                out.endian = Some(Endian::Big);
+            } else if meta.path.is_ident("little_endian") {
+                out.endian = Some(Endian::Little);
+            } else if meta.path.is_ident("pad") {
+                out.pad = meta.value()?.parse::<LitInt>()?.base10_parse()?;
+            } else {
+                return Err(meta.error("expected `big_endian`, `little_endian` or `pad = N`"))
+            }
+            Ok(())
+        })?;
+    }
+    Ok(out)
+}
+
+//an expression that reads a `ty` from `s`, failing with a `ReadErr`
+fn reader(ty: &Type, endian: Endian) -> TokenStream {
+    const WIDE: [&str; 10] = ["u16", "u32", "u64", "u128", "i16", "i32", "i64", "i128", "f32", "f64"];
+
+    match ty {
+        Type::Path(path) if path.qself.is_none() => {
+            let name = path.path.get_ident().map(ToString::to_string).unwrap_or_default();
+            if WIDE.contains(&name.as_str()) {
+                let f = match endian {
+                    Endian::Big => format_ident!("be_{name}"),
+                    Endian::Little => format_ident!("le_{name}"),
+                };
+                quote! { ::parsa::builtins::#f(s)? }
+            } else {
+                quote! { <#ty as ::parsa::ParsableBytes>::parse(s).map_err(::core::convert::Into::<::parsa::builtins::ReadErr>::into)? }
+            }
+        },
+        Type::Array(array) => {
+            let (ty, elem, len) = (&array.elem, reader(&array.elem, endian), &array.len);
+            quote! {{
+                let mut items: ::std::vec::Vec<#ty> = ::std::vec::Vec::with_capacity(#len);
+                for _ in 0..#len {
+                    items.push(#elem);
+                }
+                items.try_into().unwrap_or_else(|_| ::core::unreachable!())
+            }}
+        },
+        Type::Group(group) => reader(&group.elem, endian),
+        Type::Paren(paren) => reader(&paren.elem, endian),
+        _ => quote! { <#ty as ::parsa::ParsableBytes>::parse(s).map_err(::core::convert::Into::<::parsa::builtins::ReadErr>::into)? },
+    }
+}
+
+//skips `n` bytes of padding
+fn pad(n: usize) -> TokenStream {
+    match n {
+        0 => quote! {},
+        n => quote! { <[u8; #n] as ::parsa::ParsableBytes>::parse(s)?; },
+    }
+}
+
+/**
+Implements `ParsableBytes` for a struct with a fixed binary layout, reading its fields in order.
+Fields can be bytes, fixed-width numbers, arrays of them, or any other `ParsableBytes` type whose
+error converts into a `ReadErr`.
+
+Numbers are big-endian unless the struct or field has `#[parsa(little_endian)]`, and
+`#[parsa(big_endian)]` switches back. `#[parsa(pad = N)]` skips `N` bytes before a field, or after
+the last field when put on the struct.
+```
+# use parsa::{ParserBytes, ParsableBytes};
+# use parsa::builtins::ReadErr;
+#[derive(ParsableBytes, Debug, PartialEq)]
+#[parsa(little_endian, pad = 1)]
+struct Header {
+    magic: [u8; 2],
+    len: u16,
+    #[parsa(big_endian, pad = 2)]
+    flags: [u16; 2],
+}
+
+let mut input = ParserBytes::from(&[b'P', b'K', 0x01, 0x00, 0, 0, 0x00, 0x01, 0x00, 0x02, 0xff, 7]);
+let header = Header::parse(&mut input).unwrap();
+assert_eq!(header, Header { magic: *b"PK", len: 1, flags: [1, 2] });
+assert_eq!(input.get(), &[7]);
+
+#[derive(ParsableBytes, Debug, PartialEq)]
+struct Packet(Header, i8);
+
+let mut input = ParserBytes::from(&[b'P', b'K', 0x01]);
+assert_eq!(Packet::parse(&mut input), Err(ReadErr { needed: 2, found: 1 }));
+```
+*/
+#[proc_macro_derive(ParsableBytes, attributes(parsa))]
+pub fn derive_parsable_bytes(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    parsable_bytes(input).unwrap_or_else(syn::Error::into_compile_error).into()
+}
+
+fn parsable_bytes(input: DeriveInput) -> syn::Result<TokenStream> {
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new_spanned(&input.ident, "ParsableBytes can only be derived for structs"))
+    };
+    let outer = options(&input.attrs)?;
+    let endian = outer.endian.unwrap_or(Endian::Big);
+
+    let mut reads = vec![];
+    let mut names = vec![];
+    for (i, field) in data.fields.iter().enumerate() {
+        let opts = options(&field.attrs)?;
+        let (pad, read) = (pad(opts.pad), reader(&field.ty, opts.endian.unwrap_or(endian)));
+        let name = format_ident!("field{i}");
+        reads.push(quote! { #pad let #name = #read; });
+        names.push(name);
+    }
+    let build = match &data.fields {
+        Fields::Named(fields) => {
+            let idents = fields.named.iter().map(|f| &f.ident);
+            quote! { Self { #(#idents: #names),* } }
+        },
+        Fields::Unnamed(_) => quote! { Self(#(#names),*) },
+        Fields::Unit => quote! { Self },
+    };
+    let trailing = pad(outer.pad);
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::parsa::ParsableBytes for #ident #ty_generics #where_clause {
+            type Err = ::parsa::builtins::ReadErr;
+            fn parse(s: &mut ::parsa::ParserBytes) -> ::core::result::Result<Self, Self::Err> {
+                #(#reads)*
+                #trailing
+                ::core::result::Result::Ok(#build)
+            }
+        }
+    })
+}
@@ -0,0 +1,111 @@
+use crate::{ParserInput, Input};
+
+/**
+A limit on how much a parse may backtrack, set with [`ParserInput::set_backtrack_limit`] or the
+[`BacktrackLimited`](crate::combinators::BacktrackLimited) combinator. Backtracking is counted
+every time the string is [rewound](ParserInput::rewind) to an earlier position.
+```
+# use parsa::BacktrackLimit;
+let limit = BacktrackLimit::bytes(4096).events(100);
+assert_eq!(limit.max_bytes, Some(4096));
+assert_eq!(limit.max_events, Some(100));
+```
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct BacktrackLimit {
+    ///The maximum amount of bytes that may be rewound in total
+    pub max_bytes: Option<usize>,
+    ///The maximum amount of rewinds
+    pub max_events: Option<usize>,
+}
+
+impl BacktrackLimit {
+    ///A limit that never triggers.
+    pub const NONE: Self = Self { max_bytes: None, max_events: None };
+
+    ///Limit the total amount of rewound bytes.
+    pub const fn bytes(max: usize) -> Self {
+        Self { max_bytes: Some(max), ..Self::NONE }
+    }
+
+    ///Limit the amount of rewinds.
+    pub const fn events(self, max: usize) -> Self {
+        Self { max_events: Some(max), ..self }
+    }
+}
+
+//running totals for a string
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct Backtrack {
+    pub(crate) limit: BacktrackLimit,
+    pub(crate) bytes: usize,
+    pub(crate) events: usize,
+    //where the limit was exceeded, if it was
+    pub(crate) exceeded: Option<usize>,
+}
+
+impl Backtrack {
+    //records a rewind of `n` bytes, returning false if this exceeds the limit
+    pub(crate) fn record(&mut self, at: usize, n: usize) -> bool {
+        self.bytes += n;
+        self.events += 1;
+        let over = self.limit.max_bytes.is_some_and(|m| self.bytes > m)
+            || self.limit.max_events.is_some_and(|m| self.events > m);
+        if over && self.exceeded.is_none() {
+            self.exceeded = Some(at);
+        }
+        self.exceeded.is_none()
+    }
+}
+
+impl<I: ?Sized + Input> ParserInput<I> {
+    /**
+    Limit how much this string may be backtracked from now on, resetting the counters. Once the
+    limit is exceeded, nothing more can be consumed and the string can't be rewound, so the parse
+    winds down quickly instead of backtracking further, leaving a string that isn't
+    [empty](ParserInput::is_empty). Run the parser with
+    [`Parser::parse_limited`](crate::Parser::parse_limited) to fail with
+    [`LimitErr::Backtrack`](crate::LimitErr::Backtrack) when that happens, or use
+    [`BacktrackLimited`](crate::combinators::BacktrackLimited) to limit only part of a parser.
+    ```
+    # use parsa::{Parser, ParserString, BacktrackLimit, LimitErr};
+    # use parsa::builtins::take;
+    let mut input = ParserString::from("abc");
+    input.set_backtrack_limit(BacktrackLimit::NONE.events(1));
+
+    let cp = input.checkpoint();
+    let _ = input.take(2);
+    input.rewind(cp);
+    assert_eq!(input.backtracked(), (2, 1));
+    assert_eq!(input.backtrack_exceeded(), None);
+
+    let _ = input.take(1);
+    input.rewind(cp);
+    assert_eq!(input.backtrack_exceeded(), Some(1));
+    assert_eq!(input.start(), 1);
+
+    let mut input = ParserString::from("aad");
+    input.set_backtrack_limit(BacktrackLimit::bytes(3));
+    let p = take("aab").or(take("aac")).or(take("aad"));
+    assert_eq!(p.parse_limited(&mut input), Err(LimitErr::Backtrack { offset: 3 }));
+    ```
+    */
+    pub fn set_backtrack_limit(&mut self, limit: BacktrackLimit) {
+        self.backtrack = Backtrack { limit, ..Backtrack::default() };
+    }
+
+    ///Get the current backtrack limit.
+    pub fn backtrack_limit(&self) -> BacktrackLimit {
+        self.backtrack.limit
+    }
+
+    ///The amount of bytes rewound, and the amount of rewinds, since the limit was last set.
+    pub fn backtracked(&self) -> (usize, usize) {
+        (self.backtrack.bytes, self.backtrack.events)
+    }
+
+    ///Where the backtrack limit was exceeded, if it was.
+    pub fn backtrack_exceeded(&self) -> Option<usize> {
+        self.backtrack.exceeded
+    }
+}
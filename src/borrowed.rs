@@ -0,0 +1,105 @@
+use std::{fmt::Debug, ops::Range};
+
+use crate::{ParserInput, Input, Checkpoint};
+
+/**
+Text borrowed from a buffer that outlives the parse, so that parsers can return slices of it
+instead of copies. Unlike a [`ParserString`](crate::ParserString), which owns its text, slices
+taken from a [`ParserStr`] are tied to the buffer rather than to the input, so they can be kept in
+the output.
+```rust
+# use parsa::{Parser, ParserStr};
+# use parsa::builtins::borrowed::{word, whitespace};
+#[derive(Debug, PartialEq)]
+struct Assign<'a> { name: &'a str, value: &'a str }
+
+fn assign<'a>(s: &mut ParserStr<'a>) -> Result<Assign<'a>, parsa::builtins::WordErr> {
+    let name = word.after(whitespace).parse(s)?;
+    let _ = word.after(whitespace).parse(s)?;
+    let value = word.parse(s)?;
+    Ok(Assign { name, value })
+}
+
+let text = String::from("x = 10");
+let ast = assign.parse(&mut ParserStr::from(text.as_str())).unwrap();
+assert_eq!(ast, Assign { name: "x", value: "10" });
+```
+*/
+#[derive(Clone, Copy)]
+pub struct Borrowed<'a>(&'a str);
+
+impl Input for Borrowed<'_> {
+    fn size(&self) -> usize { self.0.size() }
+
+    fn is_boundary(&self, offset: usize) -> bool { self.0.is_boundary(offset) }
+
+    fn advance(&self, offset: usize, n: usize) -> Option<usize> { self.0.advance(offset, n) }
+
+    fn describe(&self, range: Range<usize>, max: usize) -> String { self.0.describe(range, max) }
+}
+
+///A shrinking-window over borrowed text. See [`Borrowed`].
+pub type ParserStr<'a> = ParserInput<Borrowed<'a>>;
+
+impl<'a> ParserStr<'a> {
+    ///Splits the string at `n` characters, shrinking it. Panics if `n` is larger than the
+    ///remaining slice.
+    ///```rust
+    ///# use parsa::ParserStr;
+    ///let text = String::from("abc123");
+    ///let mut input = ParserStr::from(text.as_str());
+    ///let front = input.take(3);
+    ///drop(input);
+    ///assert_eq!(front, "abc");
+    ///```
+    pub fn take(&mut self, n: usize) -> &'a str {
+        self.try_take(n).expect("took past the end of the input")
+    }
+
+    ///Splits the string at `n` characters, shrinking it. Returns [`None`] if `n` is larger than
+    ///the remaining slice.
+    pub fn try_take(&mut self, n: usize) -> Option<&'a str> {
+        let start = self.ptr;
+        let end = self.full.advance(start, n).filter(|&end| end <= self.end)?;
+        self.ptr = end;
+        Some(&self.full.0[start..end])
+    }
+
+    ///Rewinds the string slice `n` bytes. Panics if `n` is larger than the taken space.
+    ///# Safety
+    ///Caller must assure that the resulting pointer lands on a UTF-8 code point, as with
+    ///[`ParserString::give`](crate::ParserString::give).
+    pub unsafe fn give(&mut self, n: usize) {
+        self.ptr -= n;
+    }
+
+    ///Set the current start position manually.
+    ///# Safety
+    ///Caller must assure that the resulting pointer lands on a UTF-8 code point.
+    pub unsafe fn set_ptr(&mut self, ptr: usize) {
+        self.ptr = ptr;
+    }
+
+    ///Get the remaining text.
+    pub fn get(&self) -> &'a str {
+        &self.full.0[self.ptr..self.end]
+    }
+
+    ///Get the text consumed since `checkpoint` was taken. Panics if the string has been rewound
+    ///to before it.
+    pub fn since(&self, checkpoint: Checkpoint) -> &'a str {
+        &self.full.0[checkpoint.offset()..self.ptr]
+    }
+}
+
+impl<'a> From<&'a str> for ParserStr<'a> {
+    fn from(value: &'a str) -> Self {
+        Self::new(Box::new(Borrowed(value)))
+    }
+}
+
+impl Debug for ParserStr<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.get())
+    }
+}
@@ -1,6 +1,9 @@
 /*!
 Composable parsers for common actions.
 
+Most builtins parse text from a [`ParserString`]. Readers for binary data, like [`be_u16`], parse
+from a [`ParserBytes`](crate::ParserBytes).
+
 See the [error coercion rules](crate::combinators#error-coercion-rules) for error handling.
 */
 
@@ -10,7 +13,57 @@ use std::{convert::Infallible, str::FromStr};
 use thiserror::Error;
 use nevermore::FromNever;
 
-use crate::{ParserString, Parser};
+use crate::{ParserString, ParserInput, Input, Parser, Expectation, grammar::{Grammar, Builtin, Described}};
+
+mod url;
+pub use url::*;
+pub mod args;
+pub mod cst;
+pub mod borrowed;
+mod brackets;
+pub use brackets::*;
+mod bytes;
+pub use bytes::*;
+mod color;
+pub use color::*;
+mod columns;
+pub use columns::*;
+mod doc;
+pub use doc::*;
+#[cfg(feature = "unicode-segmentation")]
+mod grapheme;
+#[cfg(feature = "unicode-segmentation")]
+pub use grapheme::*;
+mod keyword;
+pub use keyword::*;
+mod layout;
+pub use layout::*;
+mod literal;
+pub use literal::*;
+mod path;
+pub use path::*;
+mod scan;
+pub use scan::*;
+mod template;
+pub use template::*;
+mod time;
+pub use time::*;
+#[cfg(feature = "unicode-classes")]
+mod unicode;
+#[cfg(feature = "unicode-classes")]
+pub use unicode::*;
+mod units;
+pub use units::*;
+mod version;
+pub use version::*;
+#[cfg(feature = "ini")]
+pub mod ini;
+#[cfg(feature = "toml")]
+pub mod toml;
+#[cfg(feature = "json")]
+pub mod json;
+#[cfg(feature = "access-log")]
+pub mod access_log;
 
 /**
 Returns the next character in the string, `Err(())` if the string is empty.
@@ -25,6 +78,7 @@ assert_eq!(next(&mut input), Ok('c'));
 assert_eq!(next(&mut input), Err(()));
 ```
 */
+#[allow(clippy::result_unit_err)]
 pub fn next(s: &mut ParserString) -> Result<char, ()> {
     s.try_take(1).ok_or(())?.chars().next().ok_or(())
 }
@@ -49,16 +103,16 @@ pub fn word(s: &mut ParserString) -> Result<String, WordErr> {
         if !c.is_whitespace() {
             out.push(c);
         } else {
-            unsafe { s.give(1) }
+            unsafe { s.give(c.len_utf8()) }
             break;
         }
     }
     
-    if out.len() == 0 { return Err(WordErr) }
+    if out.is_empty() { return Err(WordErr) }
     Ok(out)
 }
 ///Indicates that a [`word`] parser has failed.
-#[derive(Debug, Clone, Copy, Error, FromNever)]
+#[derive(Debug, Clone, Copy, Error, FromNever, PartialEq, Eq)]
 #[error("found no characters")]
 pub struct WordErr;
 
@@ -83,19 +137,55 @@ pub fn whitespace(s: &mut ParserString) -> Result<usize, Infallible> {
 
     while let Ok(c) = next.parse(s) { 
         if c != ' ' {
+            unsafe { s.give(c.len_utf8()) }
             break
         }
         ctr += 1 
     }
 
-    if !s.get().is_empty() {
-        unsafe { s.give(1) }
-    }
     Ok(ctr)
 }
 
 
+/**
+Succeeds with a clone of `value`, consuming nothing. This is the neutral element of
+[`Parser::chain`], as [`fail`] is of [`Parser::or`].
+```
+# use parsa::{Parser, ParserString};
+# use parsa::builtins::success;
+let mut input = ParserString::from("abc");
+assert_eq!(success(5).parse(&mut input), Ok(5));
+assert_eq!(input.get(), "abc");
+```
+*/
+pub fn success<T: Clone, I: ?Sized + Input>(value: T) -> impl Parser<T, I, Err = Infallible> {
+    Described::new(move |_: &mut ParserInput<I>| Ok(value.clone()), Grammar::Seq(vec![]))
+}
+
+/**
+Fails with a clone of `err`, consuming nothing. This is the neutral element of [`Parser::or`],
+which makes it a starting point for alternatives built at runtime. Its grammar is an empty
+[`Grammar::Alt`], which matches nothing, so it disappears from the alternatives built on it.
+```
+# use parsa::{Parser, ParserString};
+# use parsa::builtins::{fail, take, TakeErr};
+let keywords = ["let", "fn", "if"];
+let keyword = keywords.iter().fold(
+    fail(TakeErr::NoMatch { expected: "keyword", found: String::new() }).boxed(),
+    |p, &k| p.or(take(k)).boxed(),
+);
+
+assert_eq!(keyword.parse(&mut ParserString::from("fn")), Ok("fn"));
+assert_eq!(keyword.describe(), r#""let" | "fn" | "if""#);
+```
+*/
+pub fn fail<T, E: Clone, I: ?Sized + Input>(err: E) -> impl Parser<T, I, Err = E> {
+    Described::new(move |_: &mut ParserInput<I>| Err(err.clone()), Grammar::Alt(vec![]))
+}
+
 /**Take the delimiter from the front of the string.
+
+On failure, the [`TakeErr`] records the delimiter and the text that was found in its place.
 ```
 # use parsa::ParserString;
 # use parsa::Parser;
@@ -106,30 +196,135 @@ let head = take("ab").parse(&mut input);
 
 assert!(head.is_ok_and(|s| s == "ab"));
 assert_eq!(input.get(), "c 123");
+
+let err = take("=").parse(&mut input).unwrap_err();
+assert_eq!(err.to_string(), r#"expected "=", found "c""#);
 ```
 */
 pub fn take(delim: &'static str) -> impl Parser<&'static str, Err = TakeErr> {
-    move |s: &mut ParserString| {
-        let head = s.try_take(delim.len())
-            .ok_or(TakeErr::NoSpace)?;
+    Described::new(move |s: &mut ParserString| {
+        let n = delim.chars().count();
+        let start = s.start();
+        if s.get().chars().take(n).count() < n {
+            s.expect(start, Expectation::Literal(delim.to_owned()));
+            return Err(TakeErr::NoSpace { expected: delim, found: s.peek(n).to_owned() })
+        }
 
+        let Some(head) = s.try_take(n) else {
+            //longer than the output limit allows
+            return Err(TakeErr::NoSpace { expected: delim, found: s.peek(n).to_owned() })
+        };
         if head == delim {
             Ok(delim)
         } else {
-            Err(TakeErr::NoMatch)
+            let found = head.to_owned();
+            s.expect(start, Expectation::Literal(delim.to_owned()));
+            Err(TakeErr::NoMatch { expected: delim, found })
         }
-    }
+    }, Grammar::Literal(delim.to_owned()))
 }
 
-///Indicates that a [`take`] parser has failed.
-#[derive(Debug, Clone, Copy, Error, FromNever)]
+///Indicates that a [`take`] parser has failed. The text that was found is never longer than the
+///delimiter, so failing alternatives don't copy the rest of the input.
+#[derive(Debug, Clone, Error, FromNever, PartialEq, Eq)]
 pub enum TakeErr {
     ///Parser failed because the string ended
-    #[error("ran out of space")]
-    NoSpace,
+    #[error("expected {expected:?}, found end of input after {found:?}")]
+    NoSpace {
+        ///The delimiter that was expected
+        expected: &'static str,
+        ///What was left of the string, which is shorter than the delimiter
+        found: String,
+    },
     ///Parser failed because the captured slice didn't match the delimiter
-    #[error("did not match delim")]
-    NoMatch,
+    #[error("expected {expected:?}, found {found:?}")]
+    NoMatch {
+        ///The delimiter that was expected
+        expected: &'static str,
+        ///The slice that was captured instead, as many characters long as the delimiter
+        found: String,
+    },
+}
+
+impl TakeErr {
+    ///The delimiter that was expected.
+    pub fn expected(&self) -> &'static str {
+        match self {
+            Self::NoSpace { expected, .. } | Self::NoMatch { expected, .. } => expected,
+        }
+    }
+    ///The text that was found in place of the delimiter.
+    pub fn found(&self) -> &str {
+        match self {
+            Self::NoSpace { found, .. } | Self::NoMatch { found, .. } => found,
+        }
+    }
+}
+
+/**
+String literals are parsers, and behave the same as [`take`]ing them.
+```
+# use parsa::{Parser, ParserString};
+# use parsa::builtins::{word, int, BuiltinErr};
+let assign = word.convert_err::<BuiltinErr>().after(" = ").chain(int::<i32, _>);
+
+let mut input = ParserString::from("x = 10");
+assert_eq!(assign.parse(&mut input).unwrap(), ("x".to_owned(), 10));
+```
+*/
+impl Parser<&'static str> for &'static str {
+    type Err = TakeErr;
+
+    fn parse(&self, s: &mut ParserString) -> Result<&'static str, Self::Err> {
+        take(self).parse(s)
+    }
+
+    fn grammar(&self) -> Grammar {
+        Grammar::Literal((*self).to_owned())
+    }
+}
+
+/**
+Characters are parsers, which match themselves.
+```
+# use parsa::{Parser, ParserString};
+# use parsa::builtins::CharErr;
+let mut input = ParserString::from("(x");
+assert_eq!('('.parse(&mut input), Ok('('));
+assert_eq!(')'.parse(&mut input), Err(CharErr { expected: ')', found: Some('x') }));
+assert_eq!(input.get(), "x");
+```
+*/
+impl Parser<char> for char {
+    type Err = CharErr;
+
+    fn parse(&self, s: &mut ParserString) -> Result<char, Self::Err> {
+        let start = s.start();
+        match s.get().chars().next() {
+            Some(c) if c == *self => {
+                s.take(1);
+                Ok(c)
+            },
+            found => {
+                s.expect(start, Expectation::Literal(self.to_string()));
+                Err(CharErr { expected: *self, found })
+            },
+        }
+    }
+
+    fn grammar(&self) -> Grammar {
+        Grammar::Literal(self.to_string())
+    }
+}
+
+///Indicates that a [`char`] literal failed to match. Nothing is consumed on failure.
+#[derive(Debug, Clone, Copy, Error, FromNever, PartialEq, Eq)]
+#[error("expected {expected:?}, found {}", found.map_or("end of input".to_owned(), |c| format!("{c:?}")))]
+pub struct CharErr {
+    ///The character that was expected
+    pub expected: char,
+    ///The character found instead, or [`None`] at the end of input
+    pub found: Option<char>,
 }
 
 ///Indicates that an [`int`] parser has failed.
@@ -154,7 +349,7 @@ assert!(num.is_ok_and(|i| i == 123));
 ```
 */
 pub fn int<I, E>(s: &mut ParserString) -> Result<I, IntErr<E>> 
-where I: num_traits::PrimInt + FromStr<Err = E> + 'static, E: std::error::Error + 'static
+where I: num_traits::PrimInt + FromStr<Err = E>, E: std::error::Error
 {
     word
     .convert_err::<IntErr<E>>()
@@ -172,7 +367,7 @@ pub enum FloatErr<E: std::error::Error> {
     #[error("{0}")]
     Word(#[from] WordErr), 
     ///Parser failed from a [`FromStr`] error
-    #[error("error parsing int: {0}")]
+    #[error("error parsing float: {0}")]
     Parse(E)
 }
 /**Parses a [`word`] into a float.
@@ -187,7 +382,7 @@ assert!(num.is_ok_and(|i| i == 123.4));
 ```
 */
 pub fn float<I, E>(s: &mut ParserString) -> Result<I, FloatErr<E>> 
-where I: num_traits::Float + FromStr<Err = E> + 'static, E: std::error::Error + 'static
+where I: num_traits::Float + FromStr<Err = E>, E: std::error::Error
 {
     word
     .convert_err::<FloatErr<E>>()
@@ -199,7 +394,7 @@ where I: num_traits::Float + FromStr<Err = E> + 'static, E: std::error::Error +
 }
 
 ///Indicates that a [`between`] parser has failed.
-#[derive(Debug, Clone, Copy, Error, FromNever)]
+#[derive(Debug, Clone, Copy, Error, FromNever, PartialEq, Eq)]
 pub enum BetweenErr {
     ///Parser failed because the opener was not found
     #[error("opener was not found")] 
@@ -220,14 +415,359 @@ assert!(middle.is_ok_and(|s| s == "abc"));
 ```
 */
 pub fn between(open: &'static str, close: &'static str) -> impl Parser<String, Err = BetweenErr> {
-    move |s: &mut ParserString| {
+    between_with(open, close, Delimiter::Consume)
+}
+
+/**Like [`between`], but `policy` decides whether the closer is consumed.
+```
+# use parsa::{Parser, ParserString};
+# use parsa::builtins::{between_with, Delimiter};
+let mut input = ParserString::from("[abc]");
+assert_eq!(between_with("[", "]", Delimiter::Keep).parse(&mut input), Ok("abc".to_owned()));
+assert_eq!(input.get(), "]");
+```
+*/
+pub fn between_with(open: &'static str, close: &'static str, policy: Delimiter) -> impl Parser<String, Err = BetweenErr> {
+    let grammar = Grammar::Literal(open.to_owned())
+        .seq(policy.grammar(Grammar::Builtin(Builtin::Text), Grammar::Literal(close.to_owned())));
+    Described::new(move |s: &mut ParserString| {
         let _ = take(open).map_err(|_| BetweenErr::NoOpen).parse(s)?;
         let mut out = String::with_capacity(s.len());
         
-        while take(close).try_parse(s).is_err() {
+        loop {
+            let cp = s.checkpoint();
+            if take(close).try_parse(s).is_ok() {
+                if policy == Delimiter::Keep {
+                    s.rewind(cp);
+                }
+                break
+            }
             out.push(next(s).map_err(|_| BetweenErr::Unmatched)?);
         }
 
         Ok(out)
+    }, grammar)
+}
+
+/**
+Parses a raw string literal: `prefix`, any number of `fence` characters, and a `"`, then text up
+to a `"` followed by the same number of fences. Escapes aren't processed, so the text may contain
+`\` freely, and quotes when fenced. With `prefix` `"r"` and `fence` `'#'`, this matches Rust's
+`r"..."` and `r#"..."#`. Nothing is consumed on failure.
+```
+# use parsa::{Parser, ParserString};
+# use parsa::builtins::{raw_string, RawStringErr};
+let raw = raw_string("r", '#');
+assert_eq!(raw.parse(&mut ParserString::from(r#"r"\d+""#)), Ok(r"\d+".to_owned()));
+assert_eq!(raw.parse(&mut ParserString::from(r##"r#"say "hi""#"##)), Ok(r#"say "hi""#.to_owned()));
+assert_eq!(raw.parse(&mut ParserString::from(r##"r##"open"#"##)), Err(RawStringErr::Unterminated { fences: 2 }));
+assert_eq!(raw.parse(&mut ParserString::from(r#""plain""#)), Err(RawStringErr::NoOpen));
+```
+*/
+pub fn raw_string(prefix: &'static str, fence: char) -> impl Parser<String, Err = RawStringErr> {
+    let grammar = Grammar::Literal(prefix.to_owned()).seq(Grammar::named("raw string", Grammar::Opaque));
+    Described::new(move |s: &mut ParserString| {
+        let Some(rest) = s.get().strip_prefix(prefix) else { return Err(RawStringErr::NoOpen) };
+        let fences = rest.chars().take_while(|&c| c == fence).count();
+        let Some(body) = rest[fences * fence.len_utf8()..].strip_prefix('"') else {
+            return Err(RawStringErr::NoOpen)
+        };
+        let close = format!("\"{}", fence.to_string().repeat(fences));
+        let Some(end) = body.find(&close) else {
+            s.expect(s.start() + s.len(), Expectation::Literal(close));
+            return Err(RawStringErr::Unterminated { fences })
+        };
+        let out = body[..end].to_owned();
+        let len = s.len() - body.len() + end + close.len();
+        unsafe { s.set_ptr(s.start() + len) }
+        Ok(out)
+    }, grammar)
+}
+
+///Indicates that a [`raw_string`] parser has failed.
+#[derive(Debug, Clone, Copy, Error, FromNever, PartialEq, Eq)]
+pub enum RawStringErr {
+    ///Parser failed because the input didn't start with the prefix, fences and a quote
+    #[error("expected a raw string")]
+    NoOpen,
+    ///Parser failed because the closing quote and fences were never found
+    #[error("unterminated raw string with {fences} fences")]
+    Unterminated {
+        ///How many fences the string was opened with
+        fences: usize,
+    },
+}
+
+///The kind of failure behind a [`BuiltinErr`]. Every builtin error type converts into this.
+#[derive(Debug, Clone, Error, PartialEq, Eq)]
+pub enum ErrKind {
+    ///A [`next`] parser ran out of input
+    #[error("unexpected end of input")]
+    Eof,
+    ///A [`word`] parser failed
+    #[error("{0}")]
+    Word(#[from] WordErr),
+    ///A [`take`] parser or string literal failed
+    #[error("{0}")]
+    Take(#[from] TakeErr),
+    ///A [`char`] literal failed
+    #[error("{0}")]
+    Char(#[from] CharErr),
+    ///A [`take_until`] parser failed
+    #[error("{0}")]
+    TakeUntil(#[from] TakeUntilErr),
+    ///A [`line`] parser failed
+    #[error("{0}")]
+    Line(#[from] LineErr),
+    ///A [`next_grapheme`] parser failed
+    #[cfg(feature = "unicode-segmentation")]
+    #[error("{0}")]
+    Grapheme(#[from] GraphemeErr),
+    ///A [`char_class`] or [`xid_identifier`] parser failed
+    #[cfg(feature = "unicode-classes")]
+    #[error("{0}")]
+    CharClass(#[from] CharClassErr),
+    ///A [`newline`] parser failed
+    #[error("{0}")]
+    Newline(#[from] NewlineErr),
+    ///A [`remaining`] parser failed
+    #[error("{0}")]
+    Remaining(#[from] RemainingErr),
+    ///A [`take_while_m_n`] parser failed
+    #[error("{0}")]
+    TakeWhile(#[from] TakeWhileErr),
+    ///A [`find`] parser failed
+    #[error("{0}")]
+    Find(#[from] FindErr),
+    ///A [`template`] parser failed
+    #[error("{0}")]
+    Template(#[from] TemplateErr),
+    ///A [`between`] parser failed
+    #[error("{0}")]
+    Between(#[from] BetweenErr),
+    ///A [`quantity`] or [`quantity_in`] parser failed
+    #[error("{0}")]
+    Quantity(#[from] QuantityErr),
+    ///A [`duration`] parser failed
+    #[error("{0}")]
+    Duration(#[from] DurationErr),
+    ///A [`hex_color`] parser failed
+    #[error("{0}")]
+    Color(#[from] ColorErr),
+    ///A [`doc_comment`] or [`attribute`] parser failed
+    #[error("{0}")]
+    Doc(#[from] DocErr),
+    ///An [`ident_except`] parser failed
+    #[error("{0}")]
+    Ident(#[from] IdentErr),
+    ///A [`keyword_of`] parser failed
+    #[error("{0}")]
+    Keyword(#[from] KeywordErr),
+    ///An [`operators`] parser failed
+    #[error("{0}")]
+    Operator(#[from] OperatorErr),
+    ///A [`BracketTracker`] parser failed
+    #[error("{0}")]
+    Bracket(#[from] BracketErr),
+    ///A [`path`] parser failed
+    #[error("{0}")]
+    Path(#[from] PathErr),
+    ///A [`glob`] parser failed
+    #[error("{0}")]
+    Glob(#[from] GlobErr),
+    ///A [`version`] or [`requirement`] parser failed
+    #[error("{0}")]
+    Version(#[from] VersionErr),
+    ///A [`raw_string`] parser failed
+    #[error("{0}")]
+    RawString(#[from] RawStringErr),
+    ///A [`number_literal`] parser failed
+    #[error("{0}")]
+    NumberLiteral(#[from] NumberLiteralErr),
+    ///A [`field`] parser failed
+    #[error("{0}")]
+    Field(#[from] FieldErr),
+    ///A timestamp parser like [`syslog_time`] failed
+    #[error("{0}")]
+    Time(#[from] TimeErr),
+    ///An [`int`] parser failed, with the message of its [`IntErr`]
+    #[error("{0}")]
+    Int(String),
+    ///A [`float`] parser failed, with the message of its [`FloatErr`]
+    #[error("{0}")]
+    Float(String),
+    ///A [`url`] parser failed
+    #[error("{0}")]
+    Url(#[from] UrlErr),
+    ///A fixed-width reader like [`be_u16`] ran out of input
+    #[error("{0}")]
+    Read(#[from] ReadErr),
+    ///A [`varint`] or [`sleb128`] parser failed
+    #[error("{0}")]
+    Varint(#[from] VarintErr),
+    ///A [`cstr`] or [`fixed_str`] parser failed
+    #[error("{0}")]
+    Str(#[from] StrErr),
+    ///A [`magic`] parser failed
+    #[error("{0}")]
+    Magic(#[from] MagicErr),
+}
+
+impl From<()> for ErrKind {
+    fn from(_: ()) -> Self { Self::Eof }
+}
+impl<E: std::error::Error> From<IntErr<E>> for ErrKind {
+    fn from(e: IntErr<E>) -> Self { Self::Int(e.to_string()) }
+}
+impl<E: std::error::Error> From<FloatErr<E>> for ErrKind {
+    fn from(e: FloatErr<E>) -> Self { Self::Float(e.to_string()) }
+}
+
+/**
+A single error type that every builtin error can be coerced into, so builtins can be sequenced
+without writing an aggregate error type first.
+
+Plain conversions don't know where the failure happened, so `offset` is [`None`]. Wrap a parser in
+[`located`] to record the offset it started at.
+```
+# use parsa::ParserString;
+# use parsa::Parser;
+# use parsa::builtins::*;
+let mut input = ParserString::from("val = 123");
+let (name, val) = word.convert_err::<BuiltinErr>()
+    .after(whitespace)
+    .after(take("=").after(whitespace))
+    .chain(int::<i32, _>)
+    .parse(&mut input)
+    .unwrap();
+assert_eq!((name.as_str(), val), ("val", 123));
+
+let mut input = ParserString::from("val : 123");
+let err = word.convert_err::<BuiltinErr>()
+    .after(whitespace)
+    .after(located(take("=")))
+    .parse(&mut input)
+    .unwrap_err();
+assert!(matches!(err.kind, ErrKind::Take(TakeErr::NoMatch { expected: "=", .. })));
+assert_eq!(err.offset, Some(4));
+```
+*/
+#[derive(Debug, Clone, PartialEq, Eq, FromNever)]
+pub struct BuiltinErr {
+    ///What went wrong
+    pub kind: ErrKind,
+    ///Where the failing parser started, if known
+    pub offset: Option<usize>,
+}
+
+impl std::fmt::Display for BuiltinErr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.offset {
+            Some(offset) => write!(f, "{} at offset {offset}", self.kind),
+            None => write!(f, "{}", self.kind),
+        }
+    }
+}
+impl std::error::Error for BuiltinErr {}
+
+impl<E: Into<ErrKind>> From<E> for BuiltinErr {
+    fn from(value: E) -> Self {
+        Self { kind: value.into(), offset: None }
+    }
+}
+
+/**Converts the error of a parser into a [`BuiltinErr`], recording the offset the parser started at.
+```
+# use parsa::ParserString;
+# use parsa::Parser;
+# use parsa::builtins::{located, word};
+let mut input = ParserString::from("abc ");
+let _ = word(&mut input);
+let err = located(word).parse(&mut input).unwrap_err();
+assert_eq!(err.offset, Some(3));
+```
+*/
+pub fn located<T, P>(p: P) -> impl Parser<T, Err = BuiltinErr> 
+where P: Parser<T>, P::Err: Into<ErrKind>
+{
+    move |s: &mut ParserString| {
+        let start = s.start();
+        p.parse(s).map_err(|e| BuiltinErr { kind: e.into(), offset: Some(start) })
+    }
+}
+
+/**
+Reads a length with `len`, then runs `item` over exactly that many items of what follows:
+characters for text, and bytes for binary data. Fails if there isn't enough input left, or if
+`item` doesn't consume the whole region. Errors are coerced like [`Chain`](crate::combinators::Chain).
+```
+# use parsa::{Parser, ParserBytes, ParserString};
+# use parsa::builtins::*;
+let mut input = ParserBytes::from(&[4, 1, 0, 2, 0, 9]);
+let items = length_prefixed(u8, le_u16.many()).parse(&mut input);
+assert_eq!(items, Ok(vec![1, 2]));
+assert_eq!(input.get(), &[9]);
+
+let field = length_prefixed(int::<usize, _>.after(whitespace), word);
+let mut input = ParserString::from("3 abcdef");
+assert!(field.parse(&mut input).is_ok_and(|w| w == "abc"));
+
+let mut input = ParserString::from("5 ab cd");
+assert!(matches!(field.parse(&mut input), Err(LengthErr::Unconsumed { offset: 4, left: 3 })));
+```
+*/
+pub fn length_prefixed<N, T, E, I, L, P>(len: L, item: P) -> impl Parser<T, I, Err = LengthErr<L::Err>>
+where
+    I: ?Sized + Input,
+    L: Parser<N, I>,
+    N: TryInto<usize>,
+    P: Parser<T, I, Err = E>,
+    E: Into<L::Err>,
+{
+    let grammar = len.grammar().seq(item.grammar());
+    Described::new(move |s: &mut ParserInput<I>| {
+        let n = len.parse(s).map_err(LengthErr::Inner)?;
+        let n = n.try_into().unwrap_or(usize::MAX);
+
+        let offset = s.start();
+        let end = s.region_end(n).ok_or(LengthErr::TooLong { len: n, offset })?;
+
+        let v = s.bounded(end, |s| item.parse(s)).map_err(|e| LengthErr::Inner(e.into()))?;
+        if s.start() != end {
+            return Err(LengthErr::Unconsumed { offset: s.start(), left: end - s.start() })
+        }
+        Ok(v)
+    }, grammar)
+}
+
+///Indicates that a [`length_prefixed`] parser has failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, FromNever)]
+pub enum LengthErr<E> {
+    ///The length or item parser failed
+    Inner(E),
+    ///The length was longer than the remaining input
+    TooLong {
+        ///The length that was read
+        len: usize,
+        ///Where the region would have started
+        offset: usize,
+    },
+    ///The item parser didn't consume the whole region
+    Unconsumed {
+        ///Where the item parser stopped
+        offset: usize,
+        ///How much of the region was left, in the units of the input
+        left: usize,
+    },
+}
+
+impl<E: std::fmt::Display> std::fmt::Display for LengthErr<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Inner(e) => write!(f, "{e}"),
+            Self::TooLong { len, offset } => write!(f, "length {len} at offset {offset} is longer than the remaining input"),
+            Self::Unconsumed { offset, left } => write!(f, "{left} unconsumed at offset {offset}"),
+        }
     }
 }
+impl<E: std::error::Error> std::error::Error for LengthErr<E> {}
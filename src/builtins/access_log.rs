@@ -0,0 +1,179 @@
+/*!
+Parsers for web server access logs in the Common and Combined Log Formats, as written by Apache
+and nginx. Fields that are logged as `-` are [`None`].
+
+Requires the `access-log` feature.
+```
+# use parsa::ParserString;
+# use parsa::builtins::access_log::entry;
+let line = r#"127.0.0.1 - frank [10/Oct/2000:13:55:36 -0700] "GET /apache_pb.gif HTTP/1.0" 200 2326 "http://www.example.com/start.html" "Mozilla/4.08 [en] (Win98; I ;Nav)""#;
+let entry = entry(&mut ParserString::from(line)).unwrap();
+assert_eq!(entry.host, "127.0.0.1");
+assert_eq!(entry.user.as_deref(), Some("frank"));
+assert_eq!(entry.time.unix(), Some(971211336));
+assert_eq!(entry.request_line(), Some(("GET", "/apache_pb.gif", Some("HTTP/1.0"))));
+assert_eq!((entry.status, entry.bytes), (200, Some(2326)));
+assert_eq!(entry.user_agent.as_deref(), Some("Mozilla/4.08 [en] (Win98; I ;Nav)"));
+```
+Whole log files can be read a line at a time with [`Records`](crate::stream::Records):
+```no_run
+# use parsa::stream::Records;
+# use parsa::builtins::access_log::entry;
+for entry in Records::open("access.log", entry, "\n")? {
+    println!("{}", entry?.status);
+}
+# Ok::<(), Box<dyn std::error::Error>>(())
+```
+*/
+use thiserror::Error;
+use nevermore::FromNever;
+
+use crate::ParserString;
+use super::{apache_time, Timestamp};
+
+///A single access log line. See [`entry`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    ///The remote host or address
+    pub host: String,
+    ///The identity reported by identd, which is almost always missing
+    pub ident: Option<String>,
+    ///The authenticated user
+    pub user: Option<String>,
+    ///When the request was received
+    pub time: Timestamp,
+    ///The raw request line, with escapes resolved. See [`Entry::request_line`].
+    pub request: String,
+    ///The response status code
+    pub status: u16,
+    ///The size of the response body
+    pub bytes: Option<u64>,
+    ///The `Referer` header, only present in the Combined Log Format
+    pub referer: Option<String>,
+    ///The `User-Agent` header, only present in the Combined Log Format
+    pub user_agent: Option<String>,
+}
+
+impl Entry {
+    ///Split the request line into its method, target and protocol, or [`None`] if it isn't
+    ///shaped like one. Clients can send anything, so this is kept separate from parsing.
+    ///```
+    ///# use parsa::ParserString;
+    ///# use parsa::builtins::access_log::entry;
+    ///let line = r#"10.0.0.1 - - [10/Oct/2000:13:55:36 +0000] "\x16\x03\x01" 400 0"#;
+    ///let entry = entry(&mut ParserString::from(line)).unwrap();
+    ///assert_eq!(entry.request, r"\x16\x03\x01");
+    ///assert_eq!(entry.request_line(), None);
+    ///```
+    pub fn request_line(&self) -> Option<(&str, &str, Option<&str>)> {
+        let mut parts = self.request.split(' ');
+        let method = parts.next().filter(|m| !m.is_empty() && m.bytes().all(|b| b.is_ascii_uppercase()))?;
+        let target = parts.next().filter(|t| !t.is_empty())?;
+        let protocol = parts.next();
+        parts.next().is_none().then_some((method, target, protocol))
+    }
+}
+
+///Indicates that an access log parser has failed. Nothing is consumed on failure.
+#[derive(Debug, Clone, Copy, Error, FromNever, PartialEq, Eq)]
+#[error("invalid {field} at offset {offset}")]
+pub struct AccessLogErr {
+    ///The name of the field that couldn't be parsed
+    pub field: &'static str,
+    ///Where the field started
+    pub offset: usize,
+}
+
+//a space separated field, or None for `-`
+fn field(s: &mut ParserString, name: &'static str) -> Result<Option<String>, AccessLogErr> {
+    let len = s.get().find([' ', '\n', '\r']).unwrap_or(s.len());
+    if len == 0 {
+        return Err(AccessLogErr { field: name, offset: s.start() })
+    }
+    let text = s.get()[..len].to_owned();
+    unsafe { s.set_ptr(s.start() + len) }
+    Ok((text != "-").then_some(text))
+}
+
+//a double quoted field, or None for `"-"`. `\"` and `\\` are unescaped and other escapes are
+//kept as written.
+fn quoted(s: &mut ParserString, name: &'static str) -> Result<Option<String>, AccessLogErr> {
+    let err = AccessLogErr { field: name, offset: s.start() };
+    let text = s.get().strip_prefix('"').ok_or(err)?;
+    let mut out = String::new();
+    let mut chars = text.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '"' => {
+                unsafe { s.set_ptr(s.start() + i + 2) }
+                return Ok((out != "-").then_some(out))
+            },
+            '\\' => match chars.next() {
+                Some((_, e @ ('"' | '\\'))) => out.push(e),
+                Some((_, e)) => { out.push('\\'); out.push(e) },
+                None => break,
+            },
+            '\n' => break,
+            c => out.push(c),
+        }
+    }
+    Err(err)
+}
+
+fn space(s: &mut ParserString, name: &'static str) -> Result<(), AccessLogErr> {
+    match s.get().strip_prefix(' ') {
+        Some(_) => { s.take(1); Ok(()) },
+        None => Err(AccessLogErr { field: name, offset: s.start() }),
+    }
+}
+
+fn fields(s: &mut ParserString) -> Result<Entry, AccessLogErr> {
+    let host = field(s, "host")?.ok_or(AccessLogErr { field: "host", offset: s.start() })?;
+    space(s, "ident")?;
+    let ident = field(s, "ident")?;
+    space(s, "user")?;
+    let user = field(s, "user")?;
+    space(s, "time")?;
+    let time = apache_time(s).map_err(|e| AccessLogErr { field: "time", offset: e.offset })?;
+    space(s, "request")?;
+    let request = quoted(s, "request")?.unwrap_or_default();
+    space(s, "status")?;
+    let offset = s.start();
+    let status = field(s, "status")?.and_then(|n| n.parse().ok())
+        .filter(|n| (100..1000).contains(n))
+        .ok_or(AccessLogErr { field: "status", offset })?;
+    space(s, "bytes")?;
+    let offset = s.start();
+    let bytes = field(s, "bytes")?.map(|n| n.parse()).transpose()
+        .map_err(|_| AccessLogErr { field: "bytes", offset })?;
+
+    let mut entry = Entry { host, ident, user, time, request, status, bytes, referer: None, user_agent: None };
+    if s.get().starts_with(" \"") {
+        space(s, "referer")?;
+        entry.referer = quoted(s, "referer")?;
+        space(s, "user agent")?;
+        entry.user_agent = quoted(s, "user agent")?;
+    }
+    Ok(entry)
+}
+
+/**
+Parses one line in the Common or Combined Log Format, stopping before the line ending. See the
+[module docs](self).
+```
+# use parsa::ParserString;
+# use parsa::builtins::access_log::{entry, AccessLogErr};
+let mut input = ParserString::from("host - - [10/Oct/2000:13:55:36 +0000] \"GET / HTTP/1.1\" 304 -\nnext");
+let common = entry(&mut input).unwrap();
+assert_eq!((common.bytes, common.referer), (None, None));
+assert_eq!(input.get(), "\nnext");
+
+let mut input = ParserString::from("host - - [10/Oct/2000:13:55:36 +0000] \"GET /\" OK 0");
+assert_eq!(entry(&mut input), Err(AccessLogErr { field: "status", offset: 46 }));
+assert_eq!(input.start(), 0);
+```
+*/
+pub fn entry(s: &mut ParserString) -> Result<Entry, AccessLogErr> {
+    let cp = s.checkpoint();
+    fields(s).inspect_err(|_| s.rewind(cp))
+}
@@ -0,0 +1,161 @@
+/*!
+Parsers for command lines: `-f` short flags, `--flag` long flags, `--key=value` options, and
+positional arguments, split on whitespace. Words can be quoted with `"` or `'` to include
+whitespace, and `\` escapes the next character outside of single quotes. Everything after a `--`
+word is positional.
+```
+# use parsa::ParserString;
+# use parsa::builtins::args::{args, Arg};
+let line = r#"open -rv --mode=fast "my file.txt" -- -not-a-flag"#;
+assert_eq!(args(&mut ParserString::from(line)), Ok(vec![
+    Arg::Positional("open".into()),
+    Arg::Short('r'),
+    Arg::Short('v'),
+    Arg::Option { key: "mode".into(), value: "fast".into() },
+    Arg::Positional("my file.txt".into()),
+    Arg::Positional("-not-a-flag".into()),
+]));
+```
+*/
+use thiserror::Error;
+use nevermore::FromNever;
+
+use crate::ParserString;
+
+///A single command line argument. See [`args`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Arg {
+    ///A short flag, like `-v`. Bundled flags like `-rv` produce one of these per character.
+    Short(char),
+    ///A long flag, like `--verbose`
+    Long(String),
+    ///A long option with a value, like `--mode=fast`
+    Option {
+        ///The name of the option
+        key: String,
+        ///The value after the `=`, which may be empty
+        value: String,
+    },
+    ///Anything else, including everything after `--`
+    Positional(String),
+}
+
+///Indicates that a command line parser has failed.
+#[derive(Debug, Clone, Copy, Error, FromNever, PartialEq, Eq)]
+pub enum ArgsErr {
+    ///Parser failed because the input was empty or only whitespace
+    #[error("expected an argument at offset {offset}")]
+    Empty {
+        ///Where the argument was expected
+        offset: usize,
+    },
+    ///Parser failed because a quote was never closed
+    #[error("unterminated quote at offset {offset}")]
+    Unterminated {
+        ///Where the quote started
+        offset: usize,
+    },
+}
+
+/**
+Parses a single word, resolving quotes and escapes, and skipping whitespace before it. Fails with
+[`ArgsErr::Empty`] if there are no words left.
+```
+# use parsa::ParserString;
+# use parsa::builtins::args::word;
+let mut input = ParserString::from(r#"  say'ing "hi there"' a\ b"#);
+assert_eq!(word(&mut input), Ok("saying \"hi there\"".to_owned()));
+assert_eq!(word(&mut input), Ok("a b".to_owned()));
+assert!(word(&mut input).is_err());
+```
+*/
+pub fn word(s: &mut ParserString) -> Result<String, ArgsErr> {
+    let n = s.get().chars().take_while(|c| c.is_whitespace()).count();
+    s.take(n);
+    if s.get().is_empty() {
+        return Err(ArgsErr::Empty { offset: s.start() })
+    }
+
+    let mut out = String::new();
+    let mut quote: Option<(char, usize)> = None;
+    loop {
+        let offset = s.start();
+        let Some(c) = s.get().chars().next() else { break };
+        match (c, quote) {
+            (c, None) if c.is_whitespace() => break,
+            ('\'' | '"', None) => quote = Some((c, offset)),
+            (c, Some((q, _))) if c == q => quote = None,
+            ('\\', q) if q.is_none_or(|(q, _)| q == '"') => {
+                s.take(1);
+                match s.get().chars().next() {
+                    Some(c) => out.push(c),
+                    None => out.push('\\'),
+                }
+                s.try_take(1);
+                continue
+            },
+            (c, _) => out.push(c),
+        }
+        s.take(1);
+    }
+
+    match quote {
+        Some((_, offset)) => Err(ArgsErr::Unterminated { offset }),
+        None => Ok(out),
+    }
+}
+
+/**
+Parses a single word into the arguments it stands for. A lone `-` is positional, since it
+usually names standard input. `--` is returned as a [`Positional`](Arg::Positional) too; [`args`]
+is what gives it meaning.
+```
+# use parsa::ParserString;
+# use parsa::builtins::args::{arg, Arg};
+assert_eq!(arg(&mut ParserString::from("--out=")), Ok(vec![
+    Arg::Option { key: "out".into(), value: "".into() },
+]));
+assert_eq!(arg(&mut ParserString::from("-")), Ok(vec![Arg::Positional("-".into())]));
+```
+*/
+pub fn arg(s: &mut ParserString) -> Result<Vec<Arg>, ArgsErr> {
+    let word = word(s)?;
+    if word == "--" || word == "-" {
+        return Ok(vec![Arg::Positional(word)])
+    }
+    if let Some(long) = word.strip_prefix("--") {
+        return Ok(vec![match long.split_once('=') {
+            Some((key, value)) => Arg::Option { key: key.to_owned(), value: value.to_owned() },
+            None => Arg::Long(long.to_owned()),
+        }])
+    }
+    match word.strip_prefix('-') {
+        Some(shorts) => Ok(shorts.chars().map(Arg::Short).collect()),
+        None => Ok(vec![Arg::Positional(word)]),
+    }
+}
+
+/**
+Parses a whole command line. See the [module docs](self). An empty line gives an empty list.
+*/
+pub fn args(s: &mut ParserString) -> Result<Vec<Arg>, ArgsErr> {
+    let mut out = vec![];
+    loop {
+        let parsed = match arg(s) {
+            Ok(parsed) => parsed,
+            Err(ArgsErr::Empty { .. }) => return Ok(out),
+            Err(e) => return Err(e),
+        };
+        if parsed == [Arg::Positional("--".into())] {
+            break
+        }
+        out.extend(parsed);
+    }
+    loop {
+        match word(s) {
+            Ok(word) => out.push(Arg::Positional(word)),
+            Err(ArgsErr::Empty { .. }) => return Ok(out),
+            Err(e) => return Err(e),
+        }
+    }
+}
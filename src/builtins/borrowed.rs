@@ -0,0 +1,58 @@
+/*!
+Builtins over [`ParserStr`], which return slices of the borrowed text instead of owned
+[`String`]s. They behave the same as their namesakes in [`builtins`](super).
+*/
+use std::convert::Infallible;
+
+use crate::{Parser, ParserStr};
+
+pub use super::WordErr;
+
+/**
+Returns the next string of characters up until whitespace, returning [`WordErr`] if the next
+character is whitespace.
+```
+# use parsa::ParserStr;
+# use parsa::builtins::borrowed::word;
+let mut input = ParserStr::from("abc 123");
+assert_eq!(word(&mut input), Ok("abc"));
+```
+*/
+pub fn word<'a>(s: &mut ParserStr<'a>) -> Result<&'a str, WordErr> {
+    let out = take_while(|c| !c.is_whitespace()).parse(s).unwrap_or_else(|e| match e {});
+    if out.is_empty() { return Err(WordErr) }
+    Ok(out)
+}
+
+/**
+Takes characters for as long as `pred` holds. This never fails, and may return an empty slice.
+```
+# use parsa::{Parser, ParserStr};
+# use parsa::builtins::borrowed::take_while;
+let mut input = ParserStr::from("123abc");
+assert_eq!(take_while(|c| c.is_ascii_digit()).parse(&mut input), Ok("123"));
+assert_eq!(input.get(), "abc");
+```
+*/
+pub fn take_while<'a>(pred: impl Fn(char) -> bool) -> impl Fn(&mut ParserStr<'a>) -> Result<&'a str, Infallible> {
+    move |s: &mut ParserStr<'a>| {
+        let len = s.get().find(|c| !pred(c)).unwrap_or(s.get().len());
+        let cp = s.checkpoint();
+        unsafe { s.set_ptr(cp.offset() + len) }
+        Ok(s.since(cp))
+    }
+}
+
+/**
+Removes leading spaces, returning the amount. This function can never fail.
+```
+# use parsa::ParserStr;
+# use parsa::builtins::borrowed::whitespace;
+let mut input = ParserStr::from("    abc");
+assert_eq!(whitespace(&mut input), Ok(4));
+assert_eq!(input.get(), "abc");
+```
+*/
+pub fn whitespace(s: &mut ParserStr) -> Result<usize, Infallible> {
+    take_while(|c| c == ' ').parse(s).map(str::len)
+}
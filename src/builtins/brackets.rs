@@ -0,0 +1,179 @@
+use std::{cell::RefCell, ops::Range};
+
+use thiserror::Error;
+use nevermore::FromNever;
+
+use crate::{Parser, ParserString, Expectation, grammar::{Grammar, Described}};
+
+/**
+Tracks which brackets are open while parsing, so that a missing or mismatched closer can point
+back at the opener it belongs to. Brackets are consumed with [`open`](BracketTracker::open) and
+[`close`](BracketTracker::close), and [`finish`](BracketTracker::finish) checks that none are
+left open at the end.
+
+Openers that the input is rewound past, such as by a failed [`or`](Parser::or) branch, are
+forgotten automatically.
+```
+# use parsa::{Parser, ParserString};
+# use parsa::builtins::{BracketTracker, BracketErr};
+let brackets = BracketTracker::new(&[('(', ')'), ('{', '}')]);
+let body = |s: &mut ParserString| {
+    loop {
+        if brackets.open().try_parse(s).is_ok() {
+            continue
+        }
+        match brackets.close().parse(s) {
+            Ok(_) => (),
+            Err(BracketErr::NotBracket) => break,
+            Err(e) => return Err(e),
+        }
+    }
+    brackets.finish().parse(s)
+};
+
+assert_eq!(body(&mut ParserString::from("{()}")), Ok(()));
+
+let err = body(&mut ParserString::from("{()")).unwrap_err();
+assert_eq!(err, BracketErr::Unclosed { opener: '{', expected: '}', opened: 0..1 });
+assert_eq!(err.to_string(), "missing `}` for the `{` opened at offset 0");
+
+let err = body(&mut ParserString::from("{(}")).unwrap_err();
+assert!(matches!(err, BracketErr::Mismatched { expected: ')', found: '}', opened, .. } if opened == (1..2)));
+```
+*/
+#[derive(Debug, Clone, Default)]
+pub struct BracketTracker {
+    pairs: Vec<(char, char)>,
+    //opener, offset
+    open: RefCell<Vec<(char, usize)>>,
+}
+
+impl BracketTracker {
+    ///Constructs a tracker for the given `(opener, closer)` pairs.
+    pub fn new(pairs: &[(char, char)]) -> Self {
+        Self { pairs: pairs.to_vec(), open: RefCell::default() }
+    }
+
+    ///The amount of brackets currently open.
+    pub fn depth(&self) -> usize {
+        self.open.borrow().len()
+    }
+
+    ///Forgets every open bracket.
+    pub fn clear(&mut self) {
+        self.open.get_mut().clear();
+    }
+
+    fn closer(&self, opener: char) -> char {
+        self.pairs.iter().find(|(o, _)| *o == opener).map(|(_, c)| *c).expect("opener from this tracker")
+    }
+
+    //drops openers at or after `offset`, which the input has been rewound past
+    fn forget_from(&self, offset: usize) {
+        self.open.borrow_mut().retain(|&(_, at)| at < offset);
+    }
+
+    ///Parses any opener, remembering where it was. Fails with [`BracketErr::NotBracket`] if the
+    ///next character isn't an opener.
+    pub fn open(&self) -> impl Parser<char, Err = BracketErr> + '_ {
+        let grammar = Grammar::Alt(self.pairs.iter().map(|(o, _)| Grammar::Literal(o.to_string())).collect());
+        Described::new(move |s: &mut ParserString| {
+            let offset = s.start();
+            self.forget_from(offset);
+            let Some(&(opener, _)) = self.pairs.iter().find(|(o, _)| s.get().starts_with(*o)) else {
+                for (o, _) in &self.pairs {
+                    s.expect(offset, Expectation::Literal(o.to_string()));
+                }
+                return Err(BracketErr::NotBracket)
+            };
+            s.take(1);
+            self.open.borrow_mut().push((opener, offset));
+            Ok(opener)
+        }, grammar)
+    }
+
+    ///Parses the closer of the innermost open bracket. Fails with
+    ///[`BracketErr::Mismatched`] if a different closer is found, with
+    ///[`BracketErr::UnmatchedClose`] if no bracket is open, and with [`BracketErr::NotBracket`] if
+    ///the next character isn't a closer. Nothing is consumed on failure.
+    pub fn close(&self) -> impl Parser<char, Err = BracketErr> + '_ {
+        let grammar = Grammar::Alt(self.pairs.iter().map(|(_, c)| Grammar::Literal(c.to_string())).collect());
+        Described::new(move |s: &mut ParserString| {
+            let offset = s.start();
+            self.forget_from(offset);
+            let innermost = self.open.borrow().last().copied();
+            let Some(&(_, found)) = self.pairs.iter().find(|(_, c)| s.get().starts_with(*c)) else {
+                if let Some((opener, _)) = innermost {
+                    s.expect(offset, Expectation::Literal(self.closer(opener).to_string()));
+                }
+                return Err(BracketErr::NotBracket)
+            };
+            let span = offset..offset + found.len_utf8();
+            let Some((opener, at)) = innermost else {
+                return Err(BracketErr::UnmatchedClose { found, span })
+            };
+            let expected = self.closer(opener);
+            if found != expected {
+                s.expect(offset, Expectation::Literal(expected.to_string()));
+                return Err(BracketErr::Mismatched { expected, found, span, opened: at..at + opener.len_utf8() })
+            }
+            s.take(1);
+            self.open.borrow_mut().pop();
+            Ok(found)
+        }, grammar)
+    }
+
+    ///Succeeds if no brackets are open, consuming nothing. Otherwise, fails with
+    ///[`BracketErr::Unclosed`] for the innermost one.
+    pub fn finish(&self) -> impl Parser<(), Err = BracketErr> + '_ {
+        Described::new(move |s: &mut ParserString| {
+            self.forget_from(s.start());
+            match self.open.borrow().last() {
+                Some(&(opener, at)) => {
+                    let expected = self.closer(opener);
+                    s.expect(s.start(), Expectation::Literal(expected.to_string()));
+                    Err(BracketErr::Unclosed { opener, expected, opened: at..at + opener.len_utf8() })
+                },
+                None => Ok(()),
+            }
+        }, Grammar::Seq(vec![]))
+    }
+}
+
+///Indicates that a [`BracketTracker`] parser has failed.
+#[derive(Debug, Clone, Error, FromNever, PartialEq, Eq)]
+pub enum BracketErr {
+    ///Parser failed because the next character wasn't the kind of bracket expected
+    #[error("expected a bracket")]
+    NotBracket,
+    ///Parser failed because a closer was found with nothing open
+    #[error("unmatched `{found}` at offset {}", span.start)]
+    UnmatchedClose {
+        ///The closer that was found
+        found: char,
+        ///Where it was found
+        span: Range<usize>,
+    },
+    ///Parser failed because a closer didn't match the innermost opener
+    #[error("expected `{expected}` but found `{found}` at offset {}, for the opener at offset {}", span.start, opened.start)]
+    Mismatched {
+        ///The closer of the innermost opener
+        expected: char,
+        ///The closer that was found
+        found: char,
+        ///Where it was found
+        span: Range<usize>,
+        ///Where the innermost opener is
+        opened: Range<usize>,
+    },
+    ///Parser failed because a bracket was left open
+    #[error("missing `{expected}` for the `{opener}` opened at offset {}", opened.start)]
+    Unclosed {
+        ///The opener that was left open
+        opener: char,
+        ///The closer that was missing
+        expected: char,
+        ///Where the opener is
+        opened: Range<usize>,
+    },
+}
@@ -0,0 +1,417 @@
+use thiserror::Error;
+use nevermore::FromNever;
+
+use crate::{Parser, ParsableBytes, ParserBytes, ParserBits, Bits, Expectation, grammar::{Grammar, Described}};
+
+/**
+Indicates that a fixed-width reader ran out of input. Nothing is consumed on failure.
+
+Sizes are in bytes, or in bits for readers over [`Bits`].
+```
+# use parsa::ParserBytes;
+# use parsa::builtins::{be_u32, ReadErr};
+let mut input = ParserBytes::from(&[0, 1]);
+assert_eq!(be_u32(&mut input), Err(ReadErr { needed: 4, found: 2 }));
+assert_eq!(input.len(), 2);
+```
+*/
+#[derive(Debug, Clone, Copy, Error, FromNever, PartialEq, Eq)]
+#[error("not enough input: needed {needed}, found {found}")]
+pub struct ReadErr {
+    ///How much the reader needed
+    pub needed: usize,
+    ///How much was left
+    pub found: usize,
+}
+
+fn read<const N: usize>(s: &mut ParserBytes) -> Result<[u8; N], ReadErr> {
+    let found = s.len();
+    let bytes = s.try_take(N).ok_or(ReadErr { needed: N, found })?;
+    Ok(bytes.try_into().expect("took exactly N bytes"))
+}
+
+/**
+Reads a single byte.
+```
+# use parsa::{Parser, ParserBytes};
+# use parsa::builtins::{u8, be_u16, le_u32};
+let mut input = ParserBytes::from(&[7, 0x01, 0x02, 0x04, 0x03, 0x02, 0x01]);
+let header = u8.chain(be_u16).chain(le_u32).parse(&mut input);
+assert_eq!(header, Ok(((7, 0x0102), 0x01020304)));
+```
+*/
+pub fn u8(s: &mut ParserBytes) -> Result<u8, ReadErr> {
+    read::<1>(s).map(|[b]| b)
+}
+
+///Reads a single byte as an [`i8`](prim@i8).
+pub fn i8(s: &mut ParserBytes) -> Result<i8, ReadErr> {
+    read(s).map(i8::from_be_bytes)
+}
+
+impl ParsableBytes for u8 {
+    type Err = ReadErr;
+    fn parse(s: &mut ParserBytes) -> Result<Self, Self::Err> { u8(s) }
+}
+
+impl ParsableBytes for i8 {
+    type Err = ReadErr;
+    fn parse(s: &mut ParserBytes) -> Result<Self, Self::Err> { i8(s) }
+}
+
+impl<T: ParsableBytes, const N: usize> ParsableBytes for [T; N] {
+    type Err = T::Err;
+    fn parse(s: &mut ParserBytes) -> Result<Self, Self::Err> {
+        let items = (0..N).map(|_| T::parse(s)).collect::<Result<Vec<_>, _>>()?;
+        Ok(items.try_into().unwrap_or_else(|_| unreachable!()))
+    }
+}
+
+macro_rules! readers {
+    ($($ty:ident: $be:ident, $le:ident;)*) => { $(
+        #[doc = concat!("Reads a big-endian [`", stringify!($ty), "`].")]
+        pub fn $be(s: &mut ParserBytes) -> Result<$ty, ReadErr> {
+            read(s).map($ty::from_be_bytes)
+        }
+
+        #[doc = concat!("Reads a little-endian [`", stringify!($ty), "`].")]
+        pub fn $le(s: &mut ParserBytes) -> Result<$ty, ReadErr> {
+            read(s).map($ty::from_le_bytes)
+        }
+    )* };
+}
+
+readers! {
+    u16: be_u16, le_u16;
+    u32: be_u32, le_u32;
+    u64: be_u64, le_u64;
+    u128: be_u128, le_u128;
+    i16: be_i16, le_i16;
+    i32: be_i32, le_i32;
+    i64: be_i64, le_i64;
+    i128: be_i128, le_i128;
+    f32: be_f32, le_f32;
+    f64: be_f64, le_f64;
+}
+
+///Indicates that a [`varint`] or [`sleb128`] parser has failed. Nothing is consumed on failure.
+#[derive(Debug, Clone, Copy, Error, FromNever, PartialEq, Eq)]
+pub enum VarintErr {
+    ///The input ended before the last byte of the number
+    #[error("unterminated varint")]
+    Eof,
+    ///The number doesn't fit in 64 bits
+    #[error("varint overflows 64 bits")]
+    Overflow,
+}
+
+//reads the 7-bit groups of a LEB128 number, least significant first, returning the value and how
+//many bits were read
+fn leb128(s: &mut ParserBytes, signed: bool) -> Result<(u64, u32), VarintErr> {
+    let bytes = s.get();
+    let mut value = 0u64;
+    for (i, &b) in bytes.iter().enumerate().take(10) {
+        let shift = i as u32 * 7;
+        let group = (b & 0x7f) as u64;
+        if shift == 63 {
+            //only the lowest bit of the last group fits, the rest must match it when signed
+            let ok = match signed {
+                false => group <= 1,
+                true => group == 0 || group == 0x7f,
+            };
+            if !ok || b & 0x80 != 0 {
+                return Err(VarintErr::Overflow)
+            }
+        }
+        value |= group << shift;
+        if b & 0x80 == 0 {
+            s.take(i + 1);
+            return Ok((value, shift + 7))
+        }
+    }
+    Err(if bytes.len() >= 10 { VarintErr::Overflow } else { VarintErr::Eof })
+}
+
+/**
+Reads an unsigned LEB128 number, as used for protobuf varints.
+```
+# use parsa::ParserBytes;
+# use parsa::builtins::{varint, VarintErr};
+let mut input = ParserBytes::from(&[0x96, 0x01, 0x80]);
+assert_eq!(varint(&mut input), Ok(150));
+assert_eq!(varint(&mut input), Err(VarintErr::Eof));
+assert_eq!(input.get(), &[0x80]);
+```
+*/
+pub fn varint(s: &mut ParserBytes) -> Result<u64, VarintErr> {
+    leb128(s, false).map(|(v, _)| v)
+}
+
+/**
+Reads a signed LEB128 number.
+```
+# use parsa::ParserBytes;
+# use parsa::builtins::sleb128;
+let mut input = ParserBytes::from(&[0xc0, 0xbb, 0x78, 0x7f]);
+assert_eq!(sleb128(&mut input), Ok(-123456));
+assert_eq!(sleb128(&mut input), Ok(-1));
+```
+*/
+pub fn sleb128(s: &mut ParserBytes) -> Result<i64, VarintErr> {
+    let (v, bits) = leb128(s, true)?;
+    let sign = bits < 64 && v & (1 << (bits - 1)) != 0;
+    Ok(if sign { (v | !0 << bits) as i64 } else { v as i64 })
+}
+
+///Indicates that a [`cstr`] or [`fixed_str`] parser has failed. Nothing is consumed on failure.
+#[derive(Debug, Clone, Copy, Error, FromNever, PartialEq, Eq)]
+pub enum StrErr {
+    ///No NUL byte was found
+    #[error("unterminated string")]
+    Unterminated,
+    ///There were fewer bytes left than the field is wide
+    #[error("{0}")]
+    Short(#[from] ReadErr),
+    ///The string isn't valid UTF-8
+    #[error("{0}")]
+    Utf8(#[from] std::str::Utf8Error),
+}
+
+/**
+Reads bytes up to a NUL byte, which is consumed but not returned.
+```
+# use parsa::ParserBytes;
+# use parsa::builtins::{cstr_bytes, StrErr};
+let mut input = ParserBytes::from(b"a\xffb\0c");
+assert_eq!(cstr_bytes(&mut input), Ok(b"a\xffb".to_vec()));
+assert_eq!(cstr_bytes(&mut input), Err(StrErr::Unterminated));
+```
+*/
+pub fn cstr_bytes(s: &mut ParserBytes) -> Result<Vec<u8>, StrErr> {
+    let len = s.get().iter().position(|&b| b == 0).ok_or(StrErr::Unterminated)?;
+    let out = s.take(len).to_vec();
+    s.take(1);
+    Ok(out)
+}
+
+/**
+Reads a NUL-terminated UTF-8 string. The NUL byte is consumed but not returned.
+```
+# use parsa::{Parser, ParserBytes};
+# use parsa::builtins::{cstr, StrErr};
+let mut input = ParserBytes::from(b"name\0\xff\0");
+assert!(cstr(&mut input).is_ok_and(|s| s == "name"));
+assert!(matches!(cstr(&mut input), Err(StrErr::Utf8(_))));
+assert_eq!(input.len(), 2);
+```
+*/
+pub fn cstr(s: &mut ParserBytes) -> Result<String, StrErr> {
+    let len = s.get().iter().position(|&b| b == 0).ok_or(StrErr::Unterminated)?;
+    let out = std::str::from_utf8(&s.get()[..len])?.to_owned();
+    s.take(len + 1);
+    Ok(out)
+}
+
+/**
+Reads a UTF-8 string from a field `n` bytes wide, trimming trailing NUL and space padding.
+```
+# use parsa::{Parser, ParserBytes};
+# use parsa::builtins::fixed_str;
+let mut input = ParserBytes::from(b"boot.img\0\0\0\0 1.2    ");
+let (name, version) = fixed_str(12).chain(fixed_str(8)).parse(&mut input).unwrap();
+assert_eq!((name.as_str(), version.as_str()), ("boot.img", " 1.2"));
+```
+*/
+pub fn fixed_str(n: usize) -> impl Parser<String, [u8], Err = StrErr> {
+    move |s: &mut ParserBytes| {
+        let found = s.len();
+        let bytes = s.get().get(..n).ok_or(ReadErr { needed: n, found })?;
+        let len = bytes.iter().rposition(|&b| b != 0 && b != b' ').map_or(0, |i| i + 1);
+        let out = std::str::from_utf8(&bytes[..len])?.to_owned();
+        s.take(n);
+        Ok(out)
+    }
+}
+
+/**
+Matches an exact sequence of bytes, like the magic number at the start of a file format. The
+binary counterpart of [`take`](super::take). Nothing is consumed on failure.
+```
+# use parsa::{Parser, ParserBytes};
+# use parsa::builtins::magic;
+let elf = magic(&[0x7f, b'E', b'L', b'F']);
+
+let mut input = ParserBytes::from(b"\x7fELF\x02");
+assert_eq!(elf.parse(&mut input), Ok(b"\x7fELF".as_slice()));
+assert_eq!(input.get(), &[2]);
+
+let mut input = ParserBytes::from(b"MZ\x90");
+let err = elf.parse(&mut input).unwrap_err();
+assert_eq!(err.to_string(), "expected bytes 7f 45 4c 46, found 4d 5a 90 and end of input");
+```
+*/
+pub fn magic(bytes: &'static [u8]) -> impl Parser<&'static [u8], [u8], Err = MagicErr> {
+    let literal = String::from_utf8_lossy(bytes).into_owned();
+    Described::new(move |s: &mut ParserBytes| {
+        let head = &s.get()[..bytes.len().min(s.len())];
+        if head != bytes {
+            let found = head.to_vec();
+            s.expect(s.start(), Expectation::Literal(String::from_utf8_lossy(bytes).into_owned()));
+            return Err(MagicErr { expected: bytes, found })
+        }
+        s.take(bytes.len());
+        Ok(bytes)
+    }, Grammar::Literal(literal))
+}
+
+///Indicates that a [`magic`] parser has failed.
+#[derive(Debug, Clone, FromNever, PartialEq, Eq)]
+pub struct MagicErr {
+    ///The bytes that were expected
+    pub expected: &'static [u8],
+    ///The bytes found in their place, which are shorter if the input ended
+    pub found: Vec<u8>,
+}
+
+impl std::fmt::Display for MagicErr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let hex = |bytes: &[u8]| bytes.iter().map(|b| format!("{b:02x}")).collect::<Vec<_>>().join(" ");
+        write!(f, "expected bytes {}, found {}", hex(self.expected), hex(&self.found))?;
+        if self.found.len() < self.expected.len() {
+            write!(f, " and end of input")?;
+        }
+        Ok(())
+    }
+}
+impl std::error::Error for MagicErr {}
+
+/**
+Runs `p`, then checks the bytes it consumed against a checksum read by `expected`. `algo` computes
+the checksum, like [`crc32`] or [`sum8`]. Errors are coerced like
+[`Chain`](crate::combinators::Chain).
+```
+# use parsa::{Parser, ParserBytes};
+# use parsa::builtins::{checksummed, crc32, be_u32, fixed_str, ChecksumErr};
+let frame = checksummed(fixed_str(9), crc32, be_u32);
+
+let mut data = b"123456789".to_vec();
+data.extend(0xcbf43926u32.to_be_bytes());
+let mut input = ParserBytes::from(data.clone());
+assert_eq!(frame.parse(&mut input), Ok("123456789".to_owned()));
+
+data[0] = b'0';
+let mut input = ParserBytes::from(data);
+assert_eq!(frame.parse(&mut input), Err(ChecksumErr::Mismatch { offset: 9 }));
+```
+*/
+pub fn checksummed<T, C, E, P, A, X>(p: P, algo: A, expected: X) -> impl Parser<T, [u8], Err = ChecksumErr<P::Err>>
+where
+    P: Parser<T, [u8]>,
+    A: Fn(&[u8]) -> C,
+    C: PartialEq,
+    X: Parser<C, [u8], Err = E>,
+    E: Into<P::Err>,
+{
+    let grammar = p.grammar().seq(expected.grammar());
+    Described::new(move |s: &mut ParserBytes| {
+        let cp = s.checkpoint();
+        let v = p.parse(s).map_err(ChecksumErr::Inner)?;
+        let sum = algo(s.since(cp));
+
+        let offset = s.start();
+        let found = expected.parse(s).map_err(|e| ChecksumErr::Inner(e.into()))?;
+        if found != sum {
+            return Err(ChecksumErr::Mismatch { offset })
+        }
+        Ok(v)
+    }, grammar)
+}
+
+///Indicates that a [`checksummed`] parser has failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, FromNever)]
+pub enum ChecksumErr<E> {
+    ///The inner or checksum parser failed
+    Inner(E),
+    ///The checksum didn't match the consumed bytes
+    Mismatch {
+        ///Where the checksum field starts
+        offset: usize,
+    },
+}
+
+impl<E: std::fmt::Display> std::fmt::Display for ChecksumErr<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Inner(e) => write!(f, "{e}"),
+            Self::Mismatch { offset } => write!(f, "checksum mismatch at offset {offset}"),
+        }
+    }
+}
+impl<E: std::error::Error> std::error::Error for ChecksumErr<E> {}
+
+///Computes the CRC-32 (IEEE) of `bytes`, as used by zip, PNG and Ethernet. For use with
+///[`checksummed`].
+pub fn crc32(bytes: &[u8]) -> u32 {
+    !bytes.iter().fold(!0u32, |crc, &b| {
+        (0..8).fold(crc ^ b as u32, |crc, _| (crc >> 1) ^ (0xedb88320 & (crc & 1).wrapping_neg()))
+    })
+}
+
+///Computes the wrapping sum of `bytes`. For use with [`checksummed`].
+pub fn sum8(bytes: &[u8]) -> u8 {
+    bytes.iter().fold(0u8, |sum, &b| sum.wrapping_add(b))
+}
+
+/**
+Runs a parser over the bits of byte input, for fields that don't fill whole bytes. Afterwards,
+the input moves on to the next whole byte. Nothing is consumed on failure.
+```
+# use parsa::{Parser, ParserBytes};
+# use parsa::builtins::{bitwise, bits, flag, u8, ReadErr};
+#[derive(Debug, PartialEq)]
+struct Flags { response: bool, opcode: u8, truncated: bool }
+
+let flags = bitwise(flag.chain(bits(4)).chain(flag.after(bits(2))))
+    .map(|((response, opcode), truncated)| Flags { response, opcode: opcode as u8, truncated });
+
+let mut input = ParserBytes::from(&[0b1_0010_0_00, 0xff]);
+let (f, next) = flags.chain(u8).parse(&mut input).unwrap();
+assert_eq!(f, Flags { response: true, opcode: 2, truncated: false });
+assert_eq!(next, 0xff);
+```
+*/
+pub fn bitwise<T, P>(p: P) -> impl Parser<T, [u8], Err = P::Err>
+where P: Parser<T, Bits>
+{
+    move |s: &mut ParserBytes| {
+        let cp = s.checkpoint();
+        let res = ParserBits::over(s, |s| p.parse(s));
+        if res.is_err() {
+            s.rewind(cp);
+        }
+        res
+    }
+}
+
+/**
+Reads `n` bits as an integer, most significant bit first. Panics if `n` is larger than 64.
+```
+# use parsa::{Parser, ParserBits};
+# use parsa::builtins::bits;
+let mut input = ParserBits::from(&[0b1100_0001]);
+assert_eq!(bits(2).parse(&mut input), Ok(0b11));
+assert_eq!(bits(6).parse(&mut input), Ok(1));
+```
+*/
+pub fn bits(n: usize) -> impl Parser<u64, Bits, Err = ReadErr> {
+    move |s: &mut ParserBits| {
+        let found = s.len();
+        s.try_take(n).ok_or(ReadErr { needed: n, found })
+    }
+}
+
+///Reads a single bit as a [`bool`].
+pub fn flag(s: &mut ParserBits) -> Result<bool, ReadErr> {
+    let found = s.len();
+    s.try_take(1).map(|b| b == 1).ok_or(ReadErr { needed: 1, found })
+}
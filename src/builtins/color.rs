@@ -0,0 +1,68 @@
+use thiserror::Error;
+use nevermore::FromNever;
+
+use crate::{Parser, ParserString};
+use super::take_while_m_n;
+
+///A color with 8-bit channels. See [`hex_color`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rgba {
+    ///Red
+    pub r: u8,
+    ///Green
+    pub g: u8,
+    ///Blue
+    pub b: u8,
+    ///Alpha, which is 255 when not given
+    pub a: u8,
+}
+
+/**
+Parses a hex color code: `#RGB`, `#RGBA`, `#RRGGBB` or `#RRGGBBAA`, in either case. Short forms
+repeat each digit, so `#f80` is `#ff8800`. Nothing is consumed on failure.
+```
+# use parsa::ParserString;
+# use parsa::builtins::{hex_color, Rgba, ColorErr};
+let mut input = ParserString::from("#1e90ff;");
+assert_eq!(hex_color(&mut input), Ok(Rgba { r: 0x1e, g: 0x90, b: 0xff, a: 255 }));
+assert_eq!(input.get(), ";");
+
+assert_eq!(hex_color(&mut ParserString::from("#F808")), Ok(Rgba { r: 255, g: 136, b: 0, a: 136 }));
+assert_eq!(hex_color(&mut ParserString::from("#12345")), Err(ColorErr::Length(5)));
+```
+*/
+pub fn hex_color(s: &mut ParserString) -> Result<Rgba, ColorErr> {
+    let cp = s.checkpoint();
+    if s.try_take(1) != Some("#") {
+        s.rewind(cp);
+        return Err(ColorErr::NoHash)
+    }
+    let digits = take_while_m_n(3, 8, |c| c.is_ascii_hexdigit()).parse(s).map_err(|e| {
+        s.rewind(cp);
+        ColorErr::Length(e.found)
+    })?;
+    let channels: Vec<u8> = match digits.len() {
+        3 | 4 => digits.chars()
+            .map(|c| c.to_digit(16).expect("hex digit") as u8 * 0x11)
+            .collect(),
+        6 | 8 => (0..digits.len()).step_by(2)
+            .map(|i| u8::from_str_radix(&digits[i..i + 2], 16).expect("hex digits"))
+            .collect(),
+        n => {
+            s.rewind(cp);
+            return Err(ColorErr::Length(n))
+        },
+    };
+    Ok(Rgba { r: channels[0], g: channels[1], b: channels[2], a: channels.get(3).copied().unwrap_or(255) })
+}
+
+///Indicates that a [`hex_color`] parser has failed.
+#[derive(Debug, Clone, Copy, Error, FromNever, PartialEq, Eq)]
+pub enum ColorErr {
+    ///Parser failed because the input didn't start with `#`
+    #[error("expected `#`")]
+    NoHash,
+    ///Parser failed because there were the wrong amount of hex digits
+    #[error("expected 3, 4, 6 or 8 hex digits, found {0}")]
+    Length(usize),
+}
@@ -0,0 +1,130 @@
+use thiserror::Error;
+use nevermore::FromNever;
+
+use crate::{Parser, ParserString, grammar::Grammar};
+
+///Which side of a [`field`] padding is trimmed from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Trim {
+    ///Keep the padding
+    None,
+    ///Trim leading padding, for right-aligned fields like numbers
+    Start,
+    ///Trim trailing padding, for left-aligned fields like text
+    #[default]
+    End,
+    ///Trim both sides
+    Both,
+}
+
+/**
+Takes a fixed-width field of `width` columns. Columns are characters unless
+[`bytes`](Field::bytes) is set. Trailing spaces are trimmed by default, which can be changed with
+[`trim`](Field::trim) and [`pad`](Field::pad). Fields compose into record layouts with
+[`chain`](Parser::chain).
+```
+# use parsa::{Parser, ParserString};
+# use parsa::builtins::{field, Trim};
+let record = field(8)
+    .chain(field(6).trim(Trim::Start))
+    .chain(field(6).pad('0').trim(Trim::Start));
+
+let mut input = ParserString::from(concat!("Zoë     ", "    42", "000150"));
+assert_eq!(record.parse(&mut input), Ok((("Zoë".to_owned(), "42".to_owned()), "150".to_owned())));
+```
+*/
+pub fn field(width: usize) -> Field {
+    Field { width, bytes: false, trim: Trim::End, pad: ' ' }
+}
+
+///A fixed-width field parser. See [`field`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Field {
+    width: usize,
+    bytes: bool,
+    trim: Trim,
+    pad: char,
+}
+
+impl Field {
+    ///Count the width in bytes rather than characters. Fails with [`FieldErr::Boundary`] if the
+    ///field would end inside a character.
+    ///```
+    ///# use parsa::{Parser, ParserString};
+    ///# use parsa::builtins::{field, FieldErr};
+    ///assert_eq!(field(3).bytes().parse(&mut ParserString::from("né!")), Ok("né".to_owned()));
+    ///assert_eq!(field(2).bytes().parse(&mut ParserString::from("né!")), Err(FieldErr::Boundary { offset: 2 }));
+    ///```
+    pub const fn bytes(mut self) -> Self {
+        self.bytes = true;
+        self
+    }
+
+    ///Set which side padding is trimmed from.
+    pub const fn trim(mut self, trim: Trim) -> Self {
+        self.trim = trim;
+        self
+    }
+
+    ///Set the padding character, a space by default.
+    pub const fn pad(mut self, pad: char) -> Self {
+        self.pad = pad;
+        self
+    }
+}
+
+impl Parser<String> for Field {
+    type Err = FieldErr;
+
+    fn parse(&self, s: &mut ParserString) -> Result<String, Self::Err> {
+        let rest = s.get();
+        let len = if self.bytes {
+            if rest.len() < self.width {
+                return Err(FieldErr::Short { needed: self.width, found: rest.len() })
+            }
+            if !rest.is_char_boundary(self.width) {
+                return Err(FieldErr::Boundary { offset: s.start() + self.width })
+            }
+            self.width
+        } else {
+            let found = rest.chars().take(self.width).count();
+            if found < self.width {
+                return Err(FieldErr::Short { needed: self.width, found })
+            }
+            rest.char_indices().nth(self.width).map_or(rest.len(), |(i, _)| i)
+        };
+
+        let text = &rest[..len];
+        let text = match self.trim {
+            Trim::None => text,
+            Trim::Start => text.trim_start_matches(self.pad),
+            Trim::End => text.trim_end_matches(self.pad),
+            Trim::Both => text.trim_matches(self.pad),
+        }.to_owned();
+        unsafe { s.set_ptr(s.start() + len) }
+        Ok(text)
+    }
+
+    fn grammar(&self) -> Grammar {
+        Grammar::named(format!("field({})", self.width), Grammar::Opaque)
+    }
+}
+
+///Indicates that a [`field`] parser has failed. Nothing is consumed on failure.
+#[derive(Debug, Clone, Copy, Error, FromNever, PartialEq, Eq)]
+pub enum FieldErr {
+    ///Parser failed because the input is shorter than the field
+    #[error("expected a field {needed} wide, found {found}")]
+    Short {
+        ///The width of the field
+        needed: usize,
+        ///How much input was left
+        found: usize,
+    },
+    ///Parser failed because a byte-width field ended inside a character
+    #[error("field ends inside a character at offset {offset}")]
+    Boundary {
+        ///Where the field would have ended
+        offset: usize,
+    },
+}
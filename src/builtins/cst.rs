@@ -0,0 +1,195 @@
+/*!
+Lossless concrete syntax trees.
+
+Parsers built with [`token`] and [`node`] produce a tree that keeps every byte of the input:
+each [`Token`] stores the trivia a [`Layout`] skipped after it, and [`root`] keeps any trivia
+before the first token. Formatters and refactoring tools can rewrite parts of the tree and print
+the rest back exactly as it was.
+
+```
+# use parsa::{Parser, ParserString};
+# use parsa::builtins::{Layout, word, take, BuiltinErr};
+# use parsa::builtins::cst::{root, node, token, Element};
+let layout = Layout::new().line_comment("//");
+let stmt = node("let", token("kw", &layout, take("let")).convert_err::<BuiltinErr>()
+    .chain(token("name", &layout, word))
+    .chain(token("eq", &layout, take("=")))
+    .chain(token("value", &layout, word)));
+let file = root("file", &layout, stmt.many());
+
+let source = "  // setup\nlet x = 1 // one\nlet  y =2\n";
+let tree = file.parse(&mut ParserString::from(source)).unwrap();
+assert_eq!(tree.text(), source);
+
+let Element::Node(first) = &tree.children[1] else { panic!() };
+let names: Vec<_> = first.tokens().map(|t| t.text.as_str()).collect();
+assert_eq!(names, ["let", "x", "=", "1"]);
+assert_eq!(first.tokens().last().unwrap().trivia, " // one\n");
+```
+*/
+use crate::{Parser, ParserString, Span, grammar::{Grammar, Described}};
+use super::Layout;
+
+///A leaf of the tree: the text a parser consumed, and the trivia after it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Token {
+    ///What kind of token this is
+    pub kind: &'static str,
+    ///The text of the token itself
+    pub text: String,
+    ///Where the text is, not including trivia
+    pub span: Span,
+    ///The whitespace and comments after the token
+    pub trivia: String,
+}
+
+///An inner node of the tree.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Node {
+    ///What kind of node this is
+    pub kind: &'static str,
+    ///The children, in order
+    pub children: Vec<Element>,
+    ///Where the node is, including the trivia of its last token
+    pub span: Span,
+}
+
+///A child of a [`Node`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Element {
+    ///An inner node
+    Node(Node),
+    ///A token
+    Token(Token),
+    ///Trivia that doesn't follow any token, like at the start of the input
+    Trivia(String),
+}
+
+impl Element {
+    fn write_text(&self, out: &mut String) {
+        match self {
+            Self::Node(node) => node.children.iter().for_each(|e| e.write_text(out)),
+            Self::Token(token) => {
+                out.push_str(&token.text);
+                out.push_str(&token.trivia);
+            },
+            Self::Trivia(trivia) => out.push_str(trivia),
+        }
+    }
+}
+
+impl Node {
+    ///The exact source text of this node, including trivia.
+    pub fn text(&self) -> String {
+        let mut out = String::with_capacity(self.span.len());
+        self.children.iter().for_each(|e| e.write_text(&mut out));
+        out
+    }
+
+    ///Iterate over every token under this node, in order.
+    pub fn tokens(&self) -> impl Iterator<Item = &Token> {
+        let mut stack: Vec<&Element> = self.children.iter().rev().collect();
+        std::iter::from_fn(move || loop {
+            match stack.pop()? {
+                Element::Node(node) => stack.extend(node.children.iter().rev()),
+                Element::Token(token) => return Some(token),
+                Element::Trivia(_) => (),
+            }
+        })
+    }
+
+    ///Iterate over the nodes directly under this one.
+    pub fn child_nodes(&self) -> impl Iterator<Item = &Node> {
+        self.children.iter().filter_map(|e| match e {
+            Element::Node(node) => Some(node),
+            _ => None,
+        })
+    }
+}
+
+///Outputs that can become the children of a [`node`]: elements, tokens and nodes, and options,
+///vectors and tuples of them.
+pub trait IntoElements {
+    ///Appends this to `out`.
+    fn into_elements(self, out: &mut Vec<Element>);
+}
+
+impl IntoElements for Element {
+    fn into_elements(self, out: &mut Vec<Element>) { out.push(self) }
+}
+impl IntoElements for Token {
+    fn into_elements(self, out: &mut Vec<Element>) { out.push(Element::Token(self)) }
+}
+impl IntoElements for Node {
+    fn into_elements(self, out: &mut Vec<Element>) { out.push(Element::Node(self)) }
+}
+impl<T: IntoElements> IntoElements for Option<T> {
+    fn into_elements(self, out: &mut Vec<Element>) {
+        if let Some(e) = self { e.into_elements(out) }
+    }
+}
+impl<T: IntoElements> IntoElements for Vec<T> {
+    fn into_elements(self, out: &mut Vec<Element>) {
+        self.into_iter().for_each(|e| e.into_elements(out))
+    }
+}
+
+macro_rules! tuple_elements {
+    ($($t:ident)*) => {
+        impl<$($t: IntoElements),*> IntoElements for ($($t,)*) {
+            #[allow(non_snake_case)]
+            fn into_elements(self, out: &mut Vec<Element>) {
+                let ($($t,)*) = self;
+                $($t.into_elements(out);)*
+            }
+        }
+    };
+}
+tuple_elements!(A B);
+tuple_elements!(A B C);
+tuple_elements!(A B C D);
+tuple_elements!(A B C D E);
+tuple_elements!(A B C D E F);
+
+/**
+Runs `p` and turns the text it consumed into a [`Token`] of `kind`, then skips the trivia after
+it with `layout`, keeping it on the token.
+*/
+pub fn token<'a, T, P: Parser<T> + 'a>(kind: &'static str, layout: &'a Layout, p: P) -> impl Parser<Token, Err = P::Err> + 'a {
+    let grammar = Grammar::named(kind, p.grammar());
+    Described::new(move |s: &mut ParserString| {
+        let start = s.checkpoint();
+        p.parse(s)?;
+        let text = s.since(start).to_owned();
+        let span = Span::new(start.offset(), s.start());
+        let trivia = s.checkpoint();
+        layout.skip(s);
+        Ok(Token { kind, text, span, trivia: s.since(trivia).to_owned() })
+    }, grammar)
+}
+
+///Runs `p` and collects its output into a [`Node`] of `kind`.
+pub fn node<T: IntoElements, P: Parser<T>>(kind: &'static str, p: P) -> impl Parser<Node, Err = P::Err> {
+    let grammar = Grammar::named(kind, p.grammar());
+    Described::new(move |s: &mut ParserString| {
+        let start = s.start();
+        let mut children = vec![];
+        p.parse(s)?.into_elements(&mut children);
+        Ok(Node { kind, children, span: Span::new(start, s.start()) })
+    }, grammar)
+}
+
+///Like [`node`], but first skips trivia with `layout`, keeping it as the first child. Use this for
+///the top of the tree, so that trivia before the first token isn't lost.
+pub fn root<'a, T: IntoElements, P: Parser<T> + 'a>(kind: &'static str, layout: &'a Layout, p: P) -> impl Parser<Node, Err = P::Err> + 'a {
+    let grammar = Grammar::named(kind, p.grammar());
+    Described::new(move |s: &mut ParserString| {
+        let start = s.checkpoint();
+        let mut children = vec![];
+        if layout.skip(s) > 0 {
+            children.push(Element::Trivia(s.since(start).to_owned()));
+        }
+        p.parse(s)?.into_elements(&mut children);
+        Ok(Node { kind, children, span: Span::new(start.offset(), s.start()) })
+    }, grammar)
+}
@@ -0,0 +1,165 @@
+use std::convert::Infallible;
+
+use thiserror::Error;
+use nevermore::FromNever;
+
+use crate::{Parser, ParserString, grammar::{Grammar, Described}};
+
+/**
+Parses a doc comment: a `///` line, or a `/** */` block. Returns its text, without the markers, a
+single leading space, or the line ending.
+```
+# use parsa::ParserString;
+# use parsa::builtins::doc_comment;
+let mut input = ParserString::from("/// Adds two numbers.\nfn add");
+assert_eq!(doc_comment(&mut input), Ok("Adds two numbers.".to_owned()));
+assert_eq!(input.get(), "fn add");
+
+assert_eq!(doc_comment(&mut ParserString::from("/** Block */")), Ok("Block ".to_owned()));
+```
+*/
+pub fn doc_comment(s: &mut ParserString) -> Result<String, DocErr> {
+    let rest = s.get();
+    if let Some(line) = rest.strip_prefix("///") {
+        let end = line.find('\n').unwrap_or(line.len());
+        let text = line[..end].strip_suffix('\r').unwrap_or(&line[..end]);
+        let text = text.strip_prefix(' ').unwrap_or(text).to_owned();
+        let consumed = 3 + (end + 1).min(line.len());
+        unsafe { s.set_ptr(s.start() + consumed) }
+        return Ok(text)
+    }
+    if let Some(block) = rest.strip_prefix("/**") {
+        let Some(end) = block.find("*/") else { return Err(DocErr::Unterminated { offset: s.start() }) };
+        let text = block[..end].strip_prefix(' ').unwrap_or(&block[..end]).to_owned();
+        unsafe { s.set_ptr(s.start() + 3 + end + 2) }
+        return Ok(text)
+    }
+    Err(DocErr::NotFound)
+}
+
+/**
+Parses an attribute like `#[derive(Debug)]`, returning the text between the brackets. Brackets
+inside it must be balanced.
+```
+# use parsa::ParserString;
+# use parsa::builtins::attribute;
+let mut input = ParserString::from("#[cfg(any(a, b[0]))] struct");
+assert_eq!(attribute(&mut input), Ok("cfg(any(a, b[0]))".to_owned()));
+assert_eq!(input.get(), " struct");
+```
+*/
+pub fn attribute(s: &mut ParserString) -> Result<String, DocErr> {
+    let Some(body) = s.get().strip_prefix("#[") else { return Err(DocErr::NotFound) };
+    let mut depth = 0;
+    for (i, c) in body.char_indices() {
+        match c {
+            '[' => depth += 1,
+            ']' if depth == 0 => {
+                let text = body[..i].to_owned();
+                unsafe { s.set_ptr(s.start() + 2 + i + 1) }
+                return Ok(text)
+            },
+            ']' => depth -= 1,
+            _ => (),
+        }
+    }
+    Err(DocErr::Unterminated { offset: s.start() })
+}
+
+///Indicates that a [`doc_comment`] or [`attribute`] parser has failed.
+#[derive(Debug, Clone, Copy, Error, FromNever, PartialEq, Eq)]
+pub enum DocErr {
+    ///Parser failed because the input didn't start with one
+    #[error("expected a doc comment or attribute")]
+    NotFound,
+    ///Parser failed because it was never closed
+    #[error("unterminated doc comment or attribute at offset {offset}")]
+    Unterminated {
+        ///Where it started
+        offset: usize,
+    },
+}
+
+//whitespace, including newlines
+fn blank(s: &mut ParserString) {
+    let n = s.get().chars().take_while(|c| c.is_whitespace()).count();
+    s.take(n);
+}
+
+/**
+Skips whitespace, including newlines, doc comments and attributes, discarding them. This can
+never fail.
+```
+# use parsa::{Parser, ParserString};
+# use parsa::builtins::{doc_trivia, word, WordErr};
+let mut input = ParserString::from("/// docs\n#[inline]\n  item");
+assert_eq!(doc_trivia.convert_err::<WordErr>().replace(word).parse(&mut input), Ok("item".to_owned()));
+```
+*/
+pub fn doc_trivia(s: &mut ParserString) -> Result<(), Infallible> {
+    loop {
+        blank(s);
+        if doc_comment(s).is_err() && attribute(s).is_err() {
+            return Ok(())
+        }
+    }
+}
+
+///An item with the doc comments and attributes before it. See [`documented`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Documented<T> {
+    ///The text of each doc comment, in order
+    pub docs: Vec<String>,
+    ///The text of each attribute, in order
+    pub attrs: Vec<String>,
+    ///The item
+    pub item: T,
+}
+
+impl<T> Documented<T> {
+    ///The doc comments joined into one string, a line each.
+    pub fn doc(&self) -> String {
+        self.docs.join("\n")
+    }
+}
+
+/**
+Collects the doc comments and attributes before `p`, in any order and separated by whitespace,
+and attaches them to its output. Nothing is consumed if `p` fails.
+```
+# use parsa::{Parser, ParserString};
+# use parsa::builtins::{documented, word};
+let item = documented(word);
+let mut input = ParserString::from("/// The answer.\n/// Don't change it.\n#[pub]\nanswer");
+let parsed = item.parse(&mut input).unwrap();
+assert_eq!(parsed.doc(), "The answer.\nDon't change it.");
+assert_eq!(parsed.attrs, ["pub"]);
+assert_eq!(parsed.item, "answer");
+```
+*/
+pub fn documented<T, P: Parser<T>>(p: P) -> impl Parser<Documented<T>, Err = P::Err> {
+    let trivia = Grammar::named("doc", Grammar::Opaque).alt(Grammar::named("attribute", Grammar::Opaque));
+    let grammar = Grammar::repeat(trivia, 0, None).seq(p.grammar());
+    Described::new(move |s: &mut ParserString| {
+        let cp = s.checkpoint();
+        let mut docs = vec![];
+        let mut attrs = vec![];
+        loop {
+            blank(s);
+            if let Ok(doc) = doc_comment(s) {
+                docs.push(doc);
+            } else if let Ok(attr) = attribute(s) {
+                attrs.push(attr);
+            } else {
+                break
+            }
+        }
+        match p.parse(s) {
+            Ok(item) => Ok(Documented { docs, attrs, item }),
+            Err(e) => {
+                s.rewind(cp);
+                Err(e)
+            },
+        }
+    }, grammar)
+}
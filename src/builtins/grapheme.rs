@@ -0,0 +1,28 @@
+use thiserror::Error;
+use nevermore::FromNever;
+
+use crate::ParserString;
+
+/**
+Returns the next grapheme cluster: what a reader sees as one character, which may be made of
+several [`char`]s. Requires the `unicode-segmentation` feature.
+```
+# use parsa::ParserString;
+# use parsa::builtins::{next_grapheme, GraphemeErr};
+let mut input = ParserString::from("👍🏽a");
+assert_eq!(next_grapheme(&mut input), Ok("👍🏽".to_owned()));
+assert_eq!(next_grapheme(&mut input), Ok("a".to_owned()));
+assert_eq!(next_grapheme(&mut input), Err(GraphemeErr));
+```
+*/
+pub fn next_grapheme(s: &mut ParserString) -> Result<String, GraphemeErr> {
+    match s.take_graphemes(1) {
+        "" => Err(GraphemeErr),
+        g => Ok(g.to_owned()),
+    }
+}
+
+///Indicates that a [`next_grapheme`] parser found no input left.
+#[derive(Debug, Clone, Copy, Error, FromNever, PartialEq, Eq)]
+#[error("no characters left")]
+pub struct GraphemeErr;
@@ -0,0 +1,164 @@
+/*!
+Parsers for INI files: `[section]` headers followed by `key = value` entries. Lines starting with
+`;` or `#` are comments, and blank lines are ignored. Keys before the first header belong to an
+unnamed section.
+
+Requires the `ini` feature.
+```
+# use parsa::{Parser, ParserString};
+# use parsa::builtins::ini::ini;
+let text = "\
+name = demo
+; the server to connect to
+[server]
+host = example.com
+port = 8080
+";
+let doc = ini(&mut ParserString::from(text)).unwrap();
+assert_eq!(doc.get("", "name"), Some("demo"));
+assert_eq!(doc.get("server", "port"), Some("8080"));
+assert_eq!(doc.sections[1].entries.len(), 2);
+```
+*/
+use thiserror::Error;
+use nevermore::FromNever;
+
+use crate::{Parser, ParserString};
+use super::{line, Delimiter};
+
+///A parsed INI document. See [`ini`].
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Ini {
+    ///The sections, in order. The first is the unnamed section, which may be empty.
+    pub sections: Vec<Section>,
+}
+
+impl Ini {
+    ///Get the value of `key` in the first section named `section`. Use `""` for keys before the
+    ///first header.
+    pub fn get(&self, section: &str, key: &str) -> Option<&str> {
+        self.sections.iter()
+            .find(|s| s.name == section)?
+            .get(key)
+    }
+}
+
+///A section of an [`Ini`] document.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Section {
+    ///The name in the header, or empty for the unnamed section
+    pub name: String,
+    ///The entries, in order
+    pub entries: Vec<(String, String)>,
+}
+
+impl Section {
+    ///Get the value of the last entry for `key`.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.entries.iter().rev().find(|(k, _)| k == key).map(|(_, v)| v.as_str())
+    }
+}
+
+///Indicates that an INI parser has failed.
+#[derive(Debug, Clone, Copy, Error, FromNever, PartialEq, Eq)]
+pub enum IniErr {
+    ///Parser failed because the line wasn't a `[section]` header
+    #[error("expected a section header at offset {offset}")]
+    NotSection {
+        ///Where the line started
+        offset: usize,
+    },
+    ///Parser failed because the line wasn't a `key = value` entry
+    #[error("expected `key = value` at offset {offset}")]
+    NotEntry {
+        ///Where the line started
+        offset: usize,
+    },
+}
+
+fn is_comment(text: &str) -> bool {
+    text.is_empty() || text.starts_with(';') || text.starts_with('#')
+}
+
+/**
+Parses a `[section]` header line, returning the name with surrounding whitespace trimmed.
+Nothing is consumed on failure.
+```
+# use parsa::ParserString;
+# use parsa::builtins::ini::section;
+assert_eq!(section(&mut ParserString::from("[ server ]\n")), Ok("server".to_owned()));
+assert!(section(&mut ParserString::from("key = 1\n")).is_err());
+```
+*/
+pub fn section(s: &mut ParserString) -> Result<String, IniErr> {
+    let offset = s.start();
+    let cp = s.checkpoint();
+    let text = line(Delimiter::Consume).parse(s).unwrap_or_default();
+    let name = text.trim().strip_prefix('[').and_then(|t| t.strip_suffix(']'));
+    match name {
+        Some(name) => Ok(name.trim().to_owned()),
+        None => {
+            s.rewind(cp);
+            Err(IniErr::NotSection { offset })
+        },
+    }
+}
+
+/**
+Parses a `key = value` line, returning both with surrounding whitespace trimmed. The value is
+everything after the first `=`, and may be empty. Nothing is consumed on failure.
+```
+# use parsa::ParserString;
+# use parsa::builtins::ini::entry;
+let mut input = ParserString::from("path = a=b\n");
+assert_eq!(entry(&mut input), Ok(("path".to_owned(), "a=b".to_owned())));
+```
+*/
+pub fn entry(s: &mut ParserString) -> Result<(String, String), IniErr> {
+    let offset = s.start();
+    let cp = s.checkpoint();
+    let text = line(Delimiter::Consume).parse(s).unwrap_or_default();
+    match text.split_once('=') {
+        Some((key, value)) if !key.trim().is_empty() && !text.trim_start().starts_with('[') => {
+            Ok((key.trim().to_owned(), value.trim().to_owned()))
+        },
+        _ => {
+            s.rewind(cp);
+            Err(IniErr::NotEntry { offset })
+        },
+    }
+}
+
+//skips blank lines and comments
+fn trivia(s: &mut ParserString) {
+    loop {
+        let cp = s.checkpoint();
+        match line(Delimiter::Consume).parse(s) {
+            Ok(text) if is_comment(text.trim()) => (),
+            _ => {
+                s.rewind(cp);
+                return
+            },
+        }
+    }
+}
+
+/**
+Parses a whole INI document. See the [module docs](self). Fails with [`IniErr::NotEntry`] on the
+first line that is neither a header, an entry, nor a comment.
+*/
+pub fn ini(s: &mut ParserString) -> Result<Ini, IniErr> {
+    let mut doc = Ini { sections: vec![Section::default()] };
+    loop {
+        trivia(s);
+        if s.is_empty() {
+            return Ok(doc)
+        }
+        if let Ok(name) = section(s) {
+            doc.sections.push(Section { name, entries: vec![] });
+            continue
+        }
+        let entry = entry(s)?;
+        doc.sections.last_mut().expect("always has the unnamed section").entries.push(entry);
+    }
+}
@@ -0,0 +1,289 @@
+/*!
+Parsers for JSON, and for newline-delimited JSON streams.
+
+Requires the `json` feature.
+```
+# use parsa::ParserString;
+# use parsa::builtins::json::{value, Value};
+let doc = value(&mut ParserString::from(r#"{"name": "parsa", "tags": ["parsing", 1.5, null]}"#)).unwrap();
+assert_eq!(doc.get("name"), Some(&Value::String("parsa".into())));
+assert_eq!(doc.get("tags"), Some(&Value::Array(vec![
+    Value::String("parsing".into()),
+    Value::Number(1.5),
+    Value::Null,
+])));
+```
+*/
+use std::io::Read;
+
+use thiserror::Error;
+use nevermore::FromNever;
+
+use crate::{ParserString, stream::{Records, RecordErr}};
+
+///A JSON value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    ///`null`
+    Null,
+    ///`true` or `false`
+    Bool(bool),
+    ///A number
+    Number(f64),
+    ///A string, with escapes resolved
+    String(String),
+    ///An array
+    Array(Vec<Value>),
+    ///An object, with its members in order
+    Object(Vec<(String, Value)>),
+}
+
+impl Value {
+    ///If this is an object, get the value of the last member named `key`.
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        match self {
+            Self::Object(members) => members.iter().rev().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+}
+
+///Indicates that a JSON parser has failed.
+#[derive(Debug, Clone, Copy, Error, FromNever, PartialEq, Eq)]
+pub enum JsonErr {
+    ///Parser failed because something else was found
+    #[error("expected {expected} at offset {offset}")]
+    Expected {
+        ///What was expected
+        expected: &'static str,
+        ///Where it was expected
+        offset: usize,
+    },
+    ///Parser failed because a number was malformed
+    #[error("invalid number at offset {offset}")]
+    Number {
+        ///Where the number started
+        offset: usize,
+    },
+    ///Parser failed because a string contained an invalid escape
+    #[error("invalid escape at offset {offset}")]
+    Escape {
+        ///Where the escape started
+        offset: usize,
+    },
+    ///Parser failed because arrays and objects were nested more than [`MAX_DEPTH`] deep
+    #[error("nested too deeply at offset {offset}")]
+    TooDeep {
+        ///Where the array or object that went too deep started
+        offset: usize,
+    },
+}
+
+///How deeply arrays and objects may be nested. Deeper documents fail with [`JsonErr::TooDeep`]
+///rather than overflowing the stack, since values are parsed recursively.
+pub const MAX_DEPTH: usize = 128;
+
+fn expected(s: &ParserString, expected: &'static str) -> JsonErr {
+    JsonErr::Expected { expected, offset: s.start() }
+}
+
+fn blank(s: &mut ParserString) {
+    let n = s.get().chars().take_while(|c| matches!(c, ' ' | '\t' | '\n' | '\r')).count();
+    s.take(n);
+}
+
+fn string(s: &mut ParserString) -> Result<String, JsonErr> {
+    if s.try_take(1) != Some("\"") {
+        return Err(expected(s, "a string"))
+    }
+    let mut out = String::new();
+    loop {
+        let offset = s.start();
+        let c = s.get().chars().next().filter(|&c| c >= ' ').ok_or_else(|| expected(s, "a closing `\"`"))?;
+        s.take(1);
+        match c {
+            '"' => return Ok(out),
+            '\\' => {
+                let escape = s.get().chars().next();
+                s.try_take(1);
+                let c = match escape {
+                    Some(c @ ('"' | '\\' | '/')) => c,
+                    Some('n') => '\n',
+                    Some('t') => '\t',
+                    Some('r') => '\r',
+                    Some('b') => '\u{8}',
+                    Some('f') => '\u{c}',
+                    Some('u') => {
+                        let unit = |s: &mut ParserString| s.try_take(4).and_then(|hex| u32::from_str_radix(hex, 16).ok());
+                        let high = unit(s).ok_or(JsonErr::Escape { offset })?;
+                        let code = if (0xd800..0xdc00).contains(&high) {
+                            if s.try_take(2) != Some("\\u") {
+                                return Err(JsonErr::Escape { offset })
+                            }
+                            let low = unit(s).filter(|low| (0xdc00..0xe000).contains(low)).ok_or(JsonErr::Escape { offset })?;
+                            0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00)
+                        } else {
+                            high
+                        };
+                        char::from_u32(code).ok_or(JsonErr::Escape { offset })?
+                    },
+                    _ => return Err(JsonErr::Escape { offset }),
+                };
+                out.push(c);
+            },
+            c => out.push(c),
+        }
+    }
+}
+
+fn number(s: &mut ParserString) -> Result<f64, JsonErr> {
+    let offset = s.start();
+    let n = s.get().chars().take_while(|c| c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E')).count();
+    let text = s.get()[..n].to_owned();
+    let valid = {
+        let digits = text.strip_prefix('-').unwrap_or(&text);
+        digits.starts_with(|c: char| c.is_ascii_digit()) && !(digits.starts_with('0') && digits[1..].starts_with(|c: char| c.is_ascii_digit()))
+    };
+    match text.parse() {
+        Ok(n) if valid => {
+            s.take(text.len());
+            Ok(n)
+        },
+        _ => Err(JsonErr::Number { offset }),
+    }
+}
+
+//the items of an array or members of an object, after the opener
+fn items<T>(s: &mut ParserString, close: &'static str, item: impl Fn(&mut ParserString) -> Result<T, JsonErr>) -> Result<Vec<T>, JsonErr> {
+    let mut out = vec![];
+    blank(s);
+    if s.get().starts_with(close) {
+        s.take(1);
+        return Ok(out)
+    }
+    loop {
+        out.push(item(s)?);
+        blank(s);
+        match s.try_take(1) {
+            Some(",") => blank(s),
+            Some(c) if c == close => return Ok(out),
+            _ => return Err(expected(s, if close == "]" { "`,` or `]`" } else { "`,` or `}`" })),
+        }
+    }
+}
+
+//a value nested inside `depth` arrays or objects
+fn element(s: &mut ParserString, depth: usize) -> Result<Value, JsonErr> {
+    let rest = s.get();
+    match rest.chars().next() {
+        Some('"') => string(s).map(Value::String),
+        Some('[' | '{') if depth >= MAX_DEPTH => Err(JsonErr::TooDeep { offset: s.start() }),
+        Some('[') => {
+            s.take(1);
+            items(s, "]", |s| element(s, depth + 1)).map(Value::Array)
+        },
+        Some('{') => {
+            s.take(1);
+            items(s, "}", |s| {
+                let key = string(s)?;
+                blank(s);
+                if s.try_take(1) != Some(":") {
+                    return Err(expected(s, "`:`"))
+                }
+                blank(s);
+                Ok((key, element(s, depth + 1)?))
+            }).map(Value::Object)
+        },
+        _ if rest.starts_with("null") => { s.take(4); Ok(Value::Null) },
+        _ if rest.starts_with("true") => { s.take(4); Ok(Value::Bool(true)) },
+        _ if rest.starts_with("false") => { s.take(5); Ok(Value::Bool(false)) },
+        Some(c) if c == '-' || c.is_ascii_digit() => number(s).map(Value::Number),
+        _ => Err(expected(s, "a value")),
+    }
+}
+
+/**
+Parses a JSON value, skipping whitespace around it. See the [module docs](self).
+```
+# use parsa::ParserString;
+# use parsa::builtins::json::{value, Value, JsonErr};
+assert_eq!(value(&mut ParserString::from(r#" "é😀" "#)), Ok(Value::String("é😀".into())));
+assert_eq!(value(&mut ParserString::from("[1,]")), Err(JsonErr::Expected { expected: "a value", offset: 3 }));
+assert_eq!(value(&mut ParserString::from("01")), Err(JsonErr::Number { offset: 0 }));
+assert_eq!(value(&mut ParserString::from("[".repeat(200))), Err(JsonErr::TooDeep { offset: 128 }));
+```
+*/
+pub fn value(s: &mut ParserString) -> Result<Value, JsonErr> {
+    blank(s);
+    let v = element(s, 0)?;
+    blank(s);
+    Ok(v)
+}
+
+//a line of NDJSON, which may be blank
+fn line(s: &mut ParserString) -> Result<Option<Value>, JsonErr> {
+    if s.get().trim().is_empty() {
+        s.take(s.len());
+        return Ok(None)
+    }
+    value(s).map(Some)
+}
+
+/**
+Reads newline-delimited JSON (also known as JSON Lines) from `reader`, yielding one value per
+line. Blank lines are ignored, and each malformed line produces its own error, with the line's
+index and byte offset, without ending the stream. Lines are read in chunks, so the whole input is
+never loaded at once.
+```
+# use parsa::builtins::json::{ndjson, Value};
+# use parsa::stream::RecordErr;
+let log = "{\"level\": \"info\"}\n\n{oops}\n{\"level\": \"warn\"}\n";
+
+let lines: Vec<_> = ndjson(log.as_bytes()).collect();
+assert_eq!(lines.len(), 3);
+assert!(matches!(lines[1], Err(RecordErr::Parse { record: 2, offset: 19, .. })));
+
+let levels: Vec<_> = ndjson(log.as_bytes())
+    .skip_malformed()
+    .map(|v| v.unwrap().get("level").cloned())
+    .collect();
+assert_eq!(levels, [Some(Value::String("info".into())), Some(Value::String("warn".into()))]);
+```
+*/
+pub fn ndjson<R: Read>(reader: R) -> Ndjson<R> {
+    Ndjson { records: Records::new(reader, line, "\n"), skip_malformed: false }
+}
+
+type LineParser = fn(&mut ParserString) -> Result<Option<Value>, JsonErr>;
+
+///An iterator of values in newline-delimited JSON. See [`ndjson`].
+#[derive(Debug)]
+pub struct Ndjson<R> {
+    records: Records<R, Option<Value>, LineParser>,
+    skip_malformed: bool,
+}
+
+impl<R> Ndjson<R> {
+    ///Silently skip lines that aren't valid JSON or UTF-8, rather than yielding their errors.
+    ///Read errors are still yielded.
+    pub fn skip_malformed(mut self) -> Self {
+        self.skip_malformed = true;
+        self
+    }
+}
+
+impl<R: Read> Iterator for Ndjson<R> {
+    type Item = Result<Value, RecordErr<JsonErr>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.records.next()? {
+                Ok(Some(v)) => return Some(Ok(v)),
+                Ok(None) => (),
+                Err(RecordErr::Io(e)) => return Some(Err(RecordErr::Io(e))),
+                Err(_) if self.skip_malformed => (),
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+}
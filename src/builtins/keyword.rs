@@ -0,0 +1,173 @@
+use thiserror::Error;
+use nevermore::FromNever;
+
+use crate::{Parser, ParserString, Expectation, grammar::{Grammar, Described}};
+
+fn is_ident_start(c: char) -> bool { c.is_alphabetic() || c == '_' }
+fn is_ident_continue(c: char) -> bool { c.is_alphanumeric() || c == '_' }
+
+/**
+A set of reserved words, for [`ident_except`] and [`keyword_of`]. Keywords may also be symbols,
+like `+=`.
+```
+# use parsa::builtins::KeywordSet;
+let keywords = KeywordSet::new(&["let", "fn", "if", "else"]);
+assert!(keywords.contains("fn"));
+assert!(!keywords.contains("fun"));
+```
+*/
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct KeywordSet {
+    //longest first, so the first match is the longest
+    words: Vec<&'static str>,
+}
+
+impl KeywordSet {
+    ///Constructs a set from a list of keywords. Duplicates are ignored.
+    pub fn new(words: &[&'static str]) -> Self {
+        let mut words = words.to_vec();
+        words.sort_by(|a, b| b.len().cmp(&a.len()).then(a.cmp(b)));
+        words.dedup();
+        Self { words }
+    }
+
+    ///Returns `true` if `word` is in the set.
+    pub fn contains(&self, word: &str) -> bool {
+        self.words.contains(&word)
+    }
+
+    ///The keywords, longest first.
+    pub fn iter(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.words.iter().copied()
+    }
+
+    //the longest keyword at the start of `text` that isn't directly followed by more of an
+    //identifier
+    fn longest_match(&self, text: &str) -> Option<&'static str> {
+        self.iter().find(|k| {
+            text.starts_with(k) && !(k.ends_with(is_ident_continue) && text[k.len()..].starts_with(is_ident_continue))
+        })
+    }
+}
+
+/**
+Parses an identifier, made of letters, digits and `_` and not starting with a digit, unless it is
+one of `keywords`. Nothing is consumed on failure.
+```
+# use parsa::{Parser, ParserString};
+# use parsa::builtins::{ident_except, KeywordSet, IdentErr};
+let keywords = KeywordSet::new(&["let", "fn"]);
+let ident = ident_except(&keywords);
+assert_eq!(ident.parse(&mut ParserString::from("letter = 1")), Ok("letter".to_owned()));
+assert_eq!(ident.parse(&mut ParserString::from("let x")), Err(IdentErr::Reserved { keyword: "let" }));
+assert_eq!(ident.parse(&mut ParserString::from("1st")), Err(IdentErr::NotIdent));
+```
+*/
+pub fn ident_except(keywords: &KeywordSet) -> impl Parser<String, Err = IdentErr> + '_ {
+    Described::new(move |s: &mut ParserString| {
+        let rest = s.get();
+        if !rest.starts_with(is_ident_start) {
+            s.expect(s.start(), Expectation::Named("identifier".to_owned()));
+            return Err(IdentErr::NotIdent)
+        }
+        let len = rest.find(|c| !is_ident_continue(c)).unwrap_or(rest.len());
+        if let Some(&keyword) = keywords.words.iter().find(|&&k| k == &rest[..len]) {
+            s.expect(s.start(), Expectation::Named("identifier".to_owned()));
+            return Err(IdentErr::Reserved { keyword })
+        }
+        Ok(s.take(rest[..len].chars().count()).to_owned())
+    }, Grammar::named("ident", Grammar::Opaque))
+}
+
+///Indicates that an [`ident_except`] parser has failed.
+#[derive(Debug, Clone, Copy, Error, FromNever, PartialEq, Eq)]
+pub enum IdentErr {
+    ///Parser failed because the input didn't start with an identifier
+    #[error("expected an identifier")]
+    NotIdent,
+    ///Parser failed because the identifier is reserved
+    #[error("{keyword:?} is reserved")]
+    Reserved {
+        ///The keyword that was found
+        keyword: &'static str,
+    },
+}
+
+/**
+Parses the longest keyword in `keywords` at the start of the input. Keywords ending in an
+identifier character only match at a word boundary, so `if` doesn't match the start of `iffy`.
+Fails with [`KeywordErr`] if no keyword matches, in which case nothing is consumed.
+```
+# use parsa::{Parser, ParserString};
+# use parsa::builtins::{keyword_of, KeywordSet};
+let keywords = KeywordSet::new(&["in", "int", "+", "+="]);
+let keyword = keyword_of(&keywords);
+let mut input = ParserString::from("int+=in");
+assert_eq!(keyword.parse(&mut input), Ok("int"));
+assert_eq!(keyword.parse(&mut input), Ok("+="));
+assert_eq!(keyword.parse(&mut input), Ok("in"));
+assert!(keyword.parse(&mut ParserString::from("inside")).is_err());
+```
+*/
+pub fn keyword_of(keywords: &KeywordSet) -> impl Parser<&'static str, Err = KeywordErr> + '_ {
+    let grammar = Grammar::Alt(keywords.iter().map(|k| Grammar::Literal(k.to_owned())).collect());
+    Described::new(move |s: &mut ParserString| {
+        match keywords.longest_match(s.get()) {
+            Some(keyword) => {
+                s.take(keyword.chars().count());
+                Ok(keyword)
+            },
+            None => {
+                for keyword in keywords.iter() {
+                    s.expect(s.start(), Expectation::Literal(keyword.to_owned()));
+                }
+                Err(KeywordErr)
+            },
+        }
+    }, grammar)
+}
+
+///Indicates that a [`keyword_of`] parser found no keyword.
+#[derive(Debug, Clone, Copy, Error, FromNever, PartialEq, Eq)]
+#[error("expected a keyword")]
+pub struct KeywordErr;
+
+/**
+Parses the longest of `ops` at the start of the input, returning which one matched. Unlike a
+chain of [`or`](Parser::or)s, the order of `ops` doesn't matter, so `>` never matches the start of
+`>>=`. Fails with [`OperatorErr`] if none match, in which case nothing is consumed.
+```
+# use parsa::{Parser, ParserString};
+# use parsa::builtins::operators;
+let op = operators(&["+", "+=", "->", ">", ">>", ">>="]);
+let mut input = ParserString::from(">>=->+");
+assert_eq!(op.parse(&mut input), Ok(">>="));
+assert_eq!(op.parse(&mut input), Ok("->"));
+assert_eq!(op.parse(&mut input), Ok("+"));
+assert!(op.parse(&mut input).is_err());
+```
+*/
+pub fn operators(ops: &[&'static str]) -> impl Parser<&'static str, Err = OperatorErr> {
+    let mut ops = ops.to_vec();
+    ops.sort_by_key(|op| std::cmp::Reverse(op.len()));
+    let grammar = Grammar::Alt(ops.iter().map(|op| Grammar::Literal((*op).to_owned())).collect());
+    Described::new(move |s: &mut ParserString| {
+        match ops.iter().find(|op| s.get().starts_with(**op)) {
+            Some(&op) => {
+                s.take(op.chars().count());
+                Ok(op)
+            },
+            None => {
+                for op in &ops {
+                    s.expect(s.start(), Expectation::Literal((*op).to_owned()));
+                }
+                Err(OperatorErr)
+            },
+        }
+    }, grammar)
+}
+
+///Indicates that an [`operators`] parser found no operator.
+#[derive(Debug, Clone, Copy, Error, FromNever, PartialEq, Eq)]
+#[error("expected an operator")]
+pub struct OperatorErr;
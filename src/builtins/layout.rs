@@ -0,0 +1,122 @@
+use std::convert::Infallible;
+
+use crate::{Parser, ParserString, grammar::{Grammar, Described}};
+use super::{take, TakeErr};
+
+/**
+What counts as trivia between tokens: which characters are whitespace, which comment styles
+exist, and whether newlines are significant. Parsers built with [`lexeme`](Layout::lexeme) and
+[`token`](Layout::token) skip trivia after themselves, so changing the layout changes trivia
+handling across a whole grammar.
+```
+# use parsa::{Parser, ParserString};
+# use parsa::builtins::{Layout, word, BuiltinErr};
+let layout = Layout::new().line_comment("//").block_comment("/*", "*/");
+let assign = layout.lexeme(word).convert_err::<BuiltinErr>()
+    .after(layout.token("="))
+    .chain(layout.lexeme(word));
+
+let mut input = ParserString::from("x /* the name */ = // comment\n  10 ");
+assert_eq!(assign.parse(&mut input).unwrap(), ("x".to_owned(), "10".to_owned()));
+assert!(input.is_empty());
+```
+*/
+#[derive(Debug, Clone)]
+pub struct Layout {
+    whitespace: fn(char) -> bool,
+    line_comments: Vec<&'static str>,
+    block_comments: Vec<(&'static str, &'static str)>,
+    significant_newlines: bool,
+}
+
+impl Default for Layout {
+    fn default() -> Self { Self::new() }
+}
+
+impl Layout {
+    ///Constructs a layout where any Unicode whitespace, including newlines, is trivia, and there
+    ///are no comments.
+    pub fn new() -> Self {
+        Self { whitespace: char::is_whitespace, line_comments: vec![], block_comments: vec![], significant_newlines: false }
+    }
+
+    ///Sets which characters are whitespace.
+    pub fn whitespace(mut self, pred: fn(char) -> bool) -> Self {
+        self.whitespace = pred;
+        self
+    }
+
+    ///Adds a comment style that runs from `start` to the end of the line. The line ending isn't
+    ///part of the comment.
+    pub fn line_comment(mut self, start: &'static str) -> Self {
+        self.line_comments.push(start);
+        self
+    }
+
+    ///Adds a comment style that runs from `open` to `close`. An unclosed comment runs to the end
+    ///of the input.
+    pub fn block_comment(mut self, open: &'static str, close: &'static str) -> Self {
+        self.block_comments.push((open, close));
+        self
+    }
+
+    ///Sets whether newlines are significant. If they are, `\n` is never trivia, so it can be
+    ///parsed as a token, and only block comments can span lines.
+    ///```
+    ///# use parsa::{Parser, ParserString};
+    ///# use parsa::builtins::{Layout, word};
+    ///let layout = Layout::new().significant_newlines(true);
+    ///let mut input = ParserString::from("a  \nb");
+    ///assert_eq!(layout.lexeme(word).parse(&mut input).unwrap(), "a");
+    ///assert_eq!(input.get(), "\nb");
+    ///```
+    pub fn significant_newlines(mut self, significant: bool) -> Self {
+        self.significant_newlines = significant;
+        self
+    }
+
+    ///Skips trivia, returning how many bytes were skipped.
+    pub fn skip(&self, s: &mut ParserString) -> usize {
+        let start = s.start();
+        loop {
+            let rest = s.get();
+            let blank = rest.find(|c| !(self.whitespace)(c) || (self.significant_newlines && c == '\n'))
+                .unwrap_or(rest.len());
+            let rest = &rest[blank..];
+
+            let comment = if let Some(start) = self.line_comments.iter().find(|c| rest.starts_with(**c)) {
+                start.len() + rest[start.len()..].find('\n').unwrap_or(rest.len() - start.len())
+            } else if let Some((open, close)) = self.block_comments.iter().find(|(o, _)| rest.starts_with(o)) {
+                open.len() + rest[open.len()..].find(close).map_or(rest.len() - open.len(), |end| end + close.len())
+            } else {
+                0
+            };
+            let comment = rest[..comment].strip_suffix('\r').map_or(comment, str::len);
+
+            if blank + comment == 0 {
+                return s.start() - start
+            }
+            unsafe { s.set_ptr(s.start() + blank + comment) }
+        }
+    }
+
+    ///A parser that skips trivia, returning how many bytes were skipped. This can never fail.
+    pub fn trivia(&self) -> impl Parser<usize, Err = Infallible> + '_ {
+        Described::new(move |s: &mut ParserString| Ok(self.skip(s)), Grammar::Seq(vec![]))
+    }
+
+    ///Runs `p`, then skips trivia after it.
+    pub fn lexeme<'a, T, P: Parser<T> + 'a>(&'a self, p: P) -> impl Parser<T, Err = P::Err> + 'a {
+        let grammar = p.grammar();
+        Described::new(move |s: &mut ParserString| {
+            let out = p.parse(s)?;
+            self.skip(s);
+            Ok(out)
+        }, grammar)
+    }
+
+    ///Takes `text`, then skips trivia after it.
+    pub fn token(&self, text: &'static str) -> impl Parser<&'static str, Err = TakeErr> + '_ {
+        self.lexeme(take(text))
+    }
+}
@@ -0,0 +1,168 @@
+use std::ops::Range;
+
+use thiserror::Error;
+use nevermore::FromNever;
+
+use crate::ParserString;
+
+///The parts of a numeric literal. See [`number_literal`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct NumberLiteral {
+    ///Whether a `-` sign was given
+    pub negative: bool,
+    ///The radix: 2, 8 or 16 from a `0b`, `0o` or `0x` prefix, 10 otherwise
+    pub radix: u32,
+    ///The digits before any fractional part, without separators
+    pub integer: String,
+    ///The digits after the `.`, without separators, if any
+    pub fraction: Option<String>,
+    ///The exponent after `e` or `E`, with its sign and without separators, if any
+    pub exponent: Option<String>,
+    ///A type suffix like `u8` or `f32`, if any
+    pub suffix: Option<String>,
+    ///Where the literal is, including its sign and suffix
+    pub span: Range<usize>,
+}
+
+impl NumberLiteral {
+    ///Returns `true` if the literal has a fractional part or exponent.
+    pub fn is_float(&self) -> bool {
+        self.fraction.is_some() || self.exponent.is_some()
+    }
+
+    ///The value of an integer literal, or [`None`] if it is a float or doesn't fit.
+    pub fn to_i128(&self) -> Option<i128> {
+        if self.is_float() {
+            return None
+        }
+        let n = i128::from_str_radix(&self.integer, self.radix).ok()?;
+        Some(if self.negative { -n } else { n })
+    }
+
+    ///The value of the literal as a float. Integers in other radixes are converted exactly if
+    ///they fit, and lose precision otherwise.
+    pub fn to_f64(&self) -> f64 {
+        let sign = if self.negative { -1.0 } else { 1.0 };
+        if self.radix != 10 {
+            return sign * u128::from_str_radix(&self.integer, self.radix).map_or(f64::INFINITY, |n| n as f64)
+        }
+        let mut text = self.integer.clone();
+        if let Some(fraction) = &self.fraction {
+            text.push('.');
+            text.push_str(fraction);
+        }
+        if let Some(exponent) = &self.exponent {
+            text.push('e');
+            text.push_str(exponent);
+        }
+        sign * text.parse::<f64>().expect("lexed a valid float")
+    }
+}
+
+//the digits of `radix` at the start of `text`, allowing `_` separators. returns the digits and
+//how many bytes they took up
+fn digits(text: &str, radix: u32) -> (String, usize) {
+    let len = text.find(|c: char| !(c == '_' || c.is_digit(radix))).unwrap_or(text.len());
+    (text[..len].replace('_', ""), len)
+}
+
+/**
+Lexes a numeric literal in one pass, in the style of Rust: an optional sign, an optional `0x`,
+`0o` or `0b` radix prefix, digits with optional `_` separators, and for decimals, an optional
+fractional part and exponent. A type suffix like `u8` or `f32` may follow. A `.` is only part of
+the literal if a digit follows it, so `1..2` and `1.max(2)` lex as `1`. Nothing is consumed on
+failure.
+```
+# use parsa::ParserString;
+# use parsa::builtins::{number_literal, NumberLiteralErr};
+let lit = number_literal(&mut ParserString::from("-1_000.5e-3f64")).unwrap();
+assert_eq!((lit.negative, lit.radix), (true, 10));
+assert_eq!((lit.integer.as_str(), lit.fraction.as_deref()), ("1000", Some("5")));
+assert_eq!((lit.exponent.as_deref(), lit.suffix.as_deref()), (Some("-3"), Some("f64")));
+assert_eq!(lit.span, 0..14);
+assert_eq!(lit.to_f64(), -1.0005);
+
+let lit = number_literal(&mut ParserString::from("0xFF_u8")).unwrap();
+assert_eq!((lit.radix, lit.to_i128(), lit.suffix.as_deref()), (16, Some(255), Some("u8")));
+
+let mut input = ParserString::from("1..2");
+assert_eq!(number_literal(&mut input).unwrap().to_i128(), Some(1));
+assert_eq!(input.get(), "..2");
+
+assert_eq!(number_literal(&mut ParserString::from("0x")), Err(NumberLiteralErr::NoDigits { offset: 2 }));
+assert_eq!(number_literal(&mut ParserString::from("2e+")), Err(NumberLiteralErr::NoExponent { offset: 3 }));
+```
+*/
+pub fn number_literal(s: &mut ParserString) -> Result<NumberLiteral, NumberLiteralErr> {
+    let start = s.start();
+    let text = s.get();
+    let mut at = 0;
+
+    let negative = text.starts_with('-');
+    if text.starts_with(['+', '-']) {
+        at += 1;
+    }
+    let radix = match text.get(at..at + 2) {
+        Some("0x") => 16,
+        Some("0o") => 8,
+        Some("0b") => 2,
+        _ => 10,
+    };
+    if radix != 10 {
+        at += 2;
+    }
+    if radix == 10 && !text[at..].starts_with(|c: char| c.is_ascii_digit()) {
+        return Err(NumberLiteralErr::NoDigits { offset: start + at })
+    }
+    let (integer, len) = digits(&text[at..], radix);
+    if integer.is_empty() {
+        return Err(NumberLiteralErr::NoDigits { offset: start + at })
+    }
+    at += len;
+
+    let mut fraction = None;
+    let mut exponent = None;
+    if radix == 10 {
+        if text[at..].starts_with('.') && text[at + 1..].starts_with(|c: char| c.is_ascii_digit()) {
+            let (digits, len) = digits(&text[at + 1..], 10);
+            fraction = Some(digits);
+            at += 1 + len;
+        }
+        if text[at..].starts_with(['e', 'E']) {
+            at += 1;
+            let sign = if text[at..].starts_with(['+', '-']) { at += 1; &text[at - 1..at] } else { "" };
+            let (digits, len) = digits(&text[at..], 10);
+            if digits.is_empty() {
+                return Err(NumberLiteralErr::NoExponent { offset: start + at })
+            }
+            exponent = Some(format!("{sign}{digits}"));
+            at += len;
+        }
+    }
+
+    let suffix_len = if text[at..].starts_with(|c: char| c.is_alphabetic() || c == '_') {
+        text[at..].find(|c: char| !(c.is_alphanumeric() || c == '_')).unwrap_or(text.len() - at)
+    } else { 0 };
+    let suffix = (suffix_len > 0).then(|| text[at..at + suffix_len].to_owned());
+    at += suffix_len;
+
+    unsafe { s.set_ptr(start + at) }
+    Ok(NumberLiteral { negative, radix, integer, fraction, exponent, suffix, span: start..start + at })
+}
+
+///Indicates that a [`number_literal`] parser has failed.
+#[derive(Debug, Clone, Copy, Error, FromNever, PartialEq, Eq)]
+pub enum NumberLiteralErr {
+    ///Parser failed because there were no digits
+    #[error("expected digits at offset {offset}")]
+    NoDigits {
+        ///Where the digits were expected
+        offset: usize,
+    },
+    ///Parser failed because an `e` wasn't followed by exponent digits
+    #[error("expected exponent digits at offset {offset}")]
+    NoExponent {
+        ///Where the digits were expected
+        offset: usize,
+    },
+}
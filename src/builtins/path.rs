@@ -0,0 +1,244 @@
+use std::path::PathBuf;
+
+use thiserror::Error;
+use nevermore::FromNever;
+
+use crate::ParserString;
+
+//characters that end a bare path or glob, besides whitespace
+fn is_shell_special(c: char) -> bool {
+    matches!(c, ';' | '|' | '&' | '<' | '>' | '(' | ')' | '$' | '`' | '"' | '\'')
+}
+
+/**
+Parses a filesystem path, either quoted with `"` or `'`, or bare. Bare paths stop at whitespace
+and characters the shell treats specially, like `;`, `|`, `>` or quotes, and `\` escapes the next
+character. Quoted paths may contain anything but their closing quote.
+```
+# use parsa::ParserString;
+# use parsa::builtins::{path, PathErr};
+# use std::path::PathBuf;
+let mut input = ParserString::from("src/main.rs>out.txt");
+assert_eq!(path(&mut input), Ok(PathBuf::from("src/main.rs")));
+assert_eq!(input.get(), ">out.txt");
+
+assert_eq!(path(&mut ParserString::from(r"my\ docs/a.txt")), Ok(PathBuf::from("my docs/a.txt")));
+assert_eq!(path(&mut ParserString::from("'C:\\Program Files'")), Ok(PathBuf::from("C:\\Program Files")));
+assert_eq!(path(&mut ParserString::from("\"open")), Err(PathErr::Unterminated { offset: 0 }));
+```
+*/
+pub fn path(s: &mut ParserString) -> Result<PathBuf, PathErr> {
+    let start = s.start();
+    let rest = s.get();
+    if let Some(quote) = rest.chars().next().filter(|&c| c == '"' || c == '\'') {
+        let Some(end) = rest[1..].find(quote) else {
+            return Err(PathErr::Unterminated { offset: start })
+        };
+        let out = PathBuf::from(&rest[1..end + 1]);
+        s.take(end + 2);
+        return Ok(out)
+    }
+
+    let mut out = String::new();
+    let mut chars = rest.char_indices().peekable();
+    let mut len = rest.len();
+    while let Some((i, c)) = chars.next() {
+        if c.is_whitespace() || is_shell_special(c) {
+            len = i;
+            break
+        }
+        match (c, chars.peek()) {
+            ('\\', Some(&(_, escaped))) => {
+                out.push(escaped);
+                chars.next();
+            },
+            (c, _) => out.push(c),
+        }
+    }
+    if len == 0 {
+        return Err(PathErr::Empty)
+    }
+    unsafe { s.set_ptr(start + len) }
+    Ok(PathBuf::from(out))
+}
+
+///Indicates that a [`path`] parser has failed.
+#[derive(Debug, Clone, Copy, Error, FromNever, PartialEq, Eq)]
+pub enum PathErr {
+    ///Parser failed because no path was found
+    #[error("expected a path")]
+    Empty,
+    ///Parser failed because a quote was never closed
+    #[error("unterminated quote at offset {offset}")]
+    Unterminated {
+        ///Where the quote started
+        offset: usize,
+    },
+}
+
+///A piece of a [`Glob`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum GlobToken {
+    ///Text that must match exactly, with escapes resolved
+    Literal(String),
+    ///`?`, matching any one character
+    One,
+    ///`*`, matching any run of characters within a path segment
+    Any,
+    ///`**`, matching any run of characters across path segments
+    Recursive,
+    ///`[...]`, matching one character in (or, if negated with `!` or `^`, not in) a set of
+    ///inclusive ranges. Single characters are ranges of one.
+    Class {
+        ///Whether the class is negated
+        negated: bool,
+        ///The ranges in the class
+        ranges: Vec<(char, char)>,
+    },
+    ///`{a,b}`, matching any of the alternatives
+    Alternatives(Vec<Glob>),
+}
+
+///A glob pattern. See [`glob`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Glob {
+    ///The pieces of the pattern, in order
+    pub tokens: Vec<GlobToken>,
+}
+
+/**
+Parses a glob pattern made of literal text, `?`, `*`, `**`, `[...]` classes, and `{a,b}`
+alternatives, which may nest. Like [`path`], bare patterns stop at whitespace and characters the
+shell treats specially, and `\` escapes the next character.
+```
+# use parsa::ParserString;
+# use parsa::builtins::{glob, Glob, GlobToken};
+let pattern = glob(&mut ParserString::from("src/**/*.{rs,t?ml}")).unwrap();
+assert_eq!(pattern.tokens, vec![
+    GlobToken::Literal("src/".into()),
+    GlobToken::Recursive,
+    GlobToken::Literal("/".into()),
+    GlobToken::Any,
+    GlobToken::Literal(".".into()),
+    GlobToken::Alternatives(vec![
+        Glob { tokens: vec![GlobToken::Literal("rs".into())] },
+        Glob { tokens: vec![GlobToken::Literal("t".into()), GlobToken::One, GlobToken::Literal("ml".into())] },
+    ]),
+]);
+
+let class = glob(&mut ParserString::from("[!a-c_]")).unwrap();
+assert_eq!(class.tokens, vec![GlobToken::Class { negated: true, ranges: vec![('a', 'c'), ('_', '_')] }]);
+```
+*/
+pub fn glob(s: &mut ParserString) -> Result<Glob, GlobErr> {
+    let cp = s.checkpoint();
+    let out = glob_tokens(s, false);
+    match out {
+        Ok(tokens) if tokens.is_empty() => Err(GlobErr::Empty),
+        Ok(tokens) => Ok(Glob { tokens }),
+        Err(e) => {
+            s.rewind(cp);
+            Err(e)
+        },
+    }
+}
+
+fn glob_tokens(s: &mut ParserString, in_braces: bool) -> Result<Vec<GlobToken>, GlobErr> {
+    let mut tokens = vec![];
+    let mut literal = String::new();
+    let flush = |tokens: &mut Vec<GlobToken>, literal: &mut String| if !literal.is_empty() {
+        tokens.push(GlobToken::Literal(std::mem::take(literal)));
+    };
+
+    loop {
+        let offset = s.start();
+        let Some(c) = s.get().chars().next() else { break };
+        if c.is_whitespace() || is_shell_special(c) || (in_braces && (c == ',' || c == '}')) {
+            break
+        }
+        s.take(1);
+        let token = match c {
+            '\\' => {
+                literal.push_str(s.try_take(1).unwrap_or("\\"));
+                continue
+            },
+            '?' => GlobToken::One,
+            '*' if s.get().starts_with('*') => {
+                s.take(1);
+                GlobToken::Recursive
+            },
+            '*' => GlobToken::Any,
+            '[' => class(s, offset)?,
+            '{' => {
+                let mut alts = vec![];
+                loop {
+                    alts.push(Glob { tokens: glob_tokens(s, true)? });
+                    match s.try_take(1) {
+                        Some(",") => (),
+                        Some("}") => break,
+                        _ => return Err(GlobErr::UnclosedBrace { offset }),
+                    }
+                }
+                GlobToken::Alternatives(alts)
+            },
+            c => {
+                literal.push(c);
+                continue
+            },
+        };
+        flush(&mut tokens, &mut literal);
+        tokens.push(token);
+    }
+    flush(&mut tokens, &mut literal);
+    Ok(tokens)
+}
+
+//the rest of a class, after its `[`
+fn class(s: &mut ParserString, offset: usize) -> Result<GlobToken, GlobErr> {
+    let negated = s.get().starts_with(['!', '^']);
+    if negated {
+        s.take(1);
+    }
+    let mut ranges = vec![];
+    loop {
+        let mut chars = s.get().chars();
+        let c = match chars.next() {
+            Some(']') if !ranges.is_empty() => {
+                s.take(1);
+                return Ok(GlobToken::Class { negated, ranges })
+            },
+            Some(c) => c,
+            None => return Err(GlobErr::UnclosedClass { offset }),
+        };
+        match (chars.next(), chars.next()) {
+            (Some('-'), Some(end)) if end != ']' => {
+                s.take(3);
+                ranges.push((c, end));
+            },
+            _ => {
+                s.take(1);
+                ranges.push((c, c));
+            },
+        }
+    }
+}
+
+///Indicates that a [`glob`] parser has failed.
+#[derive(Debug, Clone, Copy, Error, FromNever, PartialEq, Eq)]
+pub enum GlobErr {
+    ///Parser failed because no pattern was found
+    #[error("expected a glob pattern")]
+    Empty,
+    ///Parser failed because a `[` was never closed
+    #[error("unclosed `[` at offset {offset}")]
+    UnclosedClass {
+        ///Where the class started
+        offset: usize,
+    },
+    ///Parser failed because a `{` was never closed
+    #[error("unclosed `{{` at offset {offset}")]
+    UnclosedBrace {
+        ///Where the alternatives started
+        offset: usize,
+    },
+}
@@ -0,0 +1,349 @@
+use thiserror::Error;
+use nevermore::FromNever;
+
+use std::convert::Infallible;
+
+use crate::{Parser, ParserString, ParserInput, Input, Expectation, grammar::{Grammar, Builtin, Described}};
+
+///What a scanning builtin like [`take_until`], [`line`] or [`between_with`](super::between_with)
+///does with the delimiter it stops at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Delimiter {
+    ///Leave the delimiter in the string, for the next parser
+    #[default]
+    Keep,
+    ///Consume the delimiter, without including it in the output
+    Consume,
+}
+
+impl Delimiter {
+    //the grammar of a scan up to `delim`
+    pub(crate) fn grammar(self, text: Grammar, delim: Grammar) -> Grammar {
+        match self {
+            Self::Keep => text,
+            Self::Consume => text.seq(delim),
+        }
+    }
+}
+
+/**
+Takes everything up to the next occurrence of `delim`, not including it. `policy` decides whether
+the delimiter itself is consumed. Fails with [`TakeUntilErr`] if the delimiter never occurs, in
+which case nothing is consumed.
+```
+# use parsa::{Parser, ParserString};
+# use parsa::builtins::{take_until, Delimiter};
+let mut input = ParserString::from("a=1;b=2;rest");
+assert_eq!(take_until(";", Delimiter::Consume).parse(&mut input), Ok("a=1".to_owned()));
+assert_eq!(take_until(";", Delimiter::Keep).parse(&mut input), Ok("b=2".to_owned()));
+assert_eq!(input.get(), ";rest");
+
+assert!(take_until("#", Delimiter::Keep).parse(&mut input).is_err());
+assert_eq!(input.get(), ";rest");
+```
+*/
+pub fn take_until(delim: &'static str, policy: Delimiter) -> impl Parser<String, Err = TakeUntilErr> {
+    let grammar = policy.grammar(Grammar::Builtin(Builtin::Text), Grammar::Literal(delim.to_owned()));
+    Described::new(move |s: &mut ParserString| {
+        let Some(at) = s.get().find(delim) else {
+            s.expect(s.start() + s.len(), Expectation::Literal(delim.to_owned()));
+            return Err(TakeUntilErr { delimiter: delim })
+        };
+        let out = s.get()[..at].to_owned();
+        let skip = match policy {
+            Delimiter::Keep => at,
+            Delimiter::Consume => at + delim.len(),
+        };
+        unsafe { s.set_ptr(s.start() + skip) }
+        Ok(out)
+    }, grammar)
+}
+
+///Indicates that a [`take_until`] parser never found its delimiter.
+#[derive(Debug, Clone, Copy, Error, FromNever, PartialEq, Eq)]
+#[error("expected {delimiter:?} before the end of input")]
+pub struct TakeUntilErr {
+    ///The delimiter that was searched for
+    pub delimiter: &'static str,
+}
+
+/**
+Takes the rest of the current line, not including the line ending, which may be `\n`, `\r\n` or a
+lone `\r`. `policy` decides whether the line ending is consumed. The last line doesn't need a line ending,
+but fails with [`LineErr`] if nothing is left.
+```
+# use parsa::{Parser, ParserString};
+# use parsa::builtins::{line, Delimiter};
+let mut input = ParserString::from("first\r\nsecond\rthird");
+let lines = line(Delimiter::Consume).many().parse(&mut input).unwrap();
+assert_eq!(lines, ["first", "second", "third"]);
+
+let mut input = ParserString::from("first\nsecond");
+assert_eq!(line(Delimiter::Keep).parse(&mut input), Ok("first".to_owned()));
+assert_eq!(input.get(), "\nsecond");
+```
+*/
+pub fn line(policy: Delimiter) -> impl Parser<String, Err = LineErr> {
+    let grammar = policy.grammar(Grammar::named("line", Grammar::Opaque), Grammar::named("newline", Grammar::Opaque));
+    Described::new(move |s: &mut ParserString| {
+        if s.get().is_empty() {
+            return Err(LineErr)
+        }
+        let (text, ending) = match s.get().find(['\n', '\r']) {
+            Some(at) => (at, newline_len(&s.get()[at..])),
+            None => (s.len(), 0),
+        };
+        let out = s.get()[..text].to_owned();
+        let skip = match policy {
+            Delimiter::Keep => text,
+            Delimiter::Consume => text + ending,
+        };
+        unsafe { s.set_ptr(s.start() + skip) }
+        Ok(out)
+    }, grammar)
+}
+
+///Indicates that a [`line`] parser found no input left.
+#[derive(Debug, Clone, Copy, Error, FromNever, PartialEq, Eq)]
+#[error("no lines left")]
+pub struct LineErr;
+
+//the length of the line ending at the start of `text`, or 0
+fn newline_len(text: &str) -> usize {
+    match text.as_bytes() {
+        [b'\r', b'\n', ..] => 2,
+        [b'\n' | b'\r', ..] => 1,
+        _ => 0,
+    }
+}
+
+/**
+Parses a line ending: `\n`, `\r\n` or a lone `\r`. Use this instead of matching `"\n"` so
+grammars accept text from any platform, with spans that still point into the original text.
+```
+# use parsa::{Parser, ParserString};
+# use parsa::builtins::{newline, word, NewlineErr, BuiltinErr};
+let mut input = ParserString::from("a\r\nb\rc\nd");
+let words = word.convert_err::<BuiltinErr>().after(newline).many().parse(&mut input).unwrap();
+assert_eq!(words, ["a", "b", "c"]);
+assert_eq!(newline(&mut input), Err(NewlineErr));
+```
+*/
+pub fn newline(s: &mut ParserString) -> Result<(), NewlineErr> {
+    match newline_len(s.get()) {
+        0 => {
+            s.expect(s.start(), Expectation::Named("newline".to_owned()));
+            Err(NewlineErr)
+        },
+        len => {
+            unsafe { s.set_ptr(s.start() + len) }
+            Ok(())
+        },
+    }
+}
+
+///Indicates that a [`newline`] parser didn't find a line ending.
+#[derive(Debug, Clone, Copy, Error, FromNever, PartialEq, Eq)]
+#[error("expected a line ending")]
+pub struct NewlineErr;
+
+/**
+Fails unless at least `n` more items remain, consuming nothing either way. Items are characters
+for text, and whatever the input is indexed by otherwise, like bytes for
+[`ParserBytes`](crate::ParserBytes). Useful to check for a fixed-size trailer up front.
+```
+# use parsa::{Parser, ParserBytes};
+# use parsa::builtins::{remaining, u8, RemainingErr, BuiltinErr};
+let mut input = ParserBytes::from(&[1, 2, 3]);
+assert_eq!(remaining(4).parse(&mut input), Err(RemainingErr { needed: 4 }));
+assert_eq!(remaining(3).convert_err::<BuiltinErr>().replace(u8).parse(&mut input), Ok(1));
+```
+*/
+pub fn remaining<I: ?Sized + Input>(n: usize) -> impl Parser<(), I, Err = RemainingErr> {
+    move |s: &mut ParserInput<I>| match s.region_end(n) {
+        Some(_) => Ok(()),
+        None => Err(RemainingErr { needed: n }),
+    }
+}
+
+///Indicates that a [`remaining`] parser found too little input left.
+#[derive(Debug, Clone, Copy, Error, FromNever, PartialEq, Eq)]
+#[error("expected at least {needed} more items")]
+pub struct RemainingErr {
+    ///How many items were needed
+    pub needed: usize,
+}
+
+/**
+Returns the [length](ParserInput::len) of the remaining input, without consuming anything. This
+can never fail.
+```
+# use parsa::{Parser, ParserString};
+# use parsa::builtins::{remaining_len, word, BuiltinErr};
+//a word that must be the last thing in the input
+let last = word.convert_err::<BuiltinErr>().chain(remaining_len)
+    .and_then(|(w, left)| if left == 0 { Ok(w) } else { Err(BuiltinErr::from(())) });
+assert!(last.parse(&mut ParserString::from("end")).is_ok());
+assert!(last.parse(&mut ParserString::from("end ")).is_err());
+```
+*/
+pub fn remaining_len<I: ?Sized + Input>(s: &mut ParserInput<I>) -> Result<usize, Infallible> {
+    Ok(s.len())
+}
+
+/**
+Returns the current [offset](ParserInput::start) into the input, without consuming anything. This
+can never fail, and is useful for recording where something began.
+```
+# use parsa::{Parser, ParserString};
+# use parsa::builtins::{position, word, whitespace, WordErr};
+let words = position.convert_err::<WordErr>().chain(word).after(whitespace).many();
+let found = words.parse(&mut ParserString::from("ab cd")).unwrap();
+assert_eq!(found, [(0, "ab".to_owned()), (3, "cd".to_owned())]);
+```
+*/
+pub fn position<I: ?Sized + Input>(s: &mut ParserInput<I>) -> Result<usize, Infallible> {
+    Ok(s.start())
+}
+
+/**
+Takes between `min` and `max` characters that satisfy `pred`, stopping early at the first one
+that doesn't. Fails with [`TakeWhileErr`] if fewer than `min` match, in which case nothing is
+consumed.
+```
+# use parsa::{Parser, ParserString};
+# use parsa::builtins::{take_while_m_n, TakeWhileErr};
+let digits = take_while_m_n(2, 4, |c| c.is_ascii_digit());
+let mut input = ParserString::from("123456");
+assert_eq!(digits.parse(&mut input), Ok("1234".to_owned()));
+assert_eq!(digits.parse(&mut input), Ok("56".to_owned()));
+assert_eq!(digits.parse(&mut input), Err(TakeWhileErr { min: 2, found: 0 }));
+```
+*/
+pub fn take_while_m_n(min: usize, max: usize, pred: impl Fn(char) -> bool) -> impl Parser<String, Err = TakeWhileErr> {
+    let grammar = Grammar::repeat(Grammar::named("char", Grammar::Opaque), min, Some(max));
+    Described::new(move |s: &mut ParserString| {
+        let found = s.get().chars().take(max).take_while(|&c| pred(c)).count();
+        if found < min {
+            return Err(TakeWhileErr { min, found })
+        }
+        Ok(s.take(found).to_owned())
+    }, grammar)
+}
+
+///Indicates that a [`take_while_m_n`] parser matched too few characters.
+#[derive(Debug, Clone, Copy, Error, FromNever, PartialEq, Eq)]
+#[error("expected at least {min} matching characters, found {found}")]
+pub struct TakeWhileErr {
+    ///The minimum amount of characters
+    pub min: usize,
+    ///How many matched
+    pub found: usize,
+}
+
+/**
+Searches for the first place `p` matches, skipping one item at a time until it does. Returns the
+match and the offset it started at. Fails with [`FindErr`] if `p` never matches, in which case
+nothing is consumed.
+```
+# use parsa::{Parser, ParserString};
+# use parsa::builtins::{find, take};
+let mut input = ParserString::from("12:01 WARN disk full");
+assert_eq!(find(take("WARN")).parse(&mut input), Ok(("WARN", 6)));
+assert_eq!(input.get(), " disk full");
+
+assert!(find(take("ERROR")).parse(&mut input).is_err());
+assert_eq!(input.get(), " disk full");
+```
+*/
+pub fn find<T, I: ?Sized + Input, P: Parser<T, I>>(p: P) -> impl Parser<(T, usize), I, Err = FindErr> {
+    let grammar = Grammar::repeat(Grammar::Opaque, 0, None).seq(p.grammar());
+    Described::new(move |s: &mut ParserInput<I>| {
+        let start = s.checkpoint();
+        loop {
+            let at = s.start();
+            if let Ok(v) = p.try_parse(s) {
+                return Ok((v, at))
+            }
+            match s.region_end(1) {
+                Some(next) => s.ptr = next,
+                None => {
+                    s.rewind(start);
+                    return Err(FindErr)
+                },
+            }
+        }
+    }, grammar)
+}
+
+///Indicates that a [`find`] parser never matched.
+#[derive(Debug, Clone, Copy, Error, FromNever, PartialEq, Eq)]
+#[error("pattern not found")]
+pub struct FindErr;
+
+/**
+Returns the next `n` characters without consuming them, or fewer if the string ends first. This
+can never fail, and lets the upcoming text decide what to parse next.
+```
+# use parsa::{Parser, ParserString};
+# use parsa::builtins::{lookahead, BuiltinErr};
+let number = lookahead(2).convert_err::<BuiltinErr>().and_then(|next| match next.as_str() {
+    "0x" => Ok(16),
+    _ => Ok::<_, BuiltinErr>(10),
+});
+let mut input = ParserString::from("0xff");
+assert_eq!(number.parse(&mut input), Ok(16));
+assert_eq!(input.get(), "0xff");
+```
+*/
+pub fn lookahead(n: usize) -> impl Parser<String, Err = Infallible> {
+    Described::new(move |s: &mut ParserString| Ok(s.peek(n).to_owned()), Grammar::Seq(vec![]))
+}
+
+/**
+Consumes a run of text, decoding escape sequences along the way. Whenever the next character is
+`escape`, it is consumed and `mapping` parses what follows into its replacement. Otherwise
+`normal` parses a run of plain text. Stops when `normal` fails or consumes nothing, and fails
+with `mapping`'s error if an escape sequence isn't recognized.
+```
+# use parsa::{Parser, ParserString};
+# use parsa::builtins::escaped_transform;
+# use std::convert::Infallible;
+let plain = |s: &mut ParserString| -> Result<String, Infallible> {
+    let n = s.get().chars().take_while(|c| !matches!(c, '\\' | '"')).count();
+    Ok(s.take(n).to_owned())
+};
+let escapes = 'n'.map(|_| "\n").or('t'.map(|_| "\t")).or('\\'.map(|_| "\\"));
+let text = escaped_transform(plain, '\\', escapes);
+
+let mut input = ParserString::from(r#"a\tb\\n\n" rest"#);
+assert_eq!(text.parse(&mut input), Ok("a\tb\\n\n".to_owned()));
+assert_eq!(input.get(), r#"" rest"#);
+
+assert!(text.parse(&mut ParserString::from(r"\q")).is_err());
+```
+*/
+pub fn escaped_transform<A, B, N, M>(normal: N, escape: char, mapping: M) -> impl Parser<String, Err = M::Err>
+where
+    A: AsRef<str>,
+    B: AsRef<str>,
+    N: Parser<A>,
+    M: Parser<B>,
+{
+    let grammar = Grammar::repeat(normal.grammar().alt(Grammar::Literal(escape.to_string()).seq(mapping.grammar())), 0, None);
+    Described::new(move |s: &mut ParserString| {
+        let mut out = String::new();
+        loop {
+            if s.get().starts_with(escape) {
+                s.take(1);
+                out.push_str(mapping.parse(s)?.as_ref());
+                continue
+            }
+            let start = s.start();
+            match normal.try_parse(s) {
+                Ok(text) if s.start() > start => out.push_str(text.as_ref()),
+                _ => return Ok(out),
+            }
+        }
+    }, grammar)
+}
@@ -0,0 +1,88 @@
+use thiserror::Error;
+use nevermore::FromNever;
+
+use crate::{Parser, ParserString, Expectation, grammar::{Grammar, Builtin, Described}};
+
+///A piece of a template. See [`template`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Segment {
+    ///Text outside of placeholders, with escapes resolved
+    Literal(String),
+    ///The expression inside a placeholder, with surrounding whitespace trimmed
+    Placeholder(String),
+}
+
+/**
+Splits the rest of the input into literal text and placeholders delimited by `open` and `close`,
+like `{{ name }}`. A `\` directly before `open` escapes it, so `\{{` is the literal text `{{`; any
+other `\` is kept as is. Literal segments are never empty, so literals and placeholders alternate
+except where two placeholders are adjacent. Fails with [`TemplateErr`] if a placeholder is never
+closed.
+```
+# use parsa::{Parser, ParserString};
+# use parsa::builtins::{template, Segment};
+let mut input = ParserString::from(r"Hi {{ user.name }}! \{{ is literal");
+assert_eq!(template("{{", "}}").parse(&mut input), Ok(vec![
+    Segment::Literal("Hi ".into()),
+    Segment::Placeholder("user.name".into()),
+    Segment::Literal("! {{ is literal".into()),
+]));
+
+let mut input = ParserString::from("${a}${b}");
+assert_eq!(template("${", "}").parse(&mut input), Ok(vec![
+    Segment::Placeholder("a".into()),
+    Segment::Placeholder("b".into()),
+]));
+```
+*/
+pub fn template(open: &'static str, close: &'static str) -> impl Parser<Vec<Segment>, Err = TemplateErr> {
+    let placeholder = Grammar::Literal(open.to_owned())
+        .seq(Grammar::named("expr", Grammar::Opaque))
+        .seq(Grammar::Literal(close.to_owned()));
+    let grammar = Grammar::repeat(Grammar::Builtin(Builtin::Text).alt(placeholder), 0, None);
+
+    Described::new(move |s: &mut ParserString| {
+        let mut out = vec![];
+        let mut literal = String::new();
+        loop {
+            let rest = s.get();
+            let Some(at) = rest.find(open) else {
+                literal.push_str(rest);
+                unsafe { s.set_ptr(s.start() + rest.len()) }
+                break
+            };
+
+            if rest[..at].ends_with('\\') {
+                literal.push_str(&rest[..at - 1]);
+                literal.push_str(open);
+                unsafe { s.set_ptr(s.start() + at + open.len()) }
+                continue
+            }
+
+            literal.push_str(&rest[..at]);
+            let offset = s.start() + at;
+            let body = &rest[at + open.len()..];
+            let Some(end) = body.find(close) else {
+                s.expect(s.start() + rest.len(), Expectation::Literal(close.to_owned()));
+                return Err(TemplateErr { offset })
+            };
+            if !literal.is_empty() {
+                out.push(Segment::Literal(std::mem::take(&mut literal)));
+            }
+            out.push(Segment::Placeholder(body[..end].trim().to_owned()));
+            unsafe { s.set_ptr(offset + open.len() + end + close.len()) }
+        }
+        if !literal.is_empty() {
+            out.push(Segment::Literal(literal));
+        }
+        Ok(out)
+    }, grammar)
+}
+
+///Indicates that a [`template`] placeholder was never closed.
+#[derive(Debug, Clone, Copy, Error, FromNever, PartialEq, Eq)]
+#[error("unclosed placeholder at offset {offset}")]
+pub struct TemplateErr {
+    ///Where the placeholder was opened
+    pub offset: usize,
+}
@@ -0,0 +1,239 @@
+use thiserror::Error;
+use nevermore::FromNever;
+
+use crate::ParserString;
+
+///A calendar date and time, as found in logs. See [`syslog_time`], [`apache_time`] and
+///[`epoch_time`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Timestamp {
+    ///The year, if the format includes one
+    pub year: Option<i32>,
+    ///The month, from 1
+    pub month: u8,
+    ///The day of the month, from 1
+    pub day: u8,
+    ///The hour, from 0 to 23
+    pub hour: u8,
+    ///The minute
+    pub minute: u8,
+    ///The second, which may be 60 for a leap second
+    pub second: u8,
+    ///Nanoseconds past the second
+    pub nanos: u32,
+    ///The offset from UTC in seconds, if the format includes one
+    pub offset: Option<i32>,
+}
+
+const MONTHS: [&str; 12] = ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"];
+
+//days since 1970-01-01 of a proleptic Gregorian date
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year - era * 400;
+    let doy = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+//the inverse of days_from_civil
+fn civil_from_days(days: i64) -> (i64, u8, u8) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u8;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u8;
+    (yoe + era * 400 + i64::from(month <= 2), month, day)
+}
+
+fn days_in_month(year: Option<i32>, month: u8) -> u8 {
+    match month {
+        2 => match year {
+            Some(y) if !(y % 4 == 0 && (y % 100 != 0 || y % 400 == 0)) => 28,
+            _ => 29,
+        },
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+impl Timestamp {
+    ///Seconds since the Unix epoch, or [`None`] if the year is unknown. A missing offset is
+    ///taken to be UTC.
+    ///```
+    ///# use parsa::ParserString;
+    ///# use parsa::builtins::apache_time;
+    ///let t = apache_time(&mut ParserString::from("[10/Oct/2000:13:55:36 -0700]")).unwrap();
+    ///assert_eq!(t.unix(), Some(971211336));
+    ///```
+    pub fn unix(&self) -> Option<i64> {
+        let days = days_from_civil(self.year?.into(), self.month.into(), self.day.into());
+        let secs = days * 86400 + i64::from(self.hour) * 3600 + i64::from(self.minute) * 60 + i64::from(self.second);
+        Some(secs - i64::from(self.offset.unwrap_or(0)))
+    }
+
+    fn is_valid(&self) -> bool {
+        (1..=12).contains(&self.month)
+            && self.day >= 1 && self.day <= days_in_month(self.year, self.month)
+            && self.hour < 24 && self.minute < 60 && self.second <= 60
+    }
+}
+
+///Indicates that a timestamp parser has failed. Nothing is consumed on failure.
+#[derive(Debug, Clone, Copy, Error, FromNever, PartialEq, Eq)]
+#[error("invalid timestamp at offset {offset}")]
+pub struct TimeErr {
+    ///Where the timestamp started
+    pub offset: usize,
+}
+
+//a small cursor over the remaining text, for the fixed layouts below
+struct Cursor<'a> {
+    text: &'a str,
+    at: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn lit(&mut self, lit: &str) -> Option<()> {
+        self.text[self.at..].starts_with(lit).then(|| self.at += lit.len())
+    }
+
+    //between `min` and `max` digits
+    fn num(&mut self, min: usize, max: usize) -> Option<u32> {
+        let n = self.text[self.at..].bytes().take(max).take_while(u8::is_ascii_digit).count();
+        if n < min {
+            return None
+        }
+        let out = self.text[self.at..self.at + n].parse().ok();
+        self.at += n;
+        out
+    }
+
+    fn month(&mut self) -> Option<u8> {
+        let i = MONTHS.iter().position(|m| self.text[self.at..].starts_with(m))?;
+        self.at += 3;
+        Some(i as u8 + 1)
+    }
+
+    fn clock(&mut self, t: &mut Timestamp) -> Option<()> {
+        t.hour = self.num(2, 2)? as u8;
+        self.lit(":")?;
+        t.minute = self.num(2, 2)? as u8;
+        self.lit(":")?;
+        t.second = self.num(2, 2)? as u8;
+        Some(())
+    }
+}
+
+//runs a layout over the input, consuming what it matched if it produced a valid timestamp
+fn layout(s: &mut ParserString, f: impl FnOnce(&mut Cursor) -> Option<Timestamp>) -> Result<Timestamp, TimeErr> {
+    let mut cursor = Cursor { text: s.get(), at: 0 };
+    match f(&mut cursor).filter(Timestamp::is_valid) {
+        Some(t) => {
+            let len = cursor.at;
+            unsafe { s.set_ptr(s.start() + len) }
+            Ok(t)
+        },
+        None => Err(TimeErr { offset: s.start() }),
+    }
+}
+
+/**
+Parses a syslog timestamp like `Jan  2 15:04:05`, where the day may be padded with a space or
+not. Syslog timestamps have no year or offset.
+```
+# use parsa::ParserString;
+# use parsa::builtins::{syslog_time, Timestamp};
+let mut input = ParserString::from("Jan  2 15:04:05 host sshd[42]: ...");
+let t = syslog_time(&mut input).unwrap();
+assert_eq!((t.month, t.day, t.hour, t.minute, t.second), (1, 2, 15, 4, 5));
+assert_eq!((t.year, t.offset), (None, None));
+assert_eq!(input.get(), " host sshd[42]: ...");
+```
+*/
+pub fn syslog_time(s: &mut ParserString) -> Result<Timestamp, TimeErr> {
+    layout(s, |c| {
+        let mut t = Timestamp { month: c.month()?, ..Timestamp::default() };
+        c.lit(" ")?;
+        let _ = c.lit(" ");
+        t.day = c.num(1, 2)? as u8;
+        c.lit(" ")?;
+        c.clock(&mut t)?;
+        Some(t)
+    })
+}
+
+/**
+Parses an Apache access log timestamp like `[10/Oct/2000:13:55:36 -0700]`, including the
+brackets.
+```
+# use parsa::ParserString;
+# use parsa::builtins::{apache_time, TimeErr};
+let t = apache_time(&mut ParserString::from("[10/Oct/2000:13:55:36 -0700]")).unwrap();
+assert_eq!((t.year, t.month, t.day), (Some(2000), 10, 10));
+assert_eq!(t.offset, Some(-7 * 3600));
+assert_eq!(apache_time(&mut ParserString::from("[31/Apr/2000:13:55:36 +0000]")), Err(TimeErr { offset: 0 }));
+```
+*/
+pub fn apache_time(s: &mut ParserString) -> Result<Timestamp, TimeErr> {
+    layout(s, |c| {
+        c.lit("[")?;
+        let day = c.num(2, 2)? as u8;
+        c.lit("/")?;
+        let month = c.month()?;
+        c.lit("/")?;
+        let year = c.num(4, 4)? as i32;
+        let mut t = Timestamp { year: Some(year), month, day, ..Timestamp::default() };
+        c.lit(":")?;
+        c.clock(&mut t)?;
+        c.lit(" ")?;
+        let sign = if c.lit("-").is_some() { -1 } else { c.lit("+").map(|_| 1)? };
+        let hours = c.num(2, 2)? as i32;
+        let minutes = c.num(2, 2)? as i32;
+        t.offset = Some(sign * (hours * 3600 + minutes * 60));
+        c.lit("]")?;
+        Some(t)
+    })
+}
+
+/**
+Parses seconds since the Unix epoch, with an optional fractional part of up to 9 digits, into a
+UTC timestamp.
+```
+# use parsa::ParserString;
+# use parsa::builtins::epoch_time;
+let t = epoch_time(&mut ParserString::from("971211336.25")).unwrap();
+assert_eq!((t.year, t.month, t.day, t.hour), (Some(2000), 10, 10, 20));
+assert_eq!((t.nanos, t.offset), (250_000_000, Some(0)));
+assert_eq!(t.unix(), Some(971211336));
+```
+*/
+pub fn epoch_time(s: &mut ParserString) -> Result<Timestamp, TimeErr> {
+    layout(s, |c| {
+        let n = c.text.bytes().take_while(u8::is_ascii_digit).count();
+        let secs: i64 = c.text[..n].parse().ok()?;
+        c.at = n;
+        let mut nanos = 0;
+        if c.text[n..].starts_with('.') && c.text[n + 1..].starts_with(|c: char| c.is_ascii_digit()) {
+            c.at += 1;
+            let start = c.at;
+            nanos = c.num(1, 9)?;
+            nanos *= 10u32.pow(9 - (c.at - start) as u32);
+        }
+        let (year, month, day) = civil_from_days(secs.div_euclid(86400));
+        let time = secs.rem_euclid(86400);
+        Some(Timestamp {
+            year: Some(i32::try_from(year).ok()?),
+            month, day,
+            hour: (time / 3600) as u8,
+            minute: (time / 60 % 60) as u8,
+            second: (time % 60) as u8,
+            nanos,
+            offset: Some(0),
+        })
+    })
+}
@@ -0,0 +1,318 @@
+/*!
+Parsers for a subset of TOML: bare and quoted keys, basic strings, integers, floats, booleans,
+arrays, and `[table]` headers. Inline tables, literal and multi-line strings, dates, and dotted
+keys outside of headers are not supported.
+
+Requires the `toml` feature.
+```
+# use parsa::ParserString;
+# use parsa::builtins::toml::{document, Value};
+let text = r#"
+title = "demo"   # a comment
+ports = [8080, 8081]
+
+[server.tls]
+enabled = true
+"#;
+let doc = document(&mut ParserString::from(text)).unwrap();
+assert_eq!(doc["title"], Value::String("demo".into()));
+assert_eq!(doc["ports"], Value::Array(vec![Value::Integer(8080), Value::Integer(8081)]));
+assert_eq!(doc["server"].get("tls").and_then(|t| t.get("enabled")), Some(&Value::Bool(true)));
+```
+*/
+use std::collections::BTreeMap;
+
+use thiserror::Error;
+use nevermore::FromNever;
+
+use crate::ParserString;
+
+///A TOML table, with its keys in sorted order.
+pub type Table = BTreeMap<String, Value>;
+
+///A TOML value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    ///A basic string, with escapes resolved
+    String(String),
+    ///An integer
+    Integer(i64),
+    ///A float
+    Float(f64),
+    ///A boolean
+    Bool(bool),
+    ///An array, whose items may have different types
+    Array(Vec<Value>),
+    ///A table
+    Table(Table),
+}
+
+impl Value {
+    ///If this is a table, get the value of `key` in it.
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        match self {
+            Self::Table(t) => t.get(key),
+            _ => None,
+        }
+    }
+}
+
+///Indicates that a TOML parser has failed.
+#[derive(Debug, Clone, Error, FromNever, PartialEq, Eq)]
+pub enum TomlErr {
+    ///Parser failed because something else was found
+    #[error("expected {expected} at offset {offset}")]
+    Expected {
+        ///What was expected
+        expected: &'static str,
+        ///Where it was expected
+        offset: usize,
+    },
+    ///Parser failed because a number was out of range or malformed
+    #[error("invalid number at offset {offset}")]
+    Number {
+        ///Where the number started
+        offset: usize,
+    },
+    ///Parser failed because a string contained an unknown escape
+    #[error("invalid escape at offset {offset}")]
+    Escape {
+        ///Where the escape started
+        offset: usize,
+    },
+    ///Parser failed because a key was defined twice, or a table header named a value that isn't
+    ///a table
+    #[error("`{key}` is already defined, at offset {offset}")]
+    Redefined {
+        ///The key
+        key: String,
+        ///Where it was defined again
+        offset: usize,
+    },
+}
+
+fn expected(s: &ParserString, expected: &'static str) -> TomlErr {
+    TomlErr::Expected { expected, offset: s.start() }
+}
+
+//consumes characters while `pred` holds
+fn take_while(s: &mut ParserString, pred: impl Fn(char) -> bool) -> &str {
+    let n = s.get().chars().take_while(|&c| pred(c)).count();
+    s.take(n)
+}
+
+//spaces and tabs
+fn blank(s: &mut ParserString) {
+    take_while(s, |c| c == ' ' || c == '\t');
+}
+
+//whitespace, newlines and comments
+fn trivia(s: &mut ParserString) {
+    loop {
+        take_while(s, char::is_whitespace);
+        if !s.get().starts_with('#') {
+            return
+        }
+        take_while(s, |c| c != '\n');
+    }
+}
+
+/**
+Parses a bare key (`A-Z a-z 0-9 _ -`) or a quoted key.
+```
+# use parsa::ParserString;
+# use parsa::builtins::toml::key;
+assert_eq!(key(&mut ParserString::from("max-size = 1")), Ok("max-size".to_owned()));
+assert_eq!(key(&mut ParserString::from(r#""a b" = 1"#)), Ok("a b".to_owned()));
+```
+*/
+pub fn key(s: &mut ParserString) -> Result<String, TomlErr> {
+    if s.get().starts_with('"') {
+        return string(s)
+    }
+    let key = take_while(s, |c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if key.is_empty() {
+        return Err(expected(s, "a key"))
+    }
+    Ok(key.to_owned())
+}
+
+/**
+Parses a basic string, resolving the escapes `\" \\ \n \t \r \b \f` and `\uXXXX`.
+```
+# use parsa::ParserString;
+# use parsa::builtins::toml::string;
+assert_eq!(string(&mut ParserString::from(r#""tab\there""#)), Ok("tab\there".to_owned()));
+```
+*/
+pub fn string(s: &mut ParserString) -> Result<String, TomlErr> {
+    if s.try_take(1) != Some("\"") {
+        return Err(expected(s, "a string"))
+    }
+    let mut out = String::new();
+    loop {
+        let offset = s.start();
+        let c = s.get().chars().next().filter(|&c| c != '\n').ok_or_else(|| expected(s, "a closing `\"`"))?;
+        s.take(1);
+        match c {
+            '"' => return Ok(out),
+            '\\' => {
+                let escape = s.get().chars().next();
+                s.try_take(1);
+                let c = match escape {
+                    Some('"') => '"',
+                    Some('\\') => '\\',
+                    Some('n') => '\n',
+                    Some('t') => '\t',
+                    Some('r') => '\r',
+                    Some('b') => '\u{8}',
+                    Some('f') => '\u{c}',
+                    Some('u') => s.try_take(4)
+                        .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                        .and_then(char::from_u32)
+                        .ok_or(TomlErr::Escape { offset })?,
+                    _ => return Err(TomlErr::Escape { offset }),
+                };
+                out.push(c);
+            },
+            c => out.push(c),
+        }
+    }
+}
+
+//an integer or a float
+fn number(s: &mut ParserString) -> Result<Value, TomlErr> {
+    let offset = s.start();
+    let text = take_while(s, |c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.' | '_'))
+        .replace('_', "");
+    let invalid = TomlErr::Number { offset };
+    if text.is_empty() {
+        return Err(expected(s, "a value"))
+    }
+    if text.contains(['.', 'e', 'E']) || text.ends_with("inf") || text.ends_with("nan") {
+        text.parse().map(Value::Float).map_err(|_| invalid)
+    } else {
+        text.parse().map(Value::Integer).map_err(|_| invalid)
+    }
+}
+
+/**
+Parses a single value.
+```
+# use parsa::ParserString;
+# use parsa::builtins::toml::{value, Value};
+assert_eq!(value(&mut ParserString::from("1_000")), Ok(Value::Integer(1000)));
+assert_eq!(value(&mut ParserString::from("-0.5")), Ok(Value::Float(-0.5)));
+assert_eq!(value(&mut ParserString::from("[[1], []]")), Ok(Value::Array(vec![
+    Value::Array(vec![Value::Integer(1)]),
+    Value::Array(vec![]),
+])));
+```
+*/
+pub fn value(s: &mut ParserString) -> Result<Value, TomlErr> {
+    let rest = s.get();
+    if rest.starts_with('"') {
+        string(s).map(Value::String)
+    } else if rest.starts_with('[') {
+        array(s).map(Value::Array)
+    } else if rest.starts_with("true") {
+        s.take(4);
+        Ok(Value::Bool(true))
+    } else if rest.starts_with("false") {
+        s.take(5);
+        Ok(Value::Bool(false))
+    } else {
+        number(s)
+    }
+}
+
+//an array, which may span lines and have a trailing comma
+fn array(s: &mut ParserString) -> Result<Vec<Value>, TomlErr> {
+    s.take(1);
+    let mut out = vec![];
+    loop {
+        trivia(s);
+        if s.get().starts_with(']') {
+            s.take(1);
+            return Ok(out)
+        }
+        out.push(value(s)?);
+        trivia(s);
+        match s.get().chars().next() {
+            Some(',') => { s.take(1); },
+            Some(']') => (),
+            _ => return Err(expected(s, "`,` or `]`")),
+        }
+    }
+}
+
+//the table at `path`, creating tables along the way
+fn table_at<'a>(root: &'a mut Table, path: &[String], offset: usize) -> Result<&'a mut Table, TomlErr> {
+    let mut table = root;
+    for key in path {
+        let next = table.entry(key.clone()).or_insert_with(|| Value::Table(Table::new()));
+        table = match next {
+            Value::Table(t) => t,
+            _ => return Err(TomlErr::Redefined { key: key.clone(), offset }),
+        };
+    }
+    Ok(table)
+}
+
+/**
+Parses a whole document into its root table. See the [module docs](self).
+```
+# use parsa::ParserString;
+# use parsa::builtins::toml::{document, TomlErr};
+let err = document(&mut ParserString::from("a = 1\na = 2")).unwrap_err();
+assert_eq!(err, TomlErr::Redefined { key: "a".into(), offset: 6 });
+```
+*/
+pub fn document(s: &mut ParserString) -> Result<Table, TomlErr> {
+    let mut root = Table::new();
+    let mut path: Vec<String> = vec![];
+    loop {
+        trivia(s);
+        if s.is_empty() {
+            return Ok(root)
+        }
+
+        let offset = s.start();
+        if s.get().starts_with('[') {
+            s.take(1);
+            path.clear();
+            loop {
+                blank(s);
+                path.push(key(s)?);
+                blank(s);
+                match s.try_take(1) {
+                    Some(".") => (),
+                    Some("]") => break,
+                    _ => return Err(expected(s, "`.` or `]`")),
+                }
+            }
+            table_at(&mut root, &path, offset)?;
+        } else {
+            let key = key(s)?;
+            blank(s);
+            if s.try_take(1) != Some("=") {
+                return Err(expected(s, "`=`"))
+            }
+            blank(s);
+            let value = value(s)?;
+            let table = table_at(&mut root, &path, offset)?;
+            if table.contains_key(&key) {
+                return Err(TomlErr::Redefined { key, offset })
+            }
+            table.insert(key, value);
+        }
+
+        blank(s);
+        if s.get().starts_with('#') {
+            take_while(s, |c| c != '\n');
+        }
+        if !s.is_empty() && !s.get().starts_with(['\n', '\r']) {
+            return Err(expected(s, "a new line"))
+        }
+    }
+}
@@ -0,0 +1,130 @@
+use thiserror::Error;
+use nevermore::FromNever;
+use unicode_general_category::get_general_category;
+
+use crate::{Parser, ParserString, grammar::{Grammar, Described}};
+
+pub use unicode_general_category::GeneralCategory;
+
+///A set of characters defined by Unicode, matched by [`char_class`]. Requires the
+///`unicode-classes` feature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CharClass {
+    ///Any letter (`L`)
+    Letter,
+    ///Any combining mark (`M`)
+    Mark,
+    ///Any number (`N`)
+    Number,
+    ///Any punctuation (`P`)
+    Punctuation,
+    ///Any symbol (`S`)
+    Symbol,
+    ///Any separator (`Z`)
+    Separator,
+    ///Control, format, private use, surrogate or unassigned (`C`)
+    Other,
+    ///One exact general category, like [`GeneralCategory::UppercaseLetter`]
+    Category(GeneralCategory),
+    ///Characters that can start an identifier, per Unicode Standard Annex #31
+    XidStart,
+    ///Characters that can continue an identifier, per Unicode Standard Annex #31
+    XidContinue,
+}
+
+impl CharClass {
+    ///Returns `true` if `c` is in this class.
+    ///```
+    ///# use parsa::builtins::{CharClass, GeneralCategory};
+    ///assert!(CharClass::Letter.contains('ж'));
+    ///assert!(CharClass::Number.contains('٣'));
+    ///assert!(CharClass::Category(GeneralCategory::CurrencySymbol).contains('€'));
+    ///assert!(!CharClass::XidStart.contains('1'));
+    ///```
+    pub fn contains(self, c: char) -> bool {
+        let major = || get_general_category(c).abbreviation().as_bytes()[0];
+        match self {
+            Self::Letter => major() == b'L',
+            Self::Mark => major() == b'M',
+            Self::Number => major() == b'N',
+            Self::Punctuation => major() == b'P',
+            Self::Symbol => major() == b'S',
+            Self::Separator => major() == b'Z',
+            Self::Other => major() == b'C',
+            Self::Category(cat) => get_general_category(c) == cat,
+            Self::XidStart => unicode_ident::is_xid_start(c),
+            Self::XidContinue => unicode_ident::is_xid_continue(c),
+        }
+    }
+
+    fn name(self) -> String {
+        match self {
+            Self::Category(cat) => cat.abbreviation().to_owned(),
+            class => format!("{class:?}"),
+        }
+    }
+}
+
+/**
+Takes one character in a Unicode [`CharClass`], failing with [`CharClassErr`] otherwise.
+Requires the `unicode-classes` feature.
+```
+# use parsa::{Parser, ParserString};
+# use parsa::builtins::{char_class, CharClass};
+let mut input = ParserString::from("πr²");
+let letters = char_class(CharClass::Letter).many().parse(&mut input).unwrap();
+assert_eq!(letters, ['π', 'r']);
+assert!(char_class(CharClass::Letter).parse(&mut input).is_err());
+assert_eq!(char_class(CharClass::Number).parse(&mut input), Ok('²'));
+```
+*/
+pub fn char_class(class: CharClass) -> impl Parser<char, Err = CharClassErr> {
+    Described::new(move |s: &mut ParserString| {
+        match s.get().chars().next() {
+            Some(c) if class.contains(c) => {
+                s.take(1);
+                Ok(c)
+            },
+            found => Err(CharClassErr { class, found }),
+        }
+    }, Grammar::named(class.name(), Grammar::Opaque))
+}
+
+/**
+Takes a Unicode identifier: an [`XidStart`](CharClass::XidStart) character or `_`, followed by
+any number of [`XidContinue`](CharClass::XidContinue) characters. This is the identifier syntax
+of Rust, Python and JavaScript, among others. Requires the `unicode-classes` feature.
+```
+# use parsa::ParserString;
+# use parsa::builtins::xid_identifier;
+let mut input = ParserString::from("größe_2 = 1");
+assert_eq!(xid_identifier(&mut input), Ok("größe_2".to_owned()));
+assert!(xid_identifier(&mut ParserString::from("2x")).is_err());
+```
+*/
+pub fn xid_identifier(s: &mut ParserString) -> Result<String, CharClassErr> {
+    let mut chars = s.get().chars();
+    match chars.next() {
+        Some(c) if c == '_' || CharClass::XidStart.contains(c) => {
+            let len = c.len_utf8() + chars
+                .take_while(|&c| CharClass::XidContinue.contains(c))
+                .map(char::len_utf8)
+                .sum::<usize>();
+            let out = s.get()[..len].to_owned();
+            unsafe { s.set_ptr(s.start() + len) }
+            Ok(out)
+        },
+        found => Err(CharClassErr { class: CharClass::XidStart, found }),
+    }
+}
+
+///Indicates that a [`char_class`] or [`xid_identifier`] parser didn't find a character in its
+///class.
+#[derive(Debug, Clone, Copy, Error, FromNever, PartialEq, Eq)]
+#[error("expected a character in {class:?}, found {found:?}")]
+pub struct CharClassErr {
+    ///The class that was expected
+    pub class: CharClass,
+    ///The next character, if there was one
+    pub found: Option<char>,
+}
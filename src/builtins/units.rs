@@ -0,0 +1,209 @@
+use std::time::Duration;
+
+use thiserror::Error;
+use nevermore::FromNever;
+
+use crate::{Parser, ParserString, grammar::{Grammar, Described}};
+
+///A number followed by a unit, like `3.5GiB`. See [`quantity`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Quantity {
+    ///The numeric part
+    pub value: f64,
+    ///The unit, which is empty if none was given
+    pub unit: String,
+}
+
+///Units of data, from `b` for bytes through `tib` for tebibytes, for use with [`quantity_in`].
+pub const BYTES: &[(&str, f64)] = &[
+    ("b", 1.0),
+    ("kb", 1e3), ("mb", 1e6), ("gb", 1e9), ("tb", 1e12),
+    ("kib", 1024.0), ("mib", 1048576.0), ("gib", 1073741824.0), ("tib", 1099511627776.0),
+];
+
+///Units of time, from `ns` for nanoseconds through `d` for days, in seconds, for use with
+///[`quantity_in`].
+pub const SECONDS: &[(&str, f64)] = &[
+    ("ns", 1e-9), ("us", 1e-6), ("µs", 1e-6), ("ms", 1e-3),
+    ("s", 1.0), ("m", 60.0), ("h", 3600.0), ("d", 86400.0),
+];
+
+//the length of a leading `[+-]digits[.digits]`
+fn number_len(text: &str) -> usize {
+    let sign = usize::from(text.starts_with(['+', '-']));
+    let digits = |from: usize| text[from..].bytes().take_while(u8::is_ascii_digit).count();
+    let whole = digits(sign);
+    let mut len = sign + whole;
+    if text[len..].starts_with('.') {
+        let frac = digits(len + 1);
+        if frac > 0 || whole > 0 {
+            len += 1 + frac;
+        }
+    }
+    if whole == 0 && len == sign { 0 } else { len }
+}
+
+//the length of a leading unit token
+fn unit_len(text: &str) -> usize {
+    text.chars()
+        .take_while(|&c| c.is_alphabetic() || c == '%')
+        .map(char::len_utf8)
+        .sum()
+}
+
+/**
+Parses a number directly followed by an optional unit, like `10kb`, `250ms` or `3.5GiB`. The unit
+is a run of letters or `%`. Fails with [`QuantityErr::NoNumber`] if the input doesn't start with
+a number, in which case nothing is consumed.
+```
+# use parsa::ParserString;
+# use parsa::builtins::{quantity, Quantity};
+let mut input = ParserString::from("3.5GiB free");
+assert_eq!(quantity(&mut input), Ok(Quantity { value: 3.5, unit: "GiB".into() }));
+assert_eq!(input.get(), " free");
+
+assert_eq!(quantity(&mut ParserString::from("-2")), Ok(Quantity { value: -2.0, unit: "".into() }));
+```
+*/
+pub fn quantity(s: &mut ParserString) -> Result<Quantity, QuantityErr> {
+    let rest = s.get();
+    let n = number_len(rest);
+    let value = rest[..n].parse().map_err(|_| QuantityErr::NoNumber)?;
+    let unit = rest[n..n + unit_len(&rest[n..])].to_owned();
+    unsafe { s.set_ptr(s.start() + n + unit.len()) }
+    Ok(Quantity { value, unit })
+}
+
+/**
+Parses a [`quantity`] and converts it to a base unit, using a table of units and how many base
+units each is worth. Units are matched ignoring ASCII case. The tables [`BYTES`] and
+[`SECONDS`] are provided. Fails with [`QuantityErr::UnknownUnit`] if the unit isn't in the
+table, including when it is missing, in which case nothing is consumed.
+```
+# use parsa::{Parser, ParserString};
+# use parsa::builtins::{quantity_in, QuantityErr, BYTES};
+let size = quantity_in(BYTES);
+assert_eq!(size.parse(&mut ParserString::from("10kb")), Ok(10_000.0));
+assert_eq!(size.parse(&mut ParserString::from("2MiB")), Ok(2_097_152.0));
+assert_eq!(
+    size.parse(&mut ParserString::from("10 kb")),
+    Err(QuantityErr::UnknownUnit { unit: "".into() }),
+);
+```
+*/
+pub fn quantity_in(table: &'static [(&'static str, f64)]) -> impl Parser<f64, Err = QuantityErr> {
+    let units = table.iter().map(|(unit, _)| Grammar::Literal((*unit).to_owned())).collect();
+    let grammar = Grammar::named("number", Grammar::Opaque).seq(Grammar::Alt(units));
+    Described::new(move |s: &mut ParserString| {
+        let cp = s.checkpoint();
+        let q = quantity(s)?;
+        match table.iter().find(|(unit, _)| unit.eq_ignore_ascii_case(&q.unit)) {
+            Some((_, scale)) => Ok(q.value * scale),
+            None => {
+                s.rewind(cp);
+                Err(QuantityErr::UnknownUnit { unit: q.unit })
+            },
+        }
+    }, grammar)
+}
+
+///Indicates that a [`quantity`] or [`quantity_in`] parser has failed.
+#[derive(Debug, Clone, Error, FromNever, PartialEq, Eq)]
+pub enum QuantityErr {
+    ///Parser failed because the input didn't start with a number
+    #[error("expected a number")]
+    NoNumber,
+    ///Parser failed because the unit wasn't recognized
+    #[error("unknown unit {unit:?}")]
+    UnknownUnit {
+        ///The unit that was found
+        unit: String,
+    },
+}
+
+//nanoseconds per duration unit
+const DURATION_UNITS: &[(&str, u128)] = &[
+    ("ns", 1), ("us", 1_000), ("µs", 1_000), ("ms", 1_000_000), ("s", 1_000_000_000),
+    ("m", 60_000_000_000), ("h", 3_600_000_000_000), ("d", 86_400_000_000_000), ("w", 604_800_000_000_000),
+];
+
+/**
+Parses a duration made of one or more segments, each a number directly followed by a unit, like
+`1h30m`, `45s` or `1.5d`. The units are `w`, `d`, `h`, `m`, `s`, `ms`, `us` or `µs`, and `ns`.
+Segments are summed, and the fractional part of a segment is rounded to the nearest nanosecond.
+Nothing is consumed on failure.
+```
+# use parsa::ParserString;
+# use parsa::builtins::{duration, DurationErr};
+# use std::time::Duration;
+let mut input = ParserString::from("1h30m left");
+assert_eq!(duration(&mut input), Ok(Duration::from_secs(5400)));
+assert_eq!(input.get(), " left");
+
+assert_eq!(duration(&mut ParserString::from("2.5ms")), Ok(Duration::from_micros(2500)));
+assert_eq!(
+    duration(&mut ParserString::from("10")),
+    Err(DurationErr::UnknownUnit { unit: "".into() }),
+);
+```
+*/
+pub fn duration(s: &mut ParserString) -> Result<Duration, DurationErr> {
+    let cp = s.checkpoint();
+    let mut nanos: u128 = 0;
+    let mut segments = 0;
+    let out = loop {
+        let rest = s.get();
+        let n = number_len(rest);
+        if n == 0 || rest.starts_with(['+', '-']) {
+            if segments == 0 {
+                break Err(DurationErr::NoNumber)
+            }
+            let secs = nanos / 1_000_000_000;
+            let Ok(secs) = u64::try_from(secs) else { break Err(DurationErr::Overflow) };
+            break Ok(Duration::new(secs, (nanos % 1_000_000_000) as u32))
+        }
+
+        let unit = &rest[n..n + unit_len(&rest[n..])];
+        let Some(&(_, scale)) = DURATION_UNITS.iter().find(|(u, _)| *u == unit) else {
+            break Err(DurationErr::UnknownUnit { unit: unit.to_owned() })
+        };
+        let (whole, frac) = rest[..n].split_once('.').unwrap_or((&rest[..n], ""));
+        let whole: u128 = if whole.is_empty() { 0 } else {
+            match whole.parse() {
+                Ok(whole) => whole,
+                Err(_) => break Err(DurationErr::Overflow),
+            }
+        };
+        let frac = format!("0.{frac}").parse::<f64>().unwrap_or(0.0);
+        let segment = whole.checked_mul(scale)
+            .and_then(|n| n.checked_add((frac * scale as f64).round() as u128))
+            .and_then(|n| n.checked_add(nanos));
+        match segment {
+            Some(total) => nanos = total,
+            None => break Err(DurationErr::Overflow),
+        }
+        segments += 1;
+        unsafe { s.set_ptr(s.start() + n + unit.len()) }
+    };
+    if out.is_err() {
+        s.rewind(cp);
+    }
+    out
+}
+
+///Indicates that a [`duration`] parser has failed.
+#[derive(Debug, Clone, Error, FromNever, PartialEq, Eq)]
+pub enum DurationErr {
+    ///Parser failed because the input didn't start with a number
+    #[error("expected a duration")]
+    NoNumber,
+    ///Parser failed because a segment had a missing or unrecognized unit
+    #[error("unknown duration unit {unit:?}")]
+    UnknownUnit {
+        ///The unit that was found
+        unit: String,
+    },
+    ///Parser failed because the duration is too large to represent
+    #[error("duration is too large")]
+    Overflow,
+}
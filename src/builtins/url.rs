@@ -0,0 +1,160 @@
+use thiserror::Error;
+use nevermore::FromNever;
+
+use crate::{ParserString, Parser, grammar::{Grammar, Builtin, Described}};
+
+///A URL/URI broken into its structural parts. See [`url`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Url {
+    ///The scheme, without the trailing `:`
+    pub scheme: String,
+    ///The authority, present if the URL had a `//` after the scheme
+    pub authority: Option<Authority>,
+    ///The path, possibly empty
+    pub path: String,
+    ///The query, without the leading `?`
+    pub query: Option<String>,
+    ///The fragment, without the leading `#`
+    pub fragment: Option<String>,
+}
+
+///The authority section of a [`Url`], as in `user@host:port`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Authority {
+    ///The user info, without the trailing `@`
+    pub userinfo: Option<String>,
+    ///The host name or address, possibly empty (as in `file:///`). IPv6 addresses keep their brackets.
+    pub host: String,
+    ///The port, if one was given
+    pub port: Option<u16>,
+}
+
+///Indicates that a [`url`] parser has failed.
+#[derive(Debug, Clone, Copy, Error, FromNever, PartialEq, Eq)]
+pub enum UrlErr {
+    ///Parser failed because there were no characters before a terminator
+    #[error("found no characters")]
+    Empty,
+    ///Parser failed because no valid scheme was found
+    #[error("missing or invalid scheme")]
+    NoScheme,
+    ///Parser failed because the port was not a valid number
+    #[error("invalid port")]
+    InvalidPort,
+}
+
+/**Parses a [`Url`], stopping at whitespace. Nothing is consumed on failure.
+
+See [`url_until`] to stop at other characters as well.
+```
+# use parsa::ParserString;
+# use parsa::builtins::url;
+let mut input = ParserString::from("https://user@example.com:8080/a/b?x=1#top rest");
+let url = url(&mut input).unwrap();
+
+assert_eq!(url.scheme, "https");
+let authority = url.authority.unwrap();
+assert_eq!(authority.userinfo.as_deref(), Some("user"));
+assert_eq!(authority.host, "example.com");
+assert_eq!(authority.port, Some(8080));
+assert_eq!(url.path, "/a/b");
+assert_eq!(url.query.as_deref(), Some("x=1"));
+assert_eq!(url.fragment.as_deref(), Some("top"));
+assert_eq!(input.get(), " rest");
+```
+*/
+pub fn url(s: &mut ParserString) -> Result<Url, UrlErr> {
+    url_until(&[]).parse(s)
+}
+
+/**Parses a [`Url`], stopping at whitespace or any of the given terminators. Nothing is consumed on
+failure.
+```
+# use parsa::ParserString;
+# use parsa::Parser;
+# use parsa::builtins::url_until;
+let mut input = ParserString::from("<mailto:someone@example.com>");
+input.take(1);
+let url = url_until(&['>']).parse(&mut input).unwrap();
+
+assert_eq!(url.scheme, "mailto");
+assert!(url.authority.is_none());
+assert_eq!(url.path, "someone@example.com");
+assert_eq!(input.get(), ">");
+```
+*/
+pub fn url_until(terminators: &'static [char]) -> impl Parser<Url, Err = UrlErr> {
+    Described::new(move |s: &mut ParserString| {
+        let end = s.get()
+            .find(|c: char| c.is_whitespace() || terminators.contains(&c))
+            .unwrap_or(s.len());
+        let text = &s.get()[..end];
+        if text.is_empty() { return Err(UrlErr::Empty) }
+
+        let url = split_url(text)?;
+        let n = text.chars().count();
+        s.take(n);
+        Ok(url)
+    }, Grammar::Builtin(Builtin::Url))
+}
+
+fn split_url(text: &str) -> Result<Url, UrlErr> {
+    let (scheme, rest) = text.split_once(':').ok_or(UrlErr::NoScheme)?;
+    let mut chars = scheme.chars();
+    let valid_scheme = chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'));
+    if !valid_scheme { return Err(UrlErr::NoScheme) }
+
+    let (rest, fragment) = match rest.split_once('#') {
+        Some((rest, fragment)) => (rest, Some(fragment.to_owned())),
+        None => (rest, None),
+    };
+    let (rest, query) = match rest.split_once('?') {
+        Some((rest, query)) => (rest, Some(query.to_owned())),
+        None => (rest, None),
+    };
+
+    let (authority, path) = match rest.strip_prefix("//") {
+        Some(rest) => {
+            let end = rest.find('/').unwrap_or(rest.len());
+            let (authority, path) = rest.split_at(end);
+            (Some(split_authority(authority)?), path)
+        },
+        None => (None, rest),
+    };
+
+    Ok(Url {
+        scheme: scheme.to_owned(),
+        authority,
+        path: path.to_owned(),
+        query,
+        fragment,
+    })
+}
+
+fn split_authority(text: &str) -> Result<Authority, UrlErr> {
+    let (userinfo, hostport) = match text.rsplit_once('@') {
+        Some((userinfo, hostport)) => (Some(userinfo.to_owned()), hostport),
+        None => (None, text),
+    };
+
+    //the last ':' outside of an IPv6 literal separates the port
+    let port_sep = match hostport.rfind(']') {
+        Some(i) => hostport[i..].find(':').map(|j| i + j),
+        None => hostport.rfind(':'),
+    };
+    let (host, port) = match port_sep {
+        Some(i) => {
+            let port = &hostport[i+1..];
+            let port = if port.is_empty() {
+                None
+            } else {
+                Some(port.parse::<u16>().map_err(|_| UrlErr::InvalidPort)?)
+            };
+            (&hostport[..i], port)
+        },
+        None => (hostport, None),
+    };
+
+    Ok(Authority { userinfo, host: host.to_owned(), port })
+}
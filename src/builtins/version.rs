@@ -0,0 +1,317 @@
+use std::cmp::Ordering;
+
+use thiserror::Error;
+use nevermore::FromNever;
+
+use crate::ParserString;
+
+///A semantic version, like `1.4.2-beta.1+build5`. See [`version`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Version {
+    ///The major version
+    pub major: u64,
+    ///The minor version
+    pub minor: u64,
+    ///The patch version
+    pub patch: u64,
+    ///The pre-release identifiers after `-`, or empty
+    pub pre: String,
+    ///The build metadata after `+`, or empty. This is ignored when comparing versions.
+    pub build: String,
+}
+
+impl Version {
+    ///Constructs a release version, without pre-release or build metadata.
+    pub fn new(major: u64, minor: u64, patch: u64) -> Self {
+        Self { major, minor, patch, pre: String::new(), build: String::new() }
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> { Some(self.cmp(other)) }
+}
+
+//pre-releases come before their release, and compare identifier by identifier
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.major, self.minor, self.patch).cmp(&(other.major, other.minor, other.patch))
+            .then_with(|| match (self.pre.is_empty(), other.pre.is_empty()) {
+                (true, true) => Ordering::Equal,
+                (true, false) => Ordering::Greater,
+                (false, true) => Ordering::Less,
+                (false, false) => self.pre.split('.').map(identifier)
+                    .cmp(other.pre.split('.').map(identifier)),
+            })
+    }
+}
+
+//numeric identifiers sort numerically, and before alphanumeric ones
+fn identifier(id: &str) -> (bool, u64, &str) {
+    match id.parse() {
+        Ok(n) => (false, n, ""),
+        Err(_) => (true, 0, id),
+    }
+}
+
+///How a [`Comparator`] compares versions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Op {
+    ///`=`, matching versions that equal every given part
+    Exact,
+    ///`>`
+    Greater,
+    ///`>=`
+    GreaterEq,
+    ///`<`
+    Less,
+    ///`<=`
+    LessEq,
+    ///`~`, allowing patch updates, or minor updates if only the major version is given
+    Tilde,
+    ///`^`, allowing updates that don't change the leftmost non-zero part. A version without an
+    ///operator is a caret requirement.
+    Caret,
+    ///`*` in place of a part, like `1.*` or `*`
+    Wildcard,
+}
+
+///One comparison of a [`Requirement`], whose version may leave out its trailing parts.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Comparator {
+    ///The comparison
+    pub op: Op,
+    ///The major version, which is only missing for `*`
+    pub major: Option<u64>,
+    ///The minor version, if given
+    pub minor: Option<u64>,
+    ///The patch version, if given
+    pub patch: Option<u64>,
+    ///The pre-release identifiers, or empty
+    pub pre: String,
+}
+
+impl Comparator {
+    //the version this comparator names, with missing parts as 0
+    fn lower(&self) -> Version {
+        Version {
+            pre: self.pre.clone(),
+            ..Version::new(self.major.unwrap_or(0), self.minor.unwrap_or(0), self.patch.unwrap_or(0))
+        }
+    }
+
+    //the first version after everything that equals the given parts
+    fn upper(&self) -> Option<Version> {
+        match (self.major, self.minor, self.patch) {
+            (None, ..) => None,
+            (Some(major), None, _) => Some(Version::new(major + 1, 0, 0)),
+            (Some(major), Some(minor), None) => Some(Version::new(major, minor + 1, 0)),
+            (Some(major), Some(minor), Some(patch)) => Some(Version::new(major, minor, patch + 1)),
+        }
+    }
+
+    ///Returns `true` if `v` satisfies this comparison, ignoring the pre-release rule of
+    ///[`Requirement::matches`].
+    pub fn matches(&self, v: &Version) -> bool {
+        let lower = self.lower();
+        let exact = self.patch.is_some();
+        let below = |upper: Option<Version>| upper.is_none_or(|u| *v < u);
+        match self.op {
+            Op::Exact | Op::Wildcard if exact => *v == lower,
+            Op::Exact | Op::Wildcard => *v >= lower && below(self.upper()),
+            Op::Greater if exact => *v > lower,
+            Op::Greater => self.upper().is_some_and(|u| *v >= u),
+            Op::GreaterEq => *v >= lower,
+            Op::Less => *v < lower,
+            Op::LessEq if exact => *v <= lower,
+            Op::LessEq => below(self.upper()),
+            Op::Tilde => {
+                let upper = match self.minor {
+                    Some(minor) => Version::new(lower.major, minor + 1, 0),
+                    None => Version::new(lower.major + 1, 0, 0),
+                };
+                *v >= lower && *v < upper
+            },
+            Op::Caret => {
+                let upper = match (self.major, self.minor, self.patch) {
+                    (Some(0), Some(0), Some(patch)) => Version::new(0, 0, patch + 1),
+                    (Some(0), Some(minor), _) => Version::new(0, minor + 1, 0),
+                    _ => Version::new(lower.major + 1, 0, 0),
+                };
+                *v >= lower && *v < upper
+            },
+        }
+    }
+}
+
+///A version requirement: comparators that must all match. See [`requirement`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Requirement {
+    ///The comparators, in order
+    pub comparators: Vec<Comparator>,
+}
+
+impl Requirement {
+    ///Returns `true` if `v` satisfies every comparator. As with Cargo, a pre-release version only
+    ///matches if some comparator names a pre-release of the same `major.minor.patch`.
+    pub fn matches(&self, v: &Version) -> bool {
+        let pre_allowed = v.pre.is_empty() || self.comparators.iter().any(|c| {
+            !c.pre.is_empty() && (c.major, c.minor, c.patch) == (Some(v.major), Some(v.minor), Some(v.patch))
+        });
+        pre_allowed && self.comparators.iter().all(|c| c.matches(v))
+    }
+}
+
+///Indicates that a [`version`] or [`requirement`] parser has failed.
+#[derive(Debug, Clone, Copy, Error, FromNever, PartialEq, Eq)]
+#[error("invalid version at offset {offset}")]
+pub struct VersionErr {
+    ///Where the malformed part was found
+    pub offset: usize,
+}
+
+fn number(s: &mut ParserString) -> Result<u64, VersionErr> {
+    let offset = s.start();
+    let n = s.get().bytes().take_while(u8::is_ascii_digit).count();
+    s.get()[..n].parse().map_err(|_| VersionErr { offset }).inspect(|_| { s.take(n); })
+}
+
+//dot-separated identifiers after `-` or `+`, if `marker` is next
+fn identifiers(s: &mut ParserString, marker: char) -> Result<String, VersionErr> {
+    if !s.get().starts_with(marker) {
+        return Ok(String::new())
+    }
+    s.take(1);
+    let offset = s.start();
+    let n = s.get().chars().take_while(|&c| c.is_ascii_alphanumeric() || c == '-' || c == '.').count();
+    let ids = s.take(n);
+    if ids.split('.').any(str::is_empty) {
+        return Err(VersionErr { offset })
+    }
+    Ok(ids.to_owned())
+}
+
+/**
+Parses a semantic version: `major.minor.patch`, optionally followed by `-` and pre-release
+identifiers and `+` and build metadata. Nothing is consumed on failure.
+```
+# use parsa::ParserString;
+# use parsa::builtins::{version, Version};
+let v = version(&mut ParserString::from("1.4.2-beta.1+build5")).unwrap();
+assert_eq!((v.major, v.minor, v.patch), (1, 4, 2));
+assert_eq!((v.pre.as_str(), v.build.as_str()), ("beta.1", "build5"));
+assert!(v < Version::new(1, 4, 2));
+assert!(version(&mut ParserString::from("1.4")).is_err());
+```
+*/
+pub fn version(s: &mut ParserString) -> Result<Version, VersionErr> {
+    let cp = s.checkpoint();
+    let out = (|| {
+        let major = number(s)?;
+        let mut rest = [0; 2];
+        for part in &mut rest {
+            if s.try_take(1) != Some(".") {
+                return Err(VersionErr { offset: s.start() })
+            }
+            *part = number(s)?;
+        }
+        let pre = identifiers(s, '-')?;
+        let build = identifiers(s, '+')?;
+        Ok(Version { pre, build, ..Version::new(major, rest[0], rest[1]) })
+    })();
+    if out.is_err() {
+        s.rewind(cp);
+    }
+    out
+}
+
+//a version whose trailing parts may be missing or `*`
+fn partial(s: &mut ParserString, mut op: Option<Op>) -> Result<Comparator, VersionErr> {
+    let mut parts = [None; 3];
+    for (i, part) in parts.iter_mut().enumerate() {
+        if i > 0 && !(s.get().starts_with('.') && s.get()[1..].starts_with(|c: char| c.is_ascii_digit() || c == '*')) {
+            break
+        }
+        if i > 0 {
+            s.take(1);
+        }
+        if s.get().starts_with('*') {
+            s.take(1);
+            op = match op {
+                None | Some(Op::Exact) => Some(Op::Wildcard),
+                _ => op,
+            };
+            continue
+        }
+        if op == Some(Op::Wildcard) {
+            return Err(VersionErr { offset: s.start() })
+        }
+        *part = Some(number(s)?);
+    }
+    let pre = if parts[2].is_some() { identifiers(s, '-')? } else { String::new() };
+    if parts[2].is_some() {
+        identifiers(s, '+')?;
+    }
+    let [major, minor, patch] = parts;
+    Ok(Comparator { op: op.unwrap_or(Op::Caret), major, minor, patch, pre })
+}
+
+/**
+Parses a version requirement: comma-separated comparators like `>=1.2, <2.0`, `~1.4.2`, `1.*` or
+`^0.3`. Versions may leave out trailing parts, and a version without an operator is a caret
+requirement, as with Cargo. Nothing is consumed on failure.
+```
+# use parsa::ParserString;
+# use parsa::builtins::{requirement, version, Op};
+let req = requirement(&mut ParserString::from(">=1.2, <2.0")).unwrap();
+assert_eq!(req.comparators[0].op, Op::GreaterEq);
+assert_eq!(req.comparators[1].minor, Some(0));
+assert_eq!(req.comparators[1].patch, None);
+
+let matches = |req: &str, v: &str| {
+    let req = requirement(&mut ParserString::from(req)).unwrap();
+    req.matches(&version(&mut ParserString::from(v)).unwrap())
+};
+assert!(matches(">=1.2, <2.0", "1.9.9"));
+assert!(!matches(">=1.2, <2.0", "2.0.0"));
+assert!(matches("^0.3", "0.3.7"));
+assert!(!matches("^0.3", "0.4.0"));
+assert!(matches("~1.4.2", "1.4.9"));
+assert!(matches("1.*", "1.8.0"));
+assert!(!matches("^1.0.0", "1.2.0-rc.1"));
+assert!(matches(">=1.2.0-rc.1", "1.2.0-rc.2"));
+```
+*/
+pub fn requirement(s: &mut ParserString) -> Result<Requirement, VersionErr> {
+    let cp = s.checkpoint();
+    let mut comparators = vec![];
+    let out = loop {
+        let n = s.get().chars().take_while(|&c| c == ' ').count();
+        s.take(n);
+        let op = [(">=", Op::GreaterEq), ("<=", Op::LessEq), (">", Op::Greater), ("<", Op::Less),
+            ("=", Op::Exact), ("~", Op::Tilde), ("^", Op::Caret)]
+            .into_iter()
+            .find(|(text, _)| s.get().starts_with(text));
+        if let Some((text, _)) = op {
+            s.take(text.len());
+            let n = s.get().chars().take_while(|&c| c == ' ').count();
+            s.take(n);
+        }
+        match partial(s, op.map(|(_, op)| op)) {
+            Ok(c) if c.major.is_none() && c.op != Op::Wildcard => break Err(VersionErr { offset: s.start() }),
+            Ok(c) => comparators.push(c),
+            Err(e) => break Err(e),
+        }
+
+        let after = s.checkpoint();
+        let n = s.get().chars().take_while(|&c| c == ' ').count();
+        s.take(n);
+        if s.try_take(1) != Some(",") {
+            s.rewind(after);
+            break Ok(Requirement { comparators })
+        }
+    };
+    if out.is_err() {
+        s.rewind(cp);
+    }
+    out
+}
@@ -0,0 +1,225 @@
+use std::{fmt::Debug, ops::Range};
+
+use crate::{ParserInput, Input, Checkpoint};
+
+///A shrinking-window read-only byte slice, for parsing binary data.
+///
+///Works with every combinator that [`ParserString`](crate::ParserString) does. Byte slices can be
+///taken from the front, and reset, with zero allocations or copies.
+///```rust
+///# use parsa::{Parser, ParserBytes};
+///let byte = |s: &mut ParserBytes| s.try_take(1).map(|b| b[0]).ok_or(());
+///let pair = byte.chain(byte);
+///
+///let mut input = ParserBytes::from(&[1, 2, 3]);
+///assert_eq!(pair.parse(&mut input), Ok((1, 2)));
+///assert_eq!(pair.try_parse(&mut input), Err(()));
+///assert_eq!(input.get(), &[3]);
+///```
+pub type ParserBytes = ParserInput<[u8]>;
+
+impl Input for [u8] {
+    fn size(&self) -> usize { self.len() }
+
+    fn is_boundary(&self, offset: usize) -> bool { offset <= self.len() }
+
+    fn describe(&self, range: Range<usize>, max: usize) -> String {
+        let end = range.end.min(range.start.saturating_add(max));
+        String::from_utf8_lossy(self.get(range.start..end).unwrap_or_default()).into_owned()
+    }
+}
+
+impl ParserBytes {
+    ///Splits off `n` bytes, shrinking the slice. Panics if `n` is larger than the remaining slice.
+    ///```rust
+    ///# use parsa::ParserBytes;
+    ///let mut input = ParserBytes::from(b"\x7fELF");
+    ///assert_eq!(input.take(1), &[0x7f]);
+    ///assert_eq!(input.take(3), b"ELF");
+    ///```
+    pub fn take(&mut self, n: usize) -> &[u8] {
+        let start = self.ptr;
+        assert!(start + n <= self.end);
+        self.ptr = start + n;
+        &self.full[start..start + n]
+    }
+
+    ///Splits off `n` bytes, shrinking the slice. Returns [`None`] if `n` is larger than the remaining slice.
+    ///```rust
+    ///# use parsa::ParserBytes;
+    ///let mut input = ParserBytes::from(b"abc");
+    ///assert_eq!(input.try_take(2), Some(b"ab".as_slice()));
+    ///assert_eq!(input.try_take(2), None);
+    ///```
+    pub fn try_take(&mut self, n: usize) -> Option<&[u8]> {
+        let start = self.ptr;
+        if start + n > self.end {
+            return None;
+        }
+        self.ptr = start + n;
+        Some(&self.full[start..start + n])
+    }
+
+    ///Rewinds the slice `n` bytes. Panics if `n` is larger than the taken space.
+    pub fn give(&mut self, n: usize) {
+        self.ptr -= n;
+    }
+
+    ///Set the current start position manually. Panics if `ptr` is out of bounds.
+    pub fn set_ptr(&mut self, ptr: usize) {
+        assert!(ptr <= self.end);
+        self.ptr = ptr;
+    }
+
+    ///Get a reference to the remaining bytes.
+    pub fn get(&self) -> &[u8] {
+        &self.full[self.ptr..self.end]
+    }
+
+    ///Get the bytes consumed since `checkpoint` was taken. Panics if the input has been rewound
+    ///to before it.
+    ///```rust
+    ///# use parsa::ParserBytes;
+    ///let mut input = ParserBytes::from(&[1, 2, 3]);
+    ///let cp = input.checkpoint();
+    ///input.take(2);
+    ///assert_eq!(input.since(cp), &[1, 2]);
+    ///```
+    pub fn since(&self, checkpoint: Checkpoint) -> &[u8] {
+        &self.full[checkpoint.offset()..self.ptr]
+    }
+}
+
+impl From<&[u8]> for ParserBytes {
+    fn from(value: &[u8]) -> Self {
+        Self::new(value.into())
+    }
+}
+
+impl<const N: usize> From<&[u8; N]> for ParserBytes {
+    fn from(value: &[u8; N]) -> Self {
+        Self::from(value.as_slice())
+    }
+}
+
+impl From<Vec<u8>> for ParserBytes {
+    fn from(value: Vec<u8>) -> Self {
+        Self::new(value.into_boxed_slice())
+    }
+}
+
+impl Debug for ParserBytes {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.get())
+    }
+}
+
+/**
+A view of bytes as a sequence of bits, most significant bit first. Positions into it are counted
+in bits.
+
+Parse bits from the middle of byte input with
+[`bitwise`](crate::builtins::bitwise), or on their own with a [`ParserBits`].
+*/
+#[repr(transparent)]
+pub struct Bits([u8]);
+
+impl Bits {
+    fn from_boxed(bytes: Box<[u8]>) -> Box<Self> {
+        //SAFETY: Bits is a transparent wrapper around [u8]
+        unsafe { Box::from_raw(Box::into_raw(bytes) as *mut Self) }
+    }
+
+    #[cfg(feature = "builtins")]
+    fn into_boxed(self: Box<Self>) -> Box<[u8]> {
+        //SAFETY: Bits is a transparent wrapper around [u8]
+        unsafe { Box::from_raw(Box::into_raw(self) as *mut [u8]) }
+    }
+
+    fn bit(&self, offset: usize) -> bool {
+        self.0[offset / 8] & (0x80 >> (offset % 8)) != 0
+    }
+}
+
+impl Input for Bits {
+    fn size(&self) -> usize { self.0.len() * 8 }
+
+    fn is_boundary(&self, offset: usize) -> bool { offset <= self.size() }
+
+    fn describe(&self, range: Range<usize>, max: usize) -> String {
+        let end = range.end.min(range.start.saturating_add(max)).min(self.size());
+        (range.start.min(end)..end).map(|i| if self.bit(i) { '1' } else { '0' }).collect()
+    }
+}
+
+///A shrinking-window read-only sequence of bits.
+///```rust
+///# use parsa::ParserBits;
+///let mut input = ParserBits::from(&[0b1010_0110]);
+///assert_eq!(input.take(3), 0b101);
+///assert_eq!(input.try_take(6), None);
+///assert_eq!(input.take(5), 0b00110);
+///```
+pub type ParserBits = ParserInput<Bits>;
+
+impl ParserBits {
+    ///Splits off `n` bits, returning them as an integer. Panics if `n` is larger than 64, or than
+    ///the remaining bits.
+    pub fn take(&mut self, n: usize) -> u64 {
+        self.try_take(n).expect("not enough bits remaining")
+    }
+
+    ///Splits off `n` bits, returning them as an integer. Returns [`None`] if `n` is larger than the
+    ///remaining bits. Panics if `n` is larger than 64.
+    pub fn try_take(&mut self, n: usize) -> Option<u64> {
+        assert!(n <= 64, "can't take more than 64 bits at once");
+        let start = self.ptr;
+        if start + n > self.end {
+            return None
+        }
+        self.ptr = start + n;
+        Some((start..start + n).fold(0, |acc, i| acc << 1 | self.full.bit(i) as u64))
+    }
+
+    ///Rewinds `n` bits. Panics if `n` is larger than the taken space.
+    pub fn give(&mut self, n: usize) {
+        self.ptr -= n;
+    }
+
+    ///Returns `true` if the current position is at the start of a byte.
+    pub fn is_aligned(&self) -> bool {
+        self.ptr.is_multiple_of(8)
+    }
+
+    //runs `f` over the bits of a byte input, then moves back to the next whole byte
+    #[cfg(feature = "builtins")]
+    pub(crate) fn over<T>(s: &mut ParserBytes, f: impl FnOnce(&mut ParserBits) -> T) -> T {
+        let bytes = std::mem::replace(s, ParserBytes::from(Vec::new()));
+        let start = bytes.start();
+        let mut bits = bytes.reinterpret(Bits::from_boxed, |o| o * 8);
+
+        let out = f(&mut bits);
+
+        *s = bits.reinterpret(Bits::into_boxed, |o| o.div_ceil(8));
+        debug_assert!(s.start() >= start);
+        out
+    }
+}
+
+impl From<&[u8]> for ParserBits {
+    fn from(value: &[u8]) -> Self {
+        Self::new(Bits::from_boxed(value.into()))
+    }
+}
+
+impl<const N: usize> From<&[u8; N]> for ParserBits {
+    fn from(value: &[u8; N]) -> Self {
+        Self::from(value.as_slice())
+    }
+}
+
+impl Debug for ParserBits {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.full.describe(self.start()..self.end, usize::MAX))
+    }
+}
@@ -2,6 +2,16 @@
 Parsers that combine/manipulate other parsers in some way.
 
 All of the types here have corresponding methods in [`Parser`] for builder pattern-style construction.
+They implement [`Clone`] and [`Debug`] whenever the parsers inside them do, so a composed parser
+can be copied out to wherever it is needed instead of being rebuilt. Adapters that return an
+`impl Parser`, like [`Parser::map`], can't be cloned.
+```
+# use parsa::{Parser, ParserString};
+# use parsa::builtins::{word, whitespace};
+let item = word.chain(whitespace).many1();
+let (a, b) = (item.clone(), item);
+assert_eq!(a.parse(&mut ParserString::from("x y")).unwrap(), b.parse(&mut ParserString::from("x y")).unwrap());
+```
 
 # Error coercion rules
 When dealing with multiple parsers with different error types, they must have a conversion to a single type.
@@ -17,11 +27,36 @@ pub enum MyErr {
     Empty(#[from] WordErr), // <- WordErr will implicitly be elevated to this type
 }
 ```
+String and [`char`] literals are parsers too, failing with a
+[`TakeErr`](crate::builtins::TakeErr) and a [`CharErr`](crate::builtins::CharErr) respectively, so
+your error type needs conversions from those to use them after its first parser.
 */
 
-use std::{marker::PhantomData, convert::Infallible};
+use std::{any::{Any, TypeId}, marker::PhantomData, convert::Infallible, fmt::{Debug, Display}, collections::HashMap, hash::Hash, rc::Rc, cell::RefCell};
+
+use nevermore::FromNever;
+
+use crate::{Parser, DynParser, ParserInput, Input, Diagnostic, Severity, Expectation, BacktrackLimit, SizeLimits, SizeExceeded, grammar::Grammar, inspect::rule};
 
-use crate::{Parser, ParserString};
+//implements Clone and Debug, requiring them only of the listed parameters rather than of the
+//phantom output and error types
+macro_rules! clone_debug {
+    ($name:ident<$($gen:ident),*> [$($bound:ident),*] { $($field:ident),* } { $($phantom:ident),* }) => {
+        impl<$($gen),*> Clone for $name<$($gen),*> where $($bound: Clone),* {
+            fn clone(&self) -> Self {
+                Self { $($field: self.$field.clone(),)* $($phantom: PhantomData,)* }
+            }
+        }
+
+        impl<$($gen),*> Debug for $name<$($gen),*> where $($bound: Debug),* {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.debug_struct(stringify!($name))
+                    $(.field(stringify!($field), &self.$field))*
+                    .finish()
+            }
+        }
+    };
+}
 
 /**Chains two parsers together.
 
@@ -39,8 +74,7 @@ assert_eq!(after, 3);
 # }
 ```
 */
-pub struct Chain<T, U, P1, P2> 
-{
+pub struct Chain<T, U, P1, P2> {
     p1: P1,
     p2: P2,
 
@@ -48,29 +82,30 @@ pub struct Chain<T, U, P1, P2>
     u: PhantomData<U>,
 }
 
-impl<T, U, P1, P2, E> Chain<T, U, P1, P2>
-where 
-    P1: Parser<T>,
-    E: Into<P1::Err>,
-    P2: Parser<U, Err = E>,
-{
+clone_debug!(Chain<T, U, P1, P2> [P1, P2] { p1, p2 } { t, u });
+
+impl<T, U, P1, P2> Chain<T, U, P1, P2> {
     ///Constructs this parser.
-    pub fn new(p1: P1, p2: P2) -> Self { Self { p1, p2, t: PhantomData, u: PhantomData } }
+    pub const fn new(p1: P1, p2: P2) -> Self { Self { p1, p2, t: PhantomData, u: PhantomData } }
 }
 
-impl<T, U, P1, P2, E> Parser<(T, U)> for Chain<T, U, P1, P2>
-where 
-    P1: Parser<T>,
+impl<T, U, P1, P2, E, I: ?Sized + Input> Parser<(T, U), I> for Chain<T, U, P1, P2>
+where
+    P1: Parser<T, I>,
     E: Into<P1::Err>,
-    P2: Parser<U, Err = E>,
+    P2: Parser<U, I, Err = E>,
 {
     type Err = P1::Err;
 
-    fn parse(&self, s: &mut ParserString) -> Result<(T, U), Self::Err> {
-        Ok((
+    fn parse(&self, s: &mut ParserInput<I>) -> Result<(T, U), Self::Err> {
+        rule(s, "chain", |s| Ok((
             self.p1.parse(s)?, 
             self.p2.parse(s).map_err(|e| e.into())?
-        ))
+        )))
+    }
+
+    fn grammar(&self) -> Grammar {
+        self.p1.grammar().seq(self.p2.grammar())
     }
 }
 
@@ -131,47 +166,116 @@ assert!(Tag::parse(&mut input).is_ok_and(|t| t == Tag::Abc(Abc)));
 assert!(Tag::parse(&mut input).is_ok_and(|t| t == Tag::Def(Def)));
 ```
 */
-pub struct Or<T, E, P1, P2> 
-where 
-    P1: Parser<T>,
-    E: Into<P1::Err>,
-    P2: Parser<T, Err = E>
-{
+pub struct Or<T, E, P1, P2> {
     p1: P1,
     p2: P2,
+    furthest: bool,
     t: PhantomData<T>,  
     e: PhantomData<E>  
 }
 
-impl<T, E, P1, P2> Or<T, E, P1, P2>
-where 
-    P1: Parser<T>,
-    E: Into<P1::Err>,
-    P2: Parser<T, Err = E>
-{
+clone_debug!(Or<T, E, P1, P2> [P1, P2] { p1, p2, furthest } { t, e });
+
+impl<T, E, P1, P2> Or<T, E, P1, P2> {
     ///Constructs this parser.
-    pub fn new(p1: P1, p2: P2) -> Self { Self { p1, p2, t: PhantomData, e: PhantomData } }
+    pub const fn new(p1: P1, p2: P2) -> Self { Self { p1, p2, furthest: false, t: PhantomData, e: PhantomData } }
+
+    ///When both parsers fail, report the error of whichever one got further into the string,
+    ///instead of always reporting the second. Ties go to the second parser. The string is left
+    ///where the reported parser failed.
+    ///```
+    ///# use parsa::{ParserString, Parser};
+    ///# use parsa::builtins::take;
+    ///let mut input = ParserString::from("let x");
+    ///let err = take("let").replace(take(" y"))
+    ///    .or(take("fn"))
+    ///    .furthest()
+    ///    .parse(&mut input)
+    ///    .unwrap_err();
+    ///assert_eq!(err.expected(), " y");
+    ///assert_eq!(input.get(), "");
+    ///```
+    pub fn furthest(mut self) -> Self {
+        self.furthest = true;
+        self
+    }
 }
 
-impl<T, E, P1, P2> Parser<T> for Or<T, E, P1, P2>
-where 
-    P1: Parser<T>,
+impl<T, E, P1, P2, I: ?Sized + Input> Parser<T, I> for Or<T, E, P1, P2>
+where
+    P1: Parser<T, I>,
     E: Into<P1::Err>,
-    P2: Parser<T, Err = E>
+    P2: Parser<T, I, Err = E>
 {
     type Err = P1::Err;
 
-    fn parse(&self, s: &mut ParserString) -> Result<T, Self::Err> {
-        match self.p1.try_parse(s) {
+    fn parse(&self, s: &mut ParserInput<I>) -> Result<T, Self::Err> {
+        rule(s, "or", |s| self.parse_inner(s))
+    }
+
+    fn grammar(&self) -> Grammar {
+        self.p1.grammar().alt(self.p2.grammar())
+    }
+}
+
+impl<T, E, P1, P2> Or<T, E, P1, P2> {
+    fn parse_inner<I: ?Sized + Input>(&self, s: &mut ParserInput<I>) -> Result<T, P1::Err>
+    where
+        P1: Parser<T, I>,
+        E: Into<P1::Err>,
+        P2: Parser<T, I, Err = E>,
+    {
+        if !self.furthest {
+            return match self.p1.try_parse(s) {
+                Ok(v) => Ok(v),
+                Err(_) => self.p2.parse(s).map_err(Into::into),
+            }
+        }
+
+        let start = s.checkpoint();
+        let e1 = match self.p1.parse(s) {
+            Ok(v) => return Ok(v),
+            Err(e) => e,
+        };
+        let end1 = s.checkpoint();
+        s.rewind(start);
+
+        match self.p2.parse(s) {
             Ok(v) => Ok(v),
-            Err(_) => self.p2.parse(s).map_err(Into::into),
+            Err(_) if end1.offset() > s.start() => {
+                s.rewind(start);
+                s.seek(end1.offset());
+                Err(e1)
+            },
+            Err(e2) => Err(e2.into()),
         }
     }
 }
 
+//one iteration of a repeating combinator. stops the repetition if the parser succeeds without
+//consuming anything, since it would otherwise loop forever. each iteration costs fuel, and
+//counts towards the item limit.
+fn repeat_step<T, I: ?Sized + Input, P: Parser<T, I>>(p: &P, s: &mut ParserInput<I>, count: usize) -> Option<T> {
+    s.burn();
+    if s.is_halted() || !s.allow_item(count) { return None }
+    let cp = s.checkpoint();
+    match p.try_parse(s) {
+        Ok(v) if s.start() != cp.offset() => Some(v),
+        Ok(_) => {
+            s.rewind(cp);
+            s.warn("repeated parser succeeded without consuming input");
+            None
+        },
+        Err(_) => None,
+    }
+}
+
 /**
 Repeatedly applies a parser, until it fails.
 
+A parser that succeeds without consuming anything would repeat forever, so repetition also stops
+there, and a warning is recorded in the string's [diagnostics](ParserInput::diagnostics).
+
 ```
 # use parsa::builtins::{word, WordErr, whitespace};
 # use parsa::{ParserString, Parser};
@@ -180,42 +284,46 @@ let words = word.after(whitespace).many().parse(&mut input).unwrap();
 assert_eq!(words, vec!["ab", "cd", "ef", "gh"]);
 ```
 */
-pub struct Many<T, P> 
-where 
-    P: Parser<T>
-{
+pub struct Many<T, P> {
     p: P,
     t: PhantomData<T>
 }
 
-impl<T, P> Many<T, P>
-where 
-    P: Parser<T>
-{
+clone_debug!(Many<T, P> [P] { p } { t });
+
+impl<T, P> Many<T, P> {
     ///Constructs this parser.
-    pub fn new(p: P) -> Self { Self { p, t: PhantomData } }
+    pub const fn new(p: P) -> Self { Self { p, t: PhantomData } }
 }
 
-impl<T, P> Parser<Vec<T>> for Many<T, P>
-where 
-    P: Parser<T>
+impl<T, P, I: ?Sized + Input> Parser<Vec<T>, I> for Many<T, P>
+where
+    P: Parser<T, I>
 {
     type Err = Infallible;
 
-    fn parse(&self, s: &mut ParserString) -> Result<Vec<T>, Self::Err> {
-        let mut out = vec![];
-        
-        while let Ok(v) = self.p.try_parse(s) {
-            out.push(v)
-        }
+    fn parse(&self, s: &mut ParserInput<I>) -> Result<Vec<T>, Self::Err> {
+        rule(s, "many", |s| {
+            let mut out = vec![];
+            
+            while let Some(v) = repeat_step(&self.p, s, out.len()) {
+                out.push(v)
+            }
+
+            Ok(out)
+        })
+    }
 
-        Ok(out)
+    fn grammar(&self) -> Grammar {
+        Grammar::repeat(self.p.grammar(), 0, None)
     }
 }
 
 /**
 Repeatedly applies a parser, until it fails. Unlike [`Many`], this parser errors if the first run errors.
 
+Like [`Many`], repetition stops if the parser succeeds without consuming anything.
+
 ```
 # use parsa::builtins::{word, WordErr, whitespace};
 # use parsa::{ParserString, Parser};
@@ -228,35 +336,1856 @@ let words = word.after(whitespace).many1().parse(&mut input);
 assert!(words.is_err());
 ```
 */
-pub struct Many1<T, P> 
-where 
-    P: Parser<T>
+pub struct Many1<T, P> {
+    p: P,
+    t: PhantomData<T>
+}
+
+clone_debug!(Many1<T, P> [P] { p } { t });
+
+impl<T, P> Many1<T, P> {
+    ///Constructs this parser.
+    pub const fn new(p: P) -> Self { Self { p, t: PhantomData } }
+}
+
+impl<T, P, I: ?Sized + Input> Parser<Vec<T>, I> for Many1<T, P>
+where
+    P: Parser<T, I>
+{
+    type Err = P::Err;
+
+    fn parse(&self, s: &mut ParserInput<I>) -> Result<Vec<T>, Self::Err> {
+        rule(s, "many1", |s| {
+            let mut out = vec![self.p.parse(s)?];
+
+            while let Some(v) = repeat_step(&self.p, s, out.len()) {
+                out.push(v)
+            }
+
+            Ok(out)
+        })
+    }
+
+    fn grammar(&self) -> Grammar {
+        Grammar::repeat(self.p.grammar(), 1, None)
+    }
+}
+
+/**
+Skips trivia (such as whitespace or comments) around a parser. The trivia parser is applied once on
+each side, and any failure from it is ignored.
+
+Trailing trivia is skipped by default; use [`Lexeme::leading_only`] to only skip leading trivia.
+```
+# use parsa::builtins::{word, whitespace};
+# use parsa::{ParserString, Parser};
+let mut input = ParserString::from("  abc  def");
+let first = word.lexeme(whitespace).parse(&mut input).unwrap();
+assert_eq!(first, "abc");
+assert_eq!(input.get(), "def");
+
+let mut input = ParserString::from("  abc  def");
+let first = word.lexeme(whitespace).leading_only().parse(&mut input).unwrap();
+assert_eq!(first, "abc");
+assert_eq!(input.get(), "  def");
+```
+*/
+pub struct Lexeme<T, U, P, W> {
+    p: P,
+    trivia: W,
+    trailing: bool,
+    t: PhantomData<T>,
+    u: PhantomData<U>,
+}
+
+clone_debug!(Lexeme<T, U, P, W> [P, W] { p, trivia, trailing } { t, u });
+
+impl<T, U, P, W> Lexeme<T, U, P, W> {
+    ///Constructs this parser.
+    pub const fn new(p: P, trivia: W) -> Self { Self { p, trivia, trailing: true, t: PhantomData, u: PhantomData } }
+
+    ///Only skip trivia before the parser, leaving anything after it untouched.
+    pub fn leading_only(mut self) -> Self {
+        self.trailing = false;
+        self
+    }
+}
+
+impl<T, U, P, W, I: ?Sized + Input> Parser<T, I> for Lexeme<T, U, P, W>
+where
+    P: Parser<T, I>,
+    W: Parser<U, I>,
 {
+    type Err = P::Err;
+
+    fn parse(&self, s: &mut ParserInput<I>) -> Result<T, Self::Err> {
+        let _ = self.trivia.try_parse(s);
+        let v = self.p.parse(s)?;
+        if self.trailing {
+            let _ = self.trivia.try_parse(s);
+        }
+        Ok(v)
+    }
+
+    fn grammar(&self) -> Grammar {
+        self.p.grammar()
+    }
+}
+
+/**
+Fails if a parser succeeds without consuming any of the string.
+```
+# use parsa::{ParserString, Parser};
+# use parsa::combinators::ConsumeErr;
+# use parsa::builtins::whitespace;
+let mut input = ParserString::from("  abc");
+assert_eq!(whitespace.must_consume().parse(&mut input), Ok(2));
+assert_eq!(whitespace.must_consume().parse(&mut input), Err(ConsumeErr::NoProgress { offset: 2 }));
+```
+*/
+pub struct MustConsume<T, P> {
     p: P,
     t: PhantomData<T>
 }
 
-impl<T, P> Many1<T, P>
-where 
-    P: Parser<T>
+clone_debug!(MustConsume<T, P> [P] { p } { t });
+
+impl<T, P> MustConsume<T, P> {
+    ///Constructs this parser.
+    pub const fn new(p: P) -> Self { Self { p, t: PhantomData } }
+}
+
+impl<T, P, I: ?Sized + Input> Parser<T, I> for MustConsume<T, P>
+where
+    P: Parser<T, I>
+{
+    type Err = ConsumeErr<P::Err>;
+
+    fn parse(&self, s: &mut ParserInput<I>) -> Result<T, Self::Err> {
+        let offset = s.start();
+        let v = self.p.parse(s).map_err(ConsumeErr::Inner)?;
+        if s.start() == offset {
+            return Err(ConsumeErr::NoProgress { offset })
+        }
+        Ok(v)
+    }
+
+    fn grammar(&self) -> Grammar {
+        self.p.grammar()
+    }
+}
+
+///Indicates that a [`MustConsume`] parser has failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, FromNever)]
+pub enum ConsumeErr<E> {
+    ///The inner parser failed
+    Inner(E),
+    ///The inner parser succeeded without consuming anything
+    NoProgress {
+        ///Where the parser started
+        offset: usize,
+    },
+}
+
+impl<E: Display> Display for ConsumeErr<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Inner(e) => write!(f, "{e}"),
+            Self::NoProgress { offset } => write!(f, "parser made no progress at offset {offset}"),
+        }
+    }
+}
+impl<E: std::error::Error> std::error::Error for ConsumeErr<E> {}
+
+/**
+Fails if another parser matches right after this one. The other parser is only looked at, so
+it never consumes anything, and on failure the string is rewound to where this parser started.
+```
+# use parsa::{ParserString, Parser};
+# use parsa::combinators::NotFollowedErr;
+# use parsa::builtins::{take, take_while_m_n};
+let keyword = take("in").not_followed_by(take_while_m_n(1, 1, char::is_alphanumeric));
+assert_eq!(keyword.parse(&mut ParserString::from("in x")), Ok("in"));
+
+let mut input = ParserString::from("int x");
+assert_eq!(keyword.parse(&mut input), Err(NotFollowedErr::Followed { offset: 2 }));
+assert_eq!(input.get(), "int x");
+```
+*/
+pub struct NotFollowedBy<T, U, P, Q> {
+    p: P,
+    q: Q,
+    t: PhantomData<T>,
+    u: PhantomData<U>,
+}
+
+clone_debug!(NotFollowedBy<T, U, P, Q> [P, Q] { p, q } { t, u });
+
+impl<T, U, P, Q> NotFollowedBy<T, U, P, Q> {
+    ///Constructs this parser.
+    pub const fn new(p: P, q: Q) -> Self { Self { p, q, t: PhantomData, u: PhantomData } }
+}
+
+impl<T, U, P, Q, I: ?Sized + Input> Parser<T, I> for NotFollowedBy<T, U, P, Q>
+where
+    P: Parser<T, I>,
+    Q: Parser<U, I>,
 {
+    type Err = NotFollowedErr<P::Err>;
+
+    fn parse(&self, s: &mut ParserInput<I>) -> Result<T, Self::Err> {
+        let start = s.checkpoint();
+        let v = self.p.parse(s).map_err(NotFollowedErr::Inner)?;
+        let end = s.checkpoint();
+        let followed = self.q.try_parse(s).is_ok();
+        s.rewind(end);
+        if followed {
+            s.rewind(start);
+            return Err(NotFollowedErr::Followed { offset: end.offset() })
+        }
+        Ok(v)
+    }
+
+    //the lookahead consumes nothing, so it isn't part of the grammar
+    fn grammar(&self) -> Grammar {
+        self.p.grammar()
+    }
+}
+
+///Indicates that a [`NotFollowedBy`] parser has failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, FromNever)]
+pub enum NotFollowedErr<E> {
+    ///The inner parser failed
+    Inner(E),
+    ///The inner parser succeeded, but was followed by what it mustn't be
+    Followed {
+        ///Where the inner parser ended
+        offset: usize,
+    },
+}
+
+impl<E: Display> Display for NotFollowedErr<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Inner(e) => write!(f, "{e}"),
+            Self::Followed { offset } => write!(f, "unexpected continuation at offset {offset}"),
+        }
+    }
+}
+impl<E: std::error::Error> std::error::Error for NotFollowedErr<E> {}
+
+/**
+Requires another parser to match at the same position as this one, keeping this parser's
+output. The other parser is only looked at, so only this parser's extent is consumed. The other
+parser is tried first, and its error is returned, converted with
+[error coercion rules](self#error-coercion-rules), if it fails.
+```
+# use parsa::{ParserString, Parser};
+# use parsa::builtins::{word, take, BuiltinErr};
+//a word that also starts with "0x"
+let hex = word.convert_err::<BuiltinErr>().and_also(take("0x"));
+assert_eq!(hex.parse(&mut ParserString::from("0xff")), Ok("0xff".to_owned()));
+
+let mut input = ParserString::from("ff");
+assert!(hex.parse(&mut input).is_err());
+assert_eq!(input.get(), "ff");
+```
+*/
+pub struct AndAlso<T, U, P, Q> {
+    p: P,
+    q: Q,
+    t: PhantomData<T>,
+    u: PhantomData<U>,
+}
+
+clone_debug!(AndAlso<T, U, P, Q> [P, Q] { p, q } { t, u });
+
+impl<T, U, P, Q> AndAlso<T, U, P, Q> {
     ///Constructs this parser.
-    pub fn new(p: P) -> Self { Self { p, t: PhantomData } }
+    pub const fn new(p: P, q: Q) -> Self { Self { p, q, t: PhantomData, u: PhantomData } }
 }
 
-impl<T, P> Parser<Vec<T>> for Many1<T, P>
-where 
-    P: Parser<T>
+impl<T, U, P, Q, E, I: ?Sized + Input> Parser<T, I> for AndAlso<T, U, P, Q>
+where
+    P: Parser<T, I>,
+    Q: Parser<U, I, Err = E>,
+    E: Into<P::Err>,
 {
     type Err = P::Err;
 
-    fn parse(&self, s: &mut ParserString) -> Result<Vec<T>, Self::Err> {
-        let mut out = vec![self.p.parse(s)?];
+    fn parse(&self, s: &mut ParserInput<I>) -> Result<T, Self::Err> {
+        let start = s.checkpoint();
+        let also = self.q.try_parse(s);
+        s.rewind(start);
+        also.map_err(Into::into)?;
+        self.p.parse(s)
+    }
+
+    //the lookahead consumes nothing, so it isn't part of the grammar
+    fn grammar(&self) -> Grammar {
+        self.p.grammar()
+    }
+}
+
+/**
+Fails with [`BacktrackErr::Exceeded`] if a parser backtracks more than a [`BacktrackLimit`] allows.
+This turns runaway backtracking on adversarial input into an error, instead of an apparent hang.
+The limit only applies while this parser runs, and the previous one is restored after.
+```
+# use parsa::{ParserString, Parser, BacktrackLimit};
+# use parsa::combinators::BacktrackErr;
+# use parsa::builtins::take;
+//every alternative reads "aa" before failing
+let p = take("aab").or(take("aac")).or(take("aad"));
+let limited = p.backtrack_limited(BacktrackLimit::bytes(3));
+
+let mut input = ParserString::from("aad");
+assert_eq!(limited.parse(&mut input), Err(BacktrackErr::Exceeded { offset: 3 }));
+```
+*/
+pub struct BacktrackLimited<T, P> {
+    p: P,
+    limit: BacktrackLimit,
+    t: PhantomData<T>
+}
+
+clone_debug!(BacktrackLimited<T, P> [P] { p, limit } { t });
+
+impl<T, P> BacktrackLimited<T, P> {
+    ///Constructs this parser.
+    pub const fn new(p: P, limit: BacktrackLimit) -> Self { Self { p, limit, t: PhantomData } }
+}
+
+impl<T, P, I: ?Sized + Input> Parser<T, I> for BacktrackLimited<T, P>
+where
+    P: Parser<T, I>
+{
+    type Err = BacktrackErr<P::Err>;
+
+    fn parse(&self, s: &mut ParserInput<I>) -> Result<T, Self::Err> {
+        let outer = std::mem::take(&mut s.backtrack);
+        s.set_backtrack_limit(self.limit);
+        let res = self.p.parse(s);
+        let exceeded = s.backtrack.exceeded;
+        s.backtrack = outer;
+
+        match exceeded {
+            Some(offset) => {
+                s.resume();
+                Err(BacktrackErr::Exceeded { offset })
+            },
+            None => res.map_err(BacktrackErr::Inner),
+        }
+    }
+
+    fn grammar(&self) -> Grammar {
+        self.p.grammar()
+    }
+}
+
+///Indicates that a [`BacktrackLimited`] parser has failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, FromNever)]
+pub enum BacktrackErr<E> {
+    ///The inner parser failed
+    Inner(E),
+    ///The inner parser backtracked more than allowed
+    Exceeded {
+        ///Where the string was when the limit was exceeded
+        offset: usize,
+    },
+}
+
+impl<E: Display> Display for BacktrackErr<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Inner(e) => write!(f, "{e}"),
+            Self::Exceeded { offset } => write!(f, "backtrack limit exceeded at offset {offset}"),
+        }
+    }
+}
+impl<E: std::error::Error> std::error::Error for BacktrackErr<E> {}
+
+/**
+Fails with [`SizeErr::Exceeded`] if a parser goes over any of the given [`SizeLimits`]. The limits
+only apply while this parser runs, and the previous ones are restored after.
+```
+# use parsa::{ParserString, Parser, SizeLimits, SizeExceeded};
+# use parsa::combinators::SizeErr;
+# use parsa::builtins::take;
+let p = take("a").many();
+
+let mut input = ParserString::from("aaaaa");
+let res = p.by_ref().size_limited(SizeLimits::input(4)).parse(&mut input);
+assert_eq!(res, Err(SizeErr::Exceeded(SizeExceeded::Input { len: 5, max: 4 })));
+
+let mut input = ParserString::from("aaaaa");
+let res = p.size_limited(SizeLimits::NONE.items(3)).parse(&mut input);
+assert_eq!(res, Err(SizeErr::Exceeded(SizeExceeded::Items { offset: 3, max: 3 })));
+```
+*/
+pub struct SizeLimited<T, P> {
+    p: P,
+    limits: SizeLimits,
+    t: PhantomData<T>
+}
+
+clone_debug!(SizeLimited<T, P> [P] { p, limits } { t });
+
+impl<T, P> SizeLimited<T, P> {
+    ///Constructs this parser.
+    pub const fn new(p: P, limits: SizeLimits) -> Self { Self { p, limits, t: PhantomData } }
+}
+
+impl<T, P, I: ?Sized + Input> Parser<T, I> for SizeLimited<T, P>
+where
+    P: Parser<T, I>
+{
+    type Err = SizeErr<P::Err>;
+
+    fn parse(&self, s: &mut ParserInput<I>) -> Result<T, Self::Err> {
+        let outer = s.sizes;
+        s.set_size_limits(self.limits);
+        let res = match s.sizes.exceeded {
+            Some(e) => Err(SizeErr::Exceeded(e)),
+            None => self.p.parse(s).map_err(SizeErr::Inner),
+        };
+        let exceeded = s.sizes.exceeded;
+        s.sizes = outer;
+
+        match exceeded {
+            Some(e) => {
+                s.resume();
+                Err(SizeErr::Exceeded(e))
+            },
+            None => res,
+        }
+    }
+
+    fn grammar(&self) -> Grammar {
+        self.p.grammar()
+    }
+}
+
+///Indicates that a [`SizeLimited`] parser has failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, FromNever)]
+pub enum SizeErr<E> {
+    ///The inner parser failed
+    Inner(E),
+    ///One of the limits was exceeded
+    Exceeded(SizeExceeded),
+}
+
+impl<E: Display> Display for SizeErr<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Inner(e) => write!(f, "{e}"),
+            Self::Exceeded(e) => write!(f, "{e}"),
+        }
+    }
+}
+impl<E: std::error::Error> std::error::Error for SizeErr<E> {}
+
+/**
+Fails with [`FuelErr::Exhausted`] if a parser runs more combinators than `fuel` allows. See
+[`ParserInput::set_fuel`] for what costs fuel. The limit only applies while this parser runs,
+and the previous one is restored after, less whatever this parser used.
+```
+# use parsa::{ParserString, Parser};
+# use parsa::combinators::FuelErr;
+# use parsa::builtins::take;
+let p = take("a").or(take("b")).many();
+
+let mut input = ParserString::from("abab");
+assert_eq!(p.by_ref().fuel_limited(100).parse(&mut input).unwrap(), ["a", "b", "a", "b"]);
+
+let mut input = ParserString::from("abababab");
+assert_eq!(p.fuel_limited(6).parse(&mut input), Err(FuelErr::Exhausted { offset: 2 }));
+```
+*/
+pub struct FuelLimited<T, P> {
+    p: P,
+    fuel: usize,
+    t: PhantomData<T>
+}
+
+clone_debug!(FuelLimited<T, P> [P] { p, fuel } { t });
+
+impl<T, P> FuelLimited<T, P> {
+    ///Constructs this parser.
+    pub const fn new(p: P, fuel: usize) -> Self { Self { p, fuel, t: PhantomData } }
+}
+
+impl<T, P, I: ?Sized + Input> Parser<T, I> for FuelLimited<T, P>
+where
+    P: Parser<T, I>
+{
+    type Err = FuelErr<P::Err>;
+
+    fn parse(&self, s: &mut ParserInput<I>) -> Result<T, Self::Err> {
+        let outer = s.fuel;
+        let fuel = outer.left.map_or(self.fuel, |left| left.min(self.fuel));
+        s.set_fuel(Some(fuel));
+        let res = self.p.parse(s);
+        let inner = s.fuel;
+        s.fuel = outer;
+        s.fuel.used += inner.used;
+        s.fuel.left = outer.left.map(|left| left.saturating_sub(inner.used));
+
+        match inner.exhausted {
+            //running out of the outer fuel isn't this parser's error to report
+            Some(offset) if outer.left.is_none_or(|left| left > self.fuel) => {
+                s.resume();
+                Err(FuelErr::Exhausted { offset })
+            },
+            Some(offset) => {
+                s.fuel.exhausted = Some(offset);
+                res.map_err(FuelErr::Inner)
+            },
+            None => res.map_err(FuelErr::Inner),
+        }
+    }
+
+    fn grammar(&self) -> Grammar {
+        self.p.grammar()
+    }
+}
 
-        while let Ok(v) = self.p.try_parse(s) {
-            out.push(v)
+///Indicates that a [`FuelLimited`] parser has failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, FromNever)]
+pub enum FuelErr<E> {
+    ///The inner parser failed
+    Inner(E),
+    ///The inner parser ran out of fuel
+    Exhausted {
+        ///Where the string was when the fuel ran out
+        offset: usize,
+    },
+}
+
+impl<E: Display> Display for FuelErr<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Inner(e) => write!(f, "{e}"),
+            Self::Exhausted { offset } => write!(f, "ran out of fuel at offset {offset}"),
         }
+    }
+}
+impl<E: std::error::Error> std::error::Error for FuelErr<E> {}
+
+/**
+Recovers from a failing parser by producing a fallback value from its error. The string is rewound
+to where the parser started, so this parser never fails.
+
+Use [`RecoverWith::report`] to also record the error in the string's
+[diagnostics](ParserInput::diagnostics).
+```
+# use parsa::{ParserString, Parser, Severity};
+# use parsa::builtins::int;
+let mut input = ParserString::from("abc");
+let num = int::<i32, _>
+    .recover_with(|_| -1)
+    .report()
+    .parse(&mut input);
+
+assert_eq!(num, Ok(-1));
+assert_eq!(input.get(), "abc");
+let diagnostic = &input.diagnostics()[0];
+assert_eq!(diagnostic.severity, Severity::Error);
+assert_eq!(diagnostic.message, "error parsing int: invalid digit found in string");
+```
+*/
+pub struct RecoverWith<T, E, P, F> {
+    p: P,
+    f: F,
+    report: Option<fn(&E) -> String>,
+    t: PhantomData<T>,
+}
+
+impl<T, E, P: Clone, F: Clone> Clone for RecoverWith<T, E, P, F> {
+    fn clone(&self) -> Self {
+        Self { p: self.p.clone(), f: self.f.clone(), report: self.report, t: PhantomData }
+    }
+}
+
+//the recovery function is usually a closure, so it isn't shown
+impl<T, E, P: Debug, F> Debug for RecoverWith<T, E, P, F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RecoverWith").field("p", &self.p).finish_non_exhaustive()
+    }
+}
+
+impl<T, E, P, F> RecoverWith<T, E, P, F> {
+    ///Constructs this parser.
+    pub const fn new(p: P, f: F) -> Self { Self { p, f, report: None, t: PhantomData } }
+
+    ///Record recovered errors as [`Severity::Error`] diagnostics.
+    pub fn report(mut self) -> Self 
+    where E: Display
+    {
+        self.report = Some(|e| e.to_string());
+        self
+    }
+}
+
+impl<T, E, P, F, I: ?Sized + Input> Parser<T, I> for RecoverWith<T, E, P, F>
+where
+    P: Parser<T, I, Err = E>,
+    F: Fn(E) -> T,
+{
+    type Err = Infallible;
+
+    fn parse(&self, s: &mut ParserInput<I>) -> Result<T, Self::Err> {
+        let cp = s.checkpoint();
+        match self.p.parse(s) {
+            Ok(v) => Ok(v),
+            Err(e) => {
+                s.rewind(cp);
+                if let Some(report) = self.report {
+                    s.emit(Diagnostic { severity: Severity::Error, message: report(&e), offset: cp.offset() });
+                }
+                Ok((self.f)(e))
+            },
+        }
+    }
+
+    fn grammar(&self) -> Grammar {
+        self.p.grammar()
+    }
+}
+
+/**
+Tries a parser, and if it fails, rewinds and hands its error to `f` to pick a fallback parser.
+The fallback can be a different branch, [`success`](crate::builtins::success) to synthesize a
+value, or [`fail`](crate::builtins::fail) to report an upgraded error. Unlike [`Or`], the
+fallback can depend on why the first parser failed.
+```
+# use parsa::{ParserString, Parser};
+# use parsa::builtins::{take, success, fail, TakeErr};
+#[derive(Debug, PartialEq)]
+enum Literal { Bool(bool), Null }
+let literal = take("true").map(|_| Literal::Bool(true))
+    .or_else(|e: TakeErr| match e {
+        //too little input left for "true", so it can't be anything else either
+        TakeErr::NoSpace { .. } => fail(e).boxed(),
+        TakeErr::NoMatch { .. } => take("null").map(|_| Literal::Null).boxed(),
+    });
+
+assert_eq!(literal.parse(&mut ParserString::from("null")), Ok(Literal::Null));
+assert!(matches!(literal.parse(&mut ParserString::from("tr")), Err(TakeErr::NoSpace { .. })));
+```
+*/
+pub struct OrElse<T, P, F> {
+    p: P,
+    f: F,
+    t: PhantomData<T>,
+}
+
+impl<T, P: Clone, F: Clone> Clone for OrElse<T, P, F> {
+    fn clone(&self) -> Self {
+        Self { p: self.p.clone(), f: self.f.clone(), t: PhantomData }
+    }
+}
+
+//the fallback function is usually a closure, so it isn't shown
+impl<T, P: Debug, F> Debug for OrElse<T, P, F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OrElse").field("p", &self.p).finish_non_exhaustive()
+    }
+}
+
+impl<T, P, F> OrElse<T, P, F> {
+    ///Constructs this parser.
+    pub const fn new(p: P, f: F) -> Self { Self { p, f, t: PhantomData } }
+}
+
+impl<T, P, F, P2, E, I: ?Sized + Input> Parser<T, I> for OrElse<T, P, F>
+where
+    P: Parser<T, I>,
+    F: Fn(P::Err) -> P2,
+    P2: Parser<T, I, Err = E>,
+    E: Into<P::Err>,
+{
+    type Err = P::Err;
+
+    fn parse(&self, s: &mut ParserInput<I>) -> Result<T, Self::Err> {
+        rule(s, "or_else", |s| {
+            let cp = s.checkpoint();
+            match self.p.parse(s) {
+                Ok(v) => Ok(v),
+                Err(e) => {
+                    s.rewind(cp);
+                    (self.f)(e).parse(s).map_err(Into::into)
+                },
+            }
+        })
+    }
+
+    fn grammar(&self) -> Grammar {
+        self.p.grammar().alt(Grammar::Opaque)
+    }
+}
+
+/**
+Recovers from a missing token by pretending it was there. If the parser fails, the string is
+rewound to where it started, a [`Severity::Error`] diagnostic naming what was missing is recorded,
+and the value from `f` is returned, marked as [`synthesized`](Inserted::synthesized). Parsing then
+continues as if the token had been found, which avoids the cascade of errors that skipping input
+would cause.
+```
+# use parsa::{ParserString, Parser};
+# use parsa::builtins::{word, BuiltinErr};
+let call = "f".convert_err::<BuiltinErr>()
+    .after("(")
+    .chain(word)
+    .chain(")".insert_missing(|| ")"));
+
+let mut input = ParserString::from("f(x");
+let res = call.parse_tolerant(&mut input, |_| unreachable!());
+assert!(res.value.1.synthesized);
+assert_eq!(res.errors().next().unwrap().to_string(), r#"error at offset 3: missing ")""#);
+```
+*/
+pub struct InsertMissing<T, P, F> {
+    p: P,
+    f: F,
+    t: PhantomData<T>,
+}
+
+///The output of an [`InsertMissing`] parser.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Inserted<T> {
+    ///The parsed value, or the stand-in if the token was missing
+    pub value: T,
+    ///Whether the token was missing, and `value` is a stand-in
+    pub synthesized: bool,
+}
+
+impl<T, P: Clone, F: Clone> Clone for InsertMissing<T, P, F> {
+    fn clone(&self) -> Self {
+        Self { p: self.p.clone(), f: self.f.clone(), t: PhantomData }
+    }
+}
+
+//the stand-in function is usually a closure, so it isn't shown
+impl<T, P: Debug, F> Debug for InsertMissing<T, P, F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("InsertMissing").field("p", &self.p).finish_non_exhaustive()
+    }
+}
+
+impl<T, P, F> InsertMissing<T, P, F> {
+    ///Constructs this parser.
+    pub const fn new(p: P, f: F) -> Self { Self { p, f, t: PhantomData } }
+}
+
+impl<T, P, F, I: ?Sized + Input> Parser<Inserted<T>, I> for InsertMissing<T, P, F>
+where
+    P: Parser<T, I>,
+    F: Fn() -> T,
+{
+    type Err = Infallible;
+
+    fn parse(&self, s: &mut ParserInput<I>) -> Result<Inserted<T>, Self::Err> {
+        let cp = s.checkpoint();
+        match self.p.parse(s) {
+            Ok(value) => Ok(Inserted { value, synthesized: false }),
+            Err(_) => {
+                s.rewind(cp);
+                let message = format!("missing {}", self.p.grammar());
+                s.emit(Diagnostic { severity: Severity::Error, message, offset: cp.offset() });
+                Ok(Inserted { value: (self.f)(), synthesized: true })
+            },
+        }
+    }
+
+    fn grammar(&self) -> Grammar {
+        Grammar::repeat(self.p.grammar(), 0, Some(1))
+    }
+}
+
+/**
+Gives a parser a name, shown when [describing its grammar](crate::grammar), and recorded as an
+[`Expectation`] when it fails.
+
+With the `tracing` feature, each run opens a `TRACE` level `tracing` span named `parse`, with
+the parser's `name` and the `offset` it started at, and records an event when it succeeds or fails.
+```
+# use parsa::{ParserString, Parser};
+# use parsa::grammar::Grammar;
+# use parsa::builtins::word;
+let ident = word.named("ident");
+assert_eq!(ident.grammar(), Grammar::named("ident", Grammar::Opaque));
+```
+*/
+pub struct Named<T, P> {
+    p: P,
+    name: String,
+    t: PhantomData<T>
+}
+
+clone_debug!(Named<T, P> [P] { p, name } { t });
+
+impl<T, P> Named<T, P> {
+    ///Constructs this parser.
+    pub fn new(p: P, name: impl Into<String>) -> Self { Self { p, name: name.into(), t: PhantomData } }
+}
+
+impl<T, P, I: ?Sized + Input> Parser<T, I> for Named<T, P>
+where
+    P: Parser<T, I>
+{
+    type Err = P::Err;
+
+    fn parse(&self, s: &mut ParserInput<I>) -> Result<T, Self::Err> {
+        let start = s.start();
+        #[cfg(feature = "tracing")]
+        let span = tracing::trace_span!("parse", name = %self.name, offset = start);
+        #[cfg(feature = "tracing")]
+        let _guard = span.enter();
+
+        let res = rule(s, &self.name, |s| self.p.parse(s));
+        #[cfg(feature = "tracing")]
+        match &res {
+            Ok(_) => tracing::trace!(end = s.start(), "success"),
+            Err(_) => tracing::trace!(end = s.start(), "failure"),
+        }
+        if res.is_err() {
+            s.expect(start, Expectation::Named(self.name.clone()));
+        }
+        res
+    }
+
+    fn grammar(&self) -> Grammar {
+        Grammar::named(self.name.clone(), self.p.grammar())
+    }
+}
+
+/**
+Reports the given grammar for a parser whose structure can't be seen, like a plain function.
+```
+# use parsa::{Parser, ParserString};
+# use parsa::grammar::{Grammar, Builtin};
+# use parsa::builtins::int;
+let n = int::<u8, _>.with_grammar(Grammar::Builtin(Builtin::Int));
+assert_eq!(n.describe(), "int");
+assert_eq!(n.parse(&mut ParserString::from("12")).unwrap(), 12);
+```
+*/
+pub struct WithGrammar<T, P> {
+    p: P,
+    grammar: Grammar,
+    t: PhantomData<T>
+}
+
+clone_debug!(WithGrammar<T, P> [P] { p, grammar } { t });
+
+impl<T, P> WithGrammar<T, P> {
+    ///Constructs this parser.
+    pub fn new(p: P, grammar: Grammar) -> Self { Self { p, grammar, t: PhantomData } }
+}
+
+impl<T, P, I: ?Sized + Input> Parser<T, I> for WithGrammar<T, P>
+where
+    P: Parser<T, I>
+{
+    type Err = P::Err;
+
+    fn parse(&self, s: &mut ParserInput<I>) -> Result<T, Self::Err> {
+        self.p.parse(s)
+    }
+
+    fn grammar(&self) -> Grammar {
+        self.grammar.clone()
+    }
+}
+
+type Handler<V, I, E> = (Grammar, Rc<dyn Fn(&mut ParserInput<I>) -> Result<V, E>>);
+
+/**
+Parses type-length-value records. A tag is read with the first parser and a length with the
+second, then the parser registered for that tag with [`Tlv::on`] runs over exactly that many items
+of input. Errors are coerced like [`Chain`], and outputs the tag and value.
+```
+# use parsa::{ParserBytes, Parser};
+# use parsa::combinators::TlvErr;
+# use parsa::builtins::{u8, be_u16, cstr, ReadErr, StrErr};
+#[derive(Debug, PartialEq)]
+enum Field { Name(String), Port(u16), Unknown(Vec<u8>) }
+
+let field = u8.convert_err::<StrErr>()
+    .tlv(u8)
+    .on(1, cstr.map(Field::Name))
+    .on(2, be_u16.map(Field::Port))
+    .otherwise(|s: &mut ParserBytes| Ok::<_, ReadErr>(Field::Unknown(s.take(s.len()).to_vec())));
+
+let mut input = ParserBytes::from(b"\x01\x04web\0\x02\x02\x1f\x90\x09\x01!");
+let fields = field.many().parse(&mut input).unwrap();
+assert_eq!(fields, vec![
+    (1, Field::Name("web".into())),
+    (2, Field::Port(8080)),
+    (9, Field::Unknown(b"!".to_vec())),
+]);
+```
+*/
+pub struct Tlv<K, N, V, T, L, I: ?Sized, E> {
+    tag: T,
+    len: L,
+    handlers: Vec<Handler<V, I, E>>,
+    index: HashMap<K, usize>,
+    otherwise: Option<Handler<V, I, E>>,
+    n: PhantomData<N>,
+}
+
+impl<K: Clone, N, V, T: Clone, L: Clone, I: ?Sized, E> Clone for Tlv<K, N, V, T, L, I, E> {
+    fn clone(&self) -> Self {
+        Self {
+            tag: self.tag.clone(),
+            len: self.len.clone(),
+            handlers: self.handlers.clone(),
+            index: self.index.clone(),
+            otherwise: self.otherwise.clone(),
+            n: PhantomData,
+        }
+    }
+}
+
+//handlers are shown by their grammar
+impl<K: Debug, N, V, T: Debug, L: Debug, I: ?Sized, E> Debug for Tlv<K, N, V, T, L, I, E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        struct Handlers<'a, K>(&'a HashMap<K, usize>, Vec<String>);
+        impl<K: Debug> Debug for Handlers<'_, K> {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.debug_map().entries(self.0.iter().map(|(k, &i)| (k, &self.1[i]))).finish()
+            }
+        }
+
+        let grammars = self.handlers.iter().map(|(g, _)| g.to_string()).collect();
+        f.debug_struct("Tlv")
+            .field("tag", &self.tag)
+            .field("len", &self.len)
+            .field("handlers", &Handlers(&self.index, grammars))
+            .field("otherwise", &self.otherwise.as_ref().map(|(g, _)| g.to_string()))
+            .finish()
+    }
+}
+
+impl<K, N, V, T, L, I: ?Sized, E> Tlv<K, N, V, T, L, I, E> {
+    ///Constructs this parser.
+    pub fn new(tag: T, len: L) -> Self {
+        Self { tag, len, handlers: vec![], index: HashMap::new(), otherwise: None, n: PhantomData }
+    }
+}
+
+impl<K: Hash + Eq, N, V, T, L, I: ?Sized + Input + 'static, E> Tlv<K, N, V, T, L, I, E> {
+    ///Parse the value of records tagged with `tag` with `p`, replacing any previous parser for it.
+    pub fn on<P, E2>(mut self, tag: K, p: P) -> Self
+    where P: Parser<V, I, Err = E2> + 'static, E2: Into<E>
+    {
+        let handler: Handler<V, I, E> = (p.grammar(), Rc::new(move |s| p.parse(s).map_err(Into::into)));
+        match self.index.get(&tag) {
+            Some(&i) => self.handlers[i] = handler,
+            None => {
+                self.index.insert(tag, self.handlers.len());
+                self.handlers.push(handler);
+            },
+        }
+        self
+    }
+
+    ///Parse the value of records with unregistered tags with `p`, instead of failing.
+    pub fn otherwise<P, E2>(mut self, p: P) -> Self
+    where P: Parser<V, I, Err = E2> + 'static, E2: Into<E>
+    {
+        self.otherwise = Some((p.grammar(), Rc::new(move |s| p.parse(s).map_err(Into::into))));
+        self
+    }
+}
+
+impl<K, N, V, T, L, I, E> Parser<(K, V), I> for Tlv<K, N, V, T, L, I, E>
+where
+    K: Hash + Eq,
+    N: TryInto<usize>,
+    T: Parser<K, I, Err = E>,
+    L: Parser<N, I>,
+    L::Err: Into<E>,
+    I: ?Sized + Input,
+{
+    type Err = TlvErr<E>;
+
+    fn parse(&self, s: &mut ParserInput<I>) -> Result<(K, V), Self::Err> {
+        let start = s.start();
+        let tag = self.tag.parse(s).map_err(TlvErr::Inner)?;
+        let n = self.len.parse(s).map_err(|e| TlvErr::Inner(e.into()))?;
+        let n = n.try_into().unwrap_or(usize::MAX);
+
+        let handler = match self.index.get(&tag) {
+            Some(&i) => &self.handlers[i],
+            None => self.otherwise.as_ref().ok_or(TlvErr::UnknownTag { offset: start })?,
+        };
+        let offset = s.start();
+        let end = s.region_end(n).ok_or(TlvErr::TooLong { len: n, offset })?;
+
+        let v = s.bounded(end, |s| (handler.1)(s)).map_err(TlvErr::Inner)?;
+        if s.start() != end {
+            return Err(TlvErr::Unconsumed { offset: s.start(), left: end - s.start() })
+        }
+        Ok((tag, v))
+    }
+
+    fn grammar(&self) -> Grammar {
+        let values = self.handlers.iter().chain(&self.otherwise)
+            .map(|(g, _)| g.clone())
+            .reduce(Grammar::alt)
+            .unwrap_or(Grammar::Opaque);
+        self.tag.grammar().seq(self.len.grammar()).seq(values)
+    }
+}
+
+///Indicates that a [`Tlv`] parser has failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, FromNever)]
+pub enum TlvErr<E> {
+    ///The tag, length or value parser failed
+    Inner(E),
+    ///No parser was registered for the tag
+    UnknownTag {
+        ///Where the record starts
+        offset: usize,
+    },
+    ///The length was longer than the remaining input
+    TooLong {
+        ///The length that was read
+        len: usize,
+        ///Where the value would have started
+        offset: usize,
+    },
+    ///The value parser didn't consume the whole value
+    Unconsumed {
+        ///Where the value parser stopped
+        offset: usize,
+        ///How much of the value was left, in the units of the input
+        left: usize,
+    },
+}
+
+impl<E: Display> Display for TlvErr<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Inner(e) => write!(f, "{e}"),
+            Self::UnknownTag { offset } => write!(f, "unknown tag at offset {offset}"),
+            Self::TooLong { len, offset } => write!(f, "length {len} at offset {offset} is longer than the remaining input"),
+            Self::Unconsumed { offset, left } => write!(f, "{left} unconsumed at offset {offset}"),
+        }
+    }
+}
+impl<E: std::error::Error> std::error::Error for TlvErr<E> {}
+
+///A borrowed parser. See [`Parser::by_ref`].
+pub struct ByRef<'a, T, P> {
+    p: &'a P,
+    t: PhantomData<T>
+}
+
+impl<T, P> Clone for ByRef<'_, T, P> {
+    fn clone(&self) -> Self { *self }
+}
+impl<T, P> Copy for ByRef<'_, T, P> {}
+
+impl<T, P: Debug> Debug for ByRef<'_, T, P> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("ByRef").field(self.p).finish()
+    }
+}
+
+impl<'a, T, P> ByRef<'a, T, P> {
+    ///Constructs this parser.
+    pub const fn new(p: &'a P) -> Self { Self { p, t: PhantomData } }
+}
+
+impl<T, P, I: ?Sized + Input> Parser<T, I> for ByRef<'_, T, P>
+where
+    P: Parser<T, I>
+{
+    type Err = P::Err;
+
+    fn parse(&self, s: &mut ParserInput<I>) -> Result<T, Self::Err> {
+        self.p.parse(s)
+    }
+
+    fn grammar(&self) -> Grammar {
+        self.p.grammar()
+    }
+}
+
+/**
+Marks a parser as consuming input before failing on purpose, so that it isn't reported by a
+[`MisuseCheck`](crate::MisuseCheck).
+```
+# use parsa::{Parser, ParserString, MisuseCheck};
+# use parsa::builtins::take;
+let mut input = ParserString::from("let y");
+input.set_misuse_check(MisuseCheck::Panic);
+
+let binding = take("let ").replace(take("x")).committed();
+assert!(binding.or(take("fn")).parse(&mut input).is_err());
+```
+*/
+pub struct Committed<T, P> {
+    p: P,
+    t: PhantomData<T>
+}
+
+clone_debug!(Committed<T, P> [P] { p } { t });
+
+impl<T, P> Committed<T, P> {
+    ///Constructs this parser.
+    pub const fn new(p: P) -> Self { Self { p, t: PhantomData } }
+}
+
+impl<T, P, I: ?Sized + Input> Parser<T, I> for Committed<T, P>
+where
+    P: Parser<T, I>
+{
+    type Err = P::Err;
+
+    fn parse(&self, s: &mut ParserInput<I>) -> Result<T, Self::Err> {
+        self.p.parse(s).inspect_err(|_| s.misuse.committed = Some(s.start()))
+    }
+
+    fn grammar(&self) -> Grammar {
+        self.p.grammar()
+    }
+}
+
+/**
+A parser backed by an [`FnMut`] closure, so it can update state as it parses. Constructed with
+[`from_fn_mut`](crate::from_fn_mut).
+
+The closure is borrowed mutably for each parse, so a parser that runs itself recursively panics.
+```
+# use parsa::{Parser, ParserString, from_fn_mut};
+# use parsa::builtins::{word, whitespace, WordErr};
+let mut seen = vec![];
+let ident = from_fn_mut(|s: &mut ParserString| {
+    let w = word.after(whitespace).parse(s)?;
+    seen.push(w.len());
+    Ok::<_, WordErr>(w)
+});
+let words = ident.many().parse(&mut ParserString::from("a bc def")).unwrap();
+assert_eq!(words.len(), 3);
+assert_eq!(seen, [1, 2, 3]);
+```
+*/
+pub struct FromFnMut<F> {
+    f: RefCell<F>,
+}
+
+impl<F> FromFnMut<F> {
+    ///Constructs this parser.
+    pub const fn new(f: F) -> Self { Self { f: RefCell::new(f) } }
+
+    ///Returns the closure.
+    pub fn into_inner(self) -> F {
+        self.f.into_inner()
+    }
+}
+
+impl<F: Clone> Clone for FromFnMut<F> {
+    fn clone(&self) -> Self { Self::new(self.f.borrow().clone()) }
+}
+
+impl<F> Debug for FromFnMut<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FromFnMut").finish_non_exhaustive()
+    }
+}
+
+impl<T, E, I: ?Sized + Input, F> Parser<T, I> for FromFnMut<F>
+where
+    F: FnMut(&mut ParserInput<I>) -> Result<T, E>
+{
+    type Err = E;
+
+    fn parse(&self, s: &mut ParserInput<I>) -> Result<T, Self::Err> {
+        let mut f = self.f.try_borrow_mut().expect("`FromFnMut` parser ran itself recursively");
+        f(s)
+    }
+}
+
+type StaticInit<T, E, I> = fn() -> Box<dyn DynParser<T, E, I>>;
+type StaticBuilt<T, E, I> = Rc<Box<dyn DynParser<T, E, I>>>;
+type StaticKey = (usize, TypeId);
+
+thread_local! {
+    //parsers built by `StaticParser`s on this thread, keyed by their init function and parser
+    //type, or None while one is being built. each is cloned out before parsing so that no borrow
+    //is held, which lets static parsers refer to themselves
+    static BUILT: RefCell<HashMap<StaticKey, Option<Rc<dyn Any>>>> = RefCell::new(HashMap::new());
+}
+
+/**
+A parser that is built the first time it runs, so that it can be stored in a `static` and shared.
+Usually declared with [`static_parser!`](crate::static_parser), or constructed directly with a
+closure that boxes the parser.
+
+The parser is built once on each thread that uses it, so it doesn't need to be [`Send`] or
+[`Sync`]. Static parsers can refer to each other and to themselves, which makes them a convenient
+way to write recursive grammars. With the `stacker` feature, they are also `StackSafe`, so
+deeply nested input doesn't overflow the stack.
+
+Combinators over plain functions can also be built in constant expressions, as their
+constructors are `const`, but spelling out their types is rarely practical.
+```
+# use std::convert::Infallible;
+# use parsa::{Parser, ParserString, combinators::StaticParser};
+# use parsa::builtins::{word, whitespace};
+static WORDS: StaticParser<Vec<String>, Infallible> = StaticParser::new(|| {
+    Box::new(word.after(whitespace).many())
+});
+
+assert_eq!(WORDS.parse(&mut ParserString::from("a b")), Ok(vec!["a".into(), "b".into()]));
+```
+*/
+pub struct StaticParser<T, E, I: ?Sized = str> {
+    init: StaticInit<T, E, I>,
+}
+
+impl<T, E, I: ?Sized> StaticParser<T, E, I> {
+    ///Constructs this parser, which is built by `init` when first used. Parsers constructed with
+    ///the same `init` share the parser it builds.
+    pub const fn new(init: StaticInit<T, E, I>) -> Self { Self { init } }
+}
+
+impl<T: 'static, E: 'static, I: ?Sized + 'static> StaticParser<T, E, I> {
+    //the built parser, or None while it is being built. parsers with the same init function build
+    //the same parser, so they can share it
+    fn get(&self) -> Option<StaticBuilt<T, E, I>> {
+        let key = (self.init as usize, TypeId::of::<Box<dyn DynParser<T, E, I>>>());
+        let any = match BUILT.with_borrow(|b| b.get(&key).cloned()) {
+            Some(any) => any?,
+            None => {
+                BUILT.with_borrow_mut(|b| b.insert(key, None));
+                let p: Rc<dyn Any> = Rc::new((self.init)());
+                BUILT.with_borrow_mut(|b| b.insert(key, Some(p.clone())));
+                p
+            },
+        };
+        Some(any.downcast().expect("static parsers are keyed by type"))
+    }
+}
+
+impl<T: 'static, E: 'static, I: ?Sized + Input + 'static> Parser<T, I> for StaticParser<T, E, I> {
+    type Err = E;
+
+    fn parse(&self, s: &mut ParserInput<I>) -> Result<T, Self::Err> {
+        let p = self.get().expect("static parser ran while it was being built");
+        #[cfg(feature = "stacker")]
+        return stack_safe(|| p.parse_dyn(s));
+        #[cfg(not(feature = "stacker"))]
+        p.parse_dyn(s)
+    }
+
+    //a parser that refers to itself sees its own grammar as opaque while it is being built
+    fn grammar(&self) -> Grammar {
+        self.get().map_or(Grammar::Opaque, |p| p.grammar_dyn())
+    }
+}
+
+impl<T, E, I: ?Sized> Debug for StaticParser<T, E, I> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StaticParser").finish_non_exhaustive()
+    }
+}
+/**
+Declares a `static` parser, built the first time it runs. See
+[`StaticParser`](crate::combinators::StaticParser).
+```
+# use parsa::{Parser, ParserString, static_parser};
+# use parsa::builtins::{word, whitespace, take, int, BuiltinErr};
+static_parser! {
+    ///A `name = value` assignment
+    pub static ASSIGN: (String, i32), BuiltinErr = word.convert_err::<BuiltinErr>()
+        .after(whitespace)
+        .after(take("=").after(whitespace))
+        .chain(int::<i32, _>);
+}
+
+assert_eq!(ASSIGN.parse(&mut ParserString::from("x = 5")).unwrap(), ("x".into(), 5));
+```
+*/
+#[macro_export]
+macro_rules! static_parser {
+    ($(#[$attr:meta])* $vis:vis static $name:ident: $t:ty, $e:ty $(, $i:ty)? = $p:expr;) => {
+        $(#[$attr])*
+        $vis static $name: $crate::combinators::StaticParser<$t, $e $(, $i)?> =
+            $crate::combinators::StaticParser::new(|| ::std::boxed::Box::new($p));
+    };
+}
+
+/**
+A fixed set of parsers with the same output, tried by [`Longest`]. Implemented for tuples of up
+to eight parsers, where the errors of the others convert into the error of the first, following
+the [error coercion rules](crate::combinators#error-coercion-rules).
+*/
+pub trait Alternatives<T, I: ?Sized + Input> {
+    ///The error type of the first alternative
+    type Err;
+
+    ///The amount of alternatives.
+    fn len(&self) -> usize;
+
+    ///Returns `true` if there are no alternatives.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    ///Runs the `n`th alternative. Panics if `n` is out of bounds.
+    fn parse_nth(&self, n: usize, s: &mut ParserInput<I>) -> Result<T, Self::Err>;
+
+    ///The grammars of every alternative.
+    fn grammars(&self) -> Vec<Grammar>;
+}
+
+macro_rules! alternatives {
+    ($p1:ident $(, $n:tt $p:ident)*) => {
+        impl<T, I: ?Sized + Input, $p1: Parser<T, I>, $($p: Parser<T, I>),*> Alternatives<T, I> for ($p1, $($p),*)
+        where $($p::Err: Into<$p1::Err>),*
+        {
+            type Err = $p1::Err;
+
+            fn len(&self) -> usize {
+                1 $(+ { let _ = $n; 1 })*
+            }
+
+            fn parse_nth(&self, n: usize, s: &mut ParserInput<I>) -> Result<T, Self::Err> {
+                match n {
+                    0 => self.0.parse(s),
+                    $($n => self.$n.parse(s).map_err(Into::into),)*
+                    _ => panic!("alternative {n} out of bounds"),
+                }
+            }
+
+            fn grammars(&self) -> Vec<Grammar> {
+                vec![self.0.grammar() $(, self.$n.grammar())*]
+            }
+        }
+    };
+}
+alternatives!(P1, 1 P2);
+alternatives!(P1, 1 P2, 2 P3);
+alternatives!(P1, 1 P2, 2 P3, 3 P4);
+alternatives!(P1, 1 P2, 2 P3, 3 P4, 4 P5);
+alternatives!(P1, 1 P2, 2 P3, 3 P4, 4 P5, 5 P6);
+alternatives!(P1, 1 P2, 2 P3, 3 P4, 4 P5, 5 P6, 6 P7);
+alternatives!(P1, 1 P2, 2 P3, 3 P4, 4 P5, 5 P6, 6 P7, 7 P8);
+
+/**
+Tries every alternative from the same position, and keeps the one that consumed the most. Ties go
+to the earlier alternative. If every alternative fails, the error of the one that got furthest is
+returned, and the string is left where it failed. Constructed with [`longest`](crate::longest).
+
+Unlike [`Or`], the order of the alternatives doesn't matter, so overlapping literals can't shadow
+each other.
+```
+# use parsa::{Parser, ParserString, longest};
+# use parsa::builtins::take;
+let op = longest((take("<"), take("<="), take("<<")));
+let mut input = ParserString::from("<=1");
+assert_eq!(op.parse(&mut input), Ok("<="));
+
+//with `or`, the first alternative wins
+let op = take("<").or(take("<="));
+let mut input = ParserString::from("<=1");
+assert_eq!(op.parse(&mut input), Ok("<"));
+```
+*/
+pub struct Longest<T, A> {
+    alts: A,
+    t: PhantomData<T>,
+}
+
+clone_debug!(Longest<T, A> [A] { alts } { t });
+
+impl<T, A> Longest<T, A> {
+    ///Constructs this parser.
+    pub const fn new(alts: A) -> Self { Self { alts, t: PhantomData } }
+}
+
+impl<T, I: ?Sized + Input, A: Alternatives<T, I>> Parser<T, I> for Longest<T, A> {
+    type Err = A::Err;
+
+    fn parse(&self, s: &mut ParserInput<I>) -> Result<T, Self::Err> {
+        rule(s, "longest", |s| self.parse_inner(s))
+    }
+
+    fn grammar(&self) -> Grammar {
+        Grammar::Alt(self.alts.grammars())
+    }
+}
+
+impl<T, A> Longest<T, A> {
+    fn parse_inner<I: ?Sized + Input>(&self, s: &mut ParserInput<I>) -> Result<T, A::Err>
+    where A: Alternatives<T, I>
+    {
+        let start = s.checkpoint();
+        //the longest success so far, with where it ended and what it emitted
+        let mut best: Option<(T, usize, Vec<crate::Diagnostic>)> = None;
+        let mut furthest: Option<(A::Err, usize)> = None;
+
+        for n in 0..self.alts.len() {
+            let res = self.alts.parse_nth(n, s);
+            let end = s.start();
+            match res {
+                Ok(v) if best.as_ref().is_none_or(|(_, e, _)| end > *e) => {
+                    best = Some((v, end, s.diagnostics[start.diagnostics..].to_vec()));
+                },
+                Ok(_) => (),
+                Err(e) if furthest.as_ref().is_none_or(|(_, f)| end > *f) => furthest = Some((e, end)),
+                Err(_) => (),
+            }
+            s.rewind(start);
+        }
+
+        match (best, furthest) {
+            (Some((v, end, diagnostics)), _) => {
+                s.seek(end);
+                s.diagnostics.extend(diagnostics);
+                Ok(v)
+            },
+            (None, Some((e, end))) => {
+                s.seek(end);
+                Err(e)
+            },
+            (None, None) => unreachable!("alternatives are never empty"),
+        }
+    }
+}
+
+/**
+Adds a "did you mean" suggestion to the errors of a parser, naming the literal it expected that
+is closest to what was found. See [`Suggestions::closest`](crate::Suggestions::closest) for how
+the closest literal is picked.
+```
+# use parsa::{Parser, ParserString};
+# use parsa::builtins::take;
+let cmd = take("commit").or(take("checkout")).or(take("push")).did_you_mean();
+
+let err = cmd.parse(&mut ParserString::from("comit -m")).unwrap_err();
+assert_eq!(err.suggestion.as_deref(), Some("commit"));
+assert_eq!(err.to_string(), r#"expected "push", found "comi", did you mean `commit`?"#);
+```
+*/
+pub struct DidYouMean<T, P> {
+    p: P,
+    t: PhantomData<T>
+}
+
+clone_debug!(DidYouMean<T, P> [P] { p } { t });
+
+impl<T, P> DidYouMean<T, P> {
+    ///Constructs this parser.
+    pub const fn new(p: P) -> Self { Self { p, t: PhantomData } }
+}
+
+impl<T, P, I: ?Sized + Input> Parser<T, I> for DidYouMean<T, P>
+where
+    P: Parser<T, I>
+{
+    type Err = SuggestErr<P::Err>;
+
+    fn parse(&self, s: &mut ParserInput<I>) -> Result<T, Self::Err> {
+        let start = s.start();
+        self.p.parse(s).map_err(|err| {
+            let expected = s.expected();
+            let suggestion = (expected.offset >= start).then(|| {
+                let rest = s.full.describe(expected.offset..s.end, 64);
+                crate::suggest::closest(&expected.items, &rest).map(str::to_owned)
+            }).flatten();
+            SuggestErr { err, suggestion }
+        })
+    }
+
+    fn grammar(&self) -> Grammar {
+        self.p.grammar()
+    }
+}
+
+///An error from a [`DidYouMean`] parser.
+#[derive(Debug, Clone, PartialEq, Eq, FromNever)]
+pub struct SuggestErr<E> {
+    ///The error of the inner parser
+    pub err: E,
+    ///The closest literal to what was found, if any was close
+    pub suggestion: Option<String>,
+}
+
+impl<E: Display> Display for SuggestErr<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.err)?;
+        if let Some(suggestion) = &self.suggestion {
+            write!(f, ", did you mean `{suggestion}`?")?;
+        }
+        Ok(())
+    }
+}
+impl<E: std::error::Error> std::error::Error for SuggestErr<E> {}
+
+/**
+Applies a parser repeatedly, with a separator between each item, like a comma separated list. The
+list may be empty. Without [`SepBy::trailing`], a separator must be followed by another item, or
+the item's error is returned.
+
+A separator and item that together consume nothing would repeat forever, so repetition stops there,
+and a warning is recorded as with [`Many`].
+```
+# use parsa::{Parser, ParserString};
+# use parsa::builtins::{between, whitespace};
+let list = between("'", "'").sep_by(','.after(whitespace));
+
+assert_eq!(list.parse(&mut ParserString::from("'a', 'b', 'c'")).unwrap(), ["a", "b", "c"]);
+assert!(list.parse(&mut ParserString::from("")).unwrap().is_empty());
+assert!(list.parse(&mut ParserString::from("'a', 'b',")).is_err());
+```
+*/
+pub struct SepBy<T, U, P, S> {
+    p: P,
+    sep: S,
+    trailing: bool,
+    t: PhantomData<T>,
+    u: PhantomData<U>,
+}
+
+clone_debug!(SepBy<T, U, P, S> [P, S] { p, sep, trailing } { t, u });
+
+impl<T, U, P, S> SepBy<T, U, P, S> {
+    ///Constructs this parser.
+    pub const fn new(p: P, sep: S) -> Self { Self { p, sep, trailing: false, t: PhantomData, u: PhantomData } }
+
+    ///Allow a separator after the last item, as in Rust or JSON5. The trailing separator is
+    ///consumed.
+    ///```
+    ///# use parsa::{Parser, ParserString};
+    ///# use parsa::builtins::{between, whitespace};
+    ///let list = between("'", "'").sep_by(','.after(whitespace)).trailing();
+    ///let mut input = ParserString::from("'a', 'b',]");
+    ///assert_eq!(list.parse(&mut input).unwrap(), ["a", "b"]);
+    ///assert_eq!(input.get(), "]");
+    ///```
+    pub fn trailing(mut self) -> Self {
+        self.trailing = true;
+        self
+    }
+}
+
+impl<T, U, P, S, I: ?Sized + Input> Parser<Vec<T>, I> for SepBy<T, U, P, S>
+where
+    P: Parser<T, I>,
+    S: Parser<U, I>,
+{
+    type Err = P::Err;
+
+    fn parse(&self, s: &mut ParserInput<I>) -> Result<Vec<T>, Self::Err> {
+        rule(s, "sep_by", |s| {
+            let mut out = vec![];
+            let Ok(first) = self.p.try_parse(s) else { return Ok(out) };
+            out.push(first);
+
+            while s.allow_item(out.len()) {
+                let cp = s.checkpoint();
+                if self.sep.try_parse(s).is_err() {
+                    break
+                }
+                match self.p.try_parse(s) {
+                    Ok(v) => out.push(v),
+                    Err(_) if self.trailing => break,
+                    Err(e) => return Err(e),
+                }
+                if s.start() == cp.offset() {
+                    s.warn("repeated parser succeeded without consuming input");
+                    break
+                }
+            }
+            Ok(out)
+        })
+    }
+
+    fn grammar(&self) -> Grammar {
+        let rest = Grammar::repeat(self.sep.grammar().seq(self.p.grammar()), 0, None);
+        let mut list = self.p.grammar().seq(rest);
+        if self.trailing {
+            list = list.seq(Grammar::repeat(self.sep.grammar(), 0, Some(1)));
+        }
+        Grammar::repeat(list, 0, Some(1))
+    }
+}
+
+/**
+Repeatedly applies a parser, collecting the outputs into any [`FromIterator`] collection, [`Vec`]
+by default. Built with [`Parser::repeated`] and configured with [`at_least`](Repeated::at_least),
+[`at_most`](Repeated::at_most), [`separated_by`](Repeated::separated_by) and
+[`collect`](Repeated::collect). Outputs are collected as they are parsed, without an intermediate
+[`Vec`].
+
+Like [`Many`], repetition stops if an item and its separator succeed without consuming anything.
+```
+# use std::collections::{BTreeSet, HashMap};
+# use parsa::{Parser, ParserString};
+# use parsa::builtins::{word, whitespace, take_while_m_n, BuiltinErr};
+let mut input = ParserString::from("c a b a");
+let set = word.after(whitespace).repeated().collect::<BTreeSet<_>>().parse(&mut input).unwrap();
+assert_eq!(set, BTreeSet::from(["a".to_owned(), "b".to_owned(), "c".to_owned()]));
+
+let name = || take_while_m_n(1, 64, char::is_alphanumeric).convert_err::<BuiltinErr>();
+let pair = name().after('=').chain(name());
+let mut input = ParserString::from("x=1,y=2");
+let map = pair.repeated().separated_by(',').collect::<HashMap<_, _>>().parse(&mut input).unwrap();
+assert_eq!(map["y"], "2");
+```
+*/
+pub struct Repeated<T, P, S = NoSeparator, U = (), C = Vec<T>> {
+    p: P,
+    sep: S,
+    min: usize,
+    max: Option<usize>,
+    t: PhantomData<T>,
+    u: PhantomData<U>,
+    c: PhantomData<C>,
+}
+
+clone_debug!(Repeated<T, P, S, U, C> [P, S] { p, sep, min, max } { t, u, c });
+
+impl<T, P> Repeated<T, P> {
+    ///Constructs this parser.
+    pub const fn new(p: P) -> Self {
+        Self { p, sep: NoSeparator, min: 0, max: None, t: PhantomData, u: PhantomData, c: PhantomData }
+    }
+}
+
+impl<T, P, S, U, C> Repeated<T, P, S, U, C> {
+    ///Fail with [`RepeatedErr`] unless at least `n` items are parsed.
+    ///```
+    ///# use parsa::{Parser, ParserString};
+    ///# use parsa::combinators::RepeatedErr;
+    ///# use parsa::builtins::{take, TakeErr};
+    ///let mut input = ParserString::from("aab");
+    ///let p = take("a").repeated().at_least(3);
+    ///assert!(matches!(p.parse(&mut input), Err(RepeatedErr::Item(TakeErr::NoMatch { .. }))));
+    ///```
+    pub fn at_least(mut self, n: usize) -> Self {
+        self.min = n;
+        self
+    }
+
+    ///Stop after `n` items, leaving the rest of the input.
+    ///```
+    ///# use parsa::{Parser, ParserString};
+    ///let mut input = ParserString::from("aaaa");
+    ///assert_eq!('a'.repeated().at_most(3).parse(&mut input).unwrap(), ['a', 'a', 'a']);
+    ///assert_eq!(input.get(), "a");
+    ///```
+    pub fn at_most(mut self, n: usize) -> Self {
+        self.max = Some(n);
+        self
+    }
+
+    ///Require `sep` between items. A separator that isn't followed by an item is left
+    ///unconsumed.
+    ///```
+    ///# use parsa::{Parser, ParserString};
+    ///# use parsa::builtins::take_while_m_n;
+    ///let mut input = ParserString::from("a,b,");
+    ///let words = take_while_m_n(1, 64, char::is_alphanumeric).repeated().separated_by(',').parse(&mut input).unwrap();
+    ///assert_eq!(words, ["a", "b"]);
+    ///assert_eq!(input.get(), ",");
+    ///```
+    pub fn separated_by<U2, S2>(self, sep: S2) -> Repeated<T, P, S2, U2, C> {
+        Repeated { p: self.p, sep, min: self.min, max: self.max, t: PhantomData, u: PhantomData, c: PhantomData }
+    }
+
+    ///Collect the outputs into `C2` instead.
+    ///```
+    ///# use parsa::{Parser, ParserString};
+    ///# use parsa::builtins::take_while_m_n;
+    ///let mut input = ParserString::from("abc1");
+    ///let p = take_while_m_n(1, 1, char::is_alphabetic).repeated().collect::<String>();
+    ///assert_eq!(p.parse(&mut input).unwrap(), "abc");
+    ///```
+    pub fn collect<C2>(self) -> Repeated<T, P, S, U, C2> {
+        Repeated { p: self.p, sep: self.sep, min: self.min, max: self.max, t: PhantomData, u: PhantomData, c: PhantomData }
+    }
+}
+
+impl<T, P, S, U, C, I: ?Sized + Input> Parser<C, I> for Repeated<T, P, S, U, C>
+where
+    P: Parser<T, I>,
+    S: Parser<U, I>,
+    C: FromIterator<T>,
+{
+    type Err = RepeatedErr<P::Err>;
+
+    fn parse(&self, s: &mut ParserInput<I>) -> Result<C, Self::Err> {
+        rule(s, "repeated", |s| {
+            let mut found = 0;
+            let mut err = None;
+            let items = std::iter::from_fn(|| {
+                if self.max.is_some_and(|max| found >= max) || !s.allow_item(found) {
+                    return None
+                }
+                let required = found < self.min;
+                let cp = s.checkpoint();
+                if found > 0 && self.sep.try_parse(s).is_err() {
+                    if required {
+                        err = Some(RepeatedErr::TooFew { min: self.min, found });
+                    }
+                    return None
+                }
+                let item = match required {
+                    true => self.p.parse(s),
+                    false => self.p.try_parse(s),
+                };
+                match item {
+                    Ok(_) if !required && s.start() == cp.offset() => {
+                        s.rewind(cp);
+                        s.warn("repeated parser succeeded without consuming input");
+                        None
+                    },
+                    Ok(v) => {
+                        found += 1;
+                        Some(v)
+                    },
+                    Err(e) if required => {
+                        err = Some(RepeatedErr::Item(e));
+                        None
+                    },
+                    Err(_) => {
+                        s.rewind(cp);
+                        None
+                    },
+                }
+            });
+            let out = C::from_iter(items);
+            match err {
+                Some(e) => Err(e),
+                None => Ok(out),
+            }
+        })
+    }
+
+    fn grammar(&self) -> Grammar {
+        let item = self.p.grammar();
+        let (min, max) = (self.min, self.max);
+        match self.sep.grammar() {
+            Grammar::Seq(sep) if sep.is_empty() => Grammar::repeat(item, min, max),
+            sep => {
+                let rest = Grammar::repeat(sep.seq(item.clone()), min.saturating_sub(1), max.map(|m| m.saturating_sub(1)));
+                match min {
+                    0 => Grammar::repeat(item.seq(rest), 0, Some(1)),
+                    _ => item.seq(rest),
+                }
+            },
+        }
+    }
+}
+
+///The separator of a [`Repeated`] parser without one, which always succeeds without consuming
+///anything.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct NoSeparator;
+
+impl<I: ?Sized + Input> Parser<(), I> for NoSeparator {
+    type Err = Infallible;
+
+    fn parse(&self, _: &mut ParserInput<I>) -> Result<(), Self::Err> {
+        Ok(())
+    }
+
+    fn grammar(&self) -> Grammar {
+        Grammar::Seq(vec![])
+    }
+}
+
+///Indicates that a [`Repeated`] parser has failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, FromNever)]
+pub enum RepeatedErr<E> {
+    ///An item needed to reach [`at_least`](Repeated::at_least) failed
+    Item(E),
+    ///The separator before an item needed to reach [`at_least`](Repeated::at_least) failed
+    TooFew {
+        ///How many items were needed
+        min: usize,
+        ///How many items were parsed
+        found: usize,
+    },
+}
+
+impl<E: Display> Display for RepeatedErr<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Item(e) => write!(f, "{e}"),
+            Self::TooFew { min, found } => write!(f, "expected at least {min} items, found {found}"),
+        }
+    }
+}
+impl<E: std::error::Error> std::error::Error for RepeatedErr<E> {}
+
+//the stack left before a stack safe parser grows it, and how much to grow it by
+#[cfg(feature = "stacker")]
+const RED_ZONE: usize = 128 * 1024;
+#[cfg(feature = "stacker")]
+const STACK_GROWTH: usize = 2 * 1024 * 1024;
+
+#[cfg(feature = "stacker")]
+pub(crate) fn stack_safe<R>(f: impl FnOnce() -> R) -> R {
+    stacker::maybe_grow(RED_ZONE, STACK_GROWTH, f)
+}
+
+/**
+Runs a parser on a new segment of stack, allocated on the heap, whenever the current one is
+close to running out. Wrap the recursive rules of a grammar in this so that legitimately deep
+input, like heavily nested JSON, parses instead of overflowing the stack. Checking the stack is
+cheap, but growing it isn't free, so wrapping every parser is unnecessary. [`StaticParser`]s do
+this already.
+
+Requires the `stacker` feature.
+```
+# use parsa::{Parser, ParserString};
+# use parsa::combinators::StackSafe;
+# use parsa::builtins::CharErr;
+fn depth(s: &mut ParserString) -> Result<usize, CharErr> {
+    let nested = '['.replace(StackSafe::new(depth).many()).after(']');
+    nested.map(|inner| inner.into_iter().max().unwrap_or(0) + 1).parse(s)
+}
+
+let deep = "[".repeat(50_000) + &"]".repeat(50_000);
+assert_eq!(depth(&mut ParserString::from(deep)), Ok(50_000));
+```
+*/
+#[cfg(feature = "stacker")]
+pub struct StackSafe<T, P> {
+    p: P,
+    t: PhantomData<T>,
+}
+
+#[cfg(feature = "stacker")]
+clone_debug!(StackSafe<T, P> [P] { p } { t });
+
+#[cfg(feature = "stacker")]
+impl<T, P> StackSafe<T, P> {
+    ///Constructs this parser.
+    pub const fn new(p: P) -> Self { Self { p, t: PhantomData } }
+}
+
+#[cfg(feature = "stacker")]
+impl<T, P, I: ?Sized + Input> Parser<T, I> for StackSafe<T, P>
+where
+    P: Parser<T, I>
+{
+    type Err = P::Err;
+
+    fn parse(&self, s: &mut ParserInput<I>) -> Result<T, Self::Err> {
+        stack_safe(|| self.p.parse(s))
+    }
 
-        Ok(out)
+    fn grammar(&self) -> Grammar {
+        self.p.grammar()
     }
 }
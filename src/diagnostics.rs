@@ -0,0 +1,117 @@
+use std::fmt::Display;
+
+use crate::{ParserInput, Input};
+
+///How serious a [`Diagnostic`] is. None of these stop a parse.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    ///Extra information, such as a hint
+    Note,
+    ///Something suspicious or deprecated
+    Warning,
+    ///A failure that a parser recovered from
+    Error,
+}
+
+///A non-fatal message emitted by a parser, collected on the [`ParserInput`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    ///How serious this diagnostic is
+    pub severity: Severity,
+    ///The message to show
+    pub message: String,
+    ///Where in the string this was emitted, relative to the "true" start
+    pub offset: usize,
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let severity = match self.severity {
+            Severity::Note => "note",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+        write!(f, "{severity} at offset {}: {}", self.offset, self.message)
+    }
+}
+
+///The result of [`Parser::parse_tolerant`](crate::Parser::parse_tolerant): a value, possibly with
+///holes filled in, along with everything that went wrong while building it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Recovered<T> {
+    ///The parsed value
+    pub value: T,
+    ///Every diagnostic emitted during the parse, including recovered errors
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl<T> Recovered<T> {
+    ///Iterate over the [`Severity::Error`] diagnostics.
+    pub fn errors(&self) -> impl Iterator<Item = &Diagnostic> {
+        self.diagnostics.iter().filter(|d| d.severity == Severity::Error)
+    }
+
+    ///Returns `true` if no errors were encountered.
+    pub fn is_clean(&self) -> bool {
+        self.errors().next().is_none()
+    }
+}
+
+impl<I: ?Sized + Input> ParserInput<I> {
+    ///Records a [`Diagnostic`]. Diagnostics emitted by a parser are discarded if the string is
+    ///rewound past them, such as by [`Parser::try_parse`](crate::Parser::try_parse).
+    ///```rust
+    ///# use parsa::{ParserString, Diagnostic, Severity};
+    ///let mut input = ParserString::from("var x");
+    ///input.emit(Diagnostic { severity: Severity::Warning, message: "`var` is deprecated".into(), offset: 0 });
+    ///
+    ///assert_eq!(input.diagnostics()[0].to_string(), "warning at offset 0: `var` is deprecated");
+    ///```
+    pub fn emit(&mut self, diagnostic: Diagnostic) {
+        self.diagnostics.push(diagnostic);
+    }
+
+    ///Records a [`Severity::Warning`] at the current position.
+    ///```rust
+    ///# use parsa::{ParserString, Parser};
+    ///# use parsa::builtins::take;
+    ///let mut input = ParserString::from("abc");
+    ///let branch = |s: &mut ParserString| {
+    ///    s.warn("tried the first branch");
+    ///    take("x").parse(s)
+    ///};
+    ///let _ = branch.or(take("a")).parse(&mut input);
+    /////the failed branch was rewound, along with its warning
+    ///assert!(input.diagnostics().is_empty());
+    ///
+    ///input.warn("done");
+    ///assert_eq!(input.diagnostics()[0].offset, 1);
+    ///```
+    pub fn warn(&mut self, message: impl Into<String>) {
+        let offset = self.start();
+        self.emit(Diagnostic { severity: Severity::Warning, message: message.into(), offset })
+    }
+
+    ///Records a [`Severity::Note`] at the current position.
+    pub fn note(&mut self, message: impl Into<String>) {
+        let offset = self.start();
+        self.emit(Diagnostic { severity: Severity::Note, message: message.into(), offset })
+    }
+
+    ///Get the diagnostics collected so far.
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.diagnostics
+    }
+
+    ///Remove and return the diagnostics collected so far.
+    ///```rust
+    ///# use parsa::ParserString;
+    ///let mut input = ParserString::from("abc");
+    ///input.warn("a");
+    ///assert_eq!(input.take_diagnostics().len(), 1);
+    ///assert!(input.diagnostics().is_empty());
+    ///```
+    pub fn take_diagnostics(&mut self) -> Vec<Diagnostic> {
+        std::mem::take(&mut self.diagnostics)
+    }
+}
@@ -0,0 +1,164 @@
+use std::string::FromUtf8Error;
+
+use crate::ParserString;
+
+const BOM: char = '\u{feff}';
+
+impl ParserString {
+    ///Constructs an input over `text`, skipping a leading byte order mark if there is one. Files
+    ///written by Windows tools often start with one, and it would otherwise be the first
+    ///character every parser sees.
+    ///```rust
+    ///# use parsa::ParserString;
+    ///let input = ParserString::without_bom("\u{feff}name = 1");
+    ///assert_eq!(input.get(), "name = 1");
+    ///```
+    pub fn without_bom(text: impl Into<String>) -> Self {
+        let mut text = text.into();
+        if text.starts_with(BOM) {
+            text.drain(..BOM.len_utf8());
+        }
+        Self::from(text)
+    }
+
+    ///Constructs an input over `text` with every `\r\n` and lone `\r` replaced by `\n`, so
+    ///grammars only need to handle one kind of line ending.
+    ///
+    ///Offsets in the input count bytes of the normalized text. To keep them pointing into the
+    ///original text, leave it as is and match line endings with
+    ///[`newline`](crate::builtins::newline) instead.
+    ///```rust
+    ///# use parsa::ParserString;
+    ///let input = ParserString::normalize_newlines("a\r\nb\rc\n");
+    ///assert_eq!(input.get(), "a\nb\nc\n");
+    ///```
+    pub fn normalize_newlines(text: impl Into<String>) -> Self {
+        let text = text.into();
+        if !text.contains('\r') {
+            return Self::from(text)
+        }
+        Self::from(text.replace("\r\n", "\n").replace('\r', "\n"))
+    }
+
+    ///Constructs an input from UTF-8 bytes, skipping a leading byte order mark. Fails if `bytes`
+    ///isn't valid UTF-8.
+    ///```rust
+    ///# use parsa::ParserString;
+    ///let input = ParserString::from_utf8(b"\xef\xbb\xbfabc".to_vec()).unwrap();
+    ///assert_eq!(input.get(), "abc");
+    ///assert!(ParserString::from_utf8(vec![0xff]).is_err());
+    ///```
+    pub fn from_utf8(bytes: Vec<u8>) -> Result<Self, FromUtf8Error> {
+        String::from_utf8(bytes).map(Self::without_bom)
+    }
+
+    ///Constructs an input from UTF-16 bytes, replacing invalid sequences with
+    ///[`U+FFFD`](char::REPLACEMENT_CHARACTER). The byte order is taken from a leading byte order
+    ///mark, which is skipped, and is little-endian without one. A trailing odd byte is replaced
+    ///too.
+    ///
+    ///Offsets in the input count bytes of the decoded UTF-8 text, not of `bytes`.
+    ///
+    ///Requires the `encoding` feature.
+    ///```rust
+    ///# use parsa::ParserString;
+    ///let input = ParserString::from_utf16_lossy(b"\xfe\xff\x00h\x00i");
+    ///assert_eq!(input.get(), "hi");
+    ///let input = ParserString::from_utf16_lossy(b"h\x00i\x00");
+    ///assert_eq!(input.get(), "hi");
+    ///```
+    #[cfg(feature = "encoding")]
+    pub fn from_utf16_lossy(bytes: &[u8]) -> Self {
+        let (bytes, big) = match bytes {
+            [0xfe, 0xff, rest @ ..] => (rest, true),
+            [0xff, 0xfe, rest @ ..] => (rest, false),
+            _ => (bytes, false),
+        };
+        let units = bytes.chunks_exact(2).map(|pair| match big {
+            true => u16::from_be_bytes([pair[0], pair[1]]),
+            false => u16::from_le_bytes([pair[0], pair[1]]),
+        });
+        let mut text: String = char::decode_utf16(units)
+            .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+            .collect();
+        if bytes.len() % 2 == 1 {
+            text.push(char::REPLACEMENT_CHARACTER);
+        }
+        Self::from(text)
+    }
+
+    ///Constructs an input from Latin-1 (ISO 8859-1) bytes, where every byte is the character
+    ///with the same code point. This can't fail.
+    ///
+    ///Offsets in the input count bytes of the decoded UTF-8 text, not of `bytes`.
+    ///
+    ///Requires the `encoding` feature.
+    ///```rust
+    ///# use parsa::ParserString;
+    ///let input = ParserString::from_latin1(b"caf\xe9");
+    ///assert_eq!(input.get(), "café");
+    ///```
+    #[cfg(feature = "encoding")]
+    pub fn from_latin1(bytes: &[u8]) -> Self {
+        Self::from(bytes.iter().map(|&b| char::from(b)).collect::<String>())
+    }
+
+    ///Constructs an input from bytes in an unknown encoding, detected from a byte order mark.
+    ///UTF-16 is decoded with [`ParserString::from_utf16_lossy`], and anything else is decoded as
+    ///UTF-8 with invalid sequences replaced.
+    ///
+    ///Requires the `encoding` feature.
+    ///```rust
+    ///# use parsa::ParserString;
+    ///assert_eq!(ParserString::decode_lossy(b"\xff\xfeo\x00k\x00").get(), "ok");
+    ///assert_eq!(ParserString::decode_lossy(b"\xef\xbb\xbfok").get(), "ok");
+    ///assert_eq!(ParserString::decode_lossy(b"o\xffk").get(), "o\u{fffd}k");
+    ///```
+    #[cfg(feature = "encoding")]
+    pub fn decode_lossy(bytes: &[u8]) -> Self {
+        match bytes {
+            [0xfe, 0xff, ..] | [0xff, 0xfe, ..] => Self::from_utf16_lossy(bytes),
+            _ => Self::without_bom(String::from_utf8_lossy(bytes)),
+        }
+    }
+
+    ///Constructs an input over `text` in Unicode Normalization Form C, so characters written
+    ///with combining marks match their precomposed forms. Literals compared against the input
+    ///should be in NFC too, which text typed into source code almost always is.
+    ///
+    ///Offsets in the input count bytes of the normalized text.
+    ///
+    ///Requires the `unicode-normalization` feature.
+    ///```rust
+    ///# use parsa::ParserString;
+    ///let input = ParserString::nfc("cafe\u{301}");
+    ///assert_eq!(input.get(), "caf\u{e9}");
+    ///```
+    #[cfg(feature = "unicode-normalization")]
+    pub fn nfc(text: impl AsRef<str>) -> Self {
+        use unicode_normalization::UnicodeNormalization;
+        Self::from(text.as_ref().nfc().collect::<String>())
+    }
+
+    ///Constructs an input over `text` lowercased and then in Normalization Form C, for
+    ///case-insensitive grammars. Lowercasing is a close approximation of full case folding that
+    ///differs for a handful of characters, like `ß` not expanding to `ss`. Literals compared
+    ///against the input should be lowercase.
+    ///
+    ///Offsets in the input count bytes of the folded text, which can differ in length from
+    ///`text`.
+    ///
+    ///Requires the `unicode-normalization` feature.
+    ///```rust
+    ///# use parsa::{Parser, ParserString};
+    ///# use parsa::builtins::take;
+    ///let mut input = ParserString::case_folded("SELECT Cafe\u{301}");
+    ///assert_eq!(input.get(), "select caf\u{e9}");
+    ///assert!(take("select").parse(&mut input).is_ok());
+    ///```
+    #[cfg(feature = "unicode-normalization")]
+    pub fn case_folded(text: impl AsRef<str>) -> Self {
+        use unicode_normalization::UnicodeNormalization;
+        Self::from(text.as_ref().to_lowercase().nfc().collect::<String>())
+    }
+}
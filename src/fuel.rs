@@ -0,0 +1,68 @@
+use crate::{ParserInput, Input};
+
+//fuel left for a string
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct Fuel {
+    pub(crate) left: Option<usize>,
+    pub(crate) used: usize,
+    //where the fuel ran out, if it did
+    pub(crate) exhausted: Option<usize>,
+}
+
+impl<I: ?Sized + Input> ParserInput<I> {
+    /**
+    Limit how many combinators may run on this string from now on, or remove the limit with
+    [`None`]. Every run of a core combinator, like [`Chain`](crate::combinators::Chain),
+    [`Or`](crate::combinators::Or) or [`Many`](crate::combinators::Many) costs one unit, as does
+    each repetition of [`Many`](crate::combinators::Many). Once the fuel runs out, nothing more
+    can be consumed and the string can't be rewound, so the parse winds down quickly. The string
+    isn't [empty](ParserInput::is_empty) afterwards, so the cut-off parse doesn't look complete.
+    This bounds the work done on untrusted input, or by untrusted grammars. Run the parser with
+    [`Parser::parse_limited`](crate::Parser::parse_limited) to fail with
+    [`LimitErr::Fuel`](crate::LimitErr::Fuel) when that happens, or use
+    [`FuelLimited`](crate::combinators::FuelLimited) to limit only part of a parser.
+    ```
+    # use parsa::{Parser, ParserString, LimitErr};
+    # use parsa::builtins::take;
+    let mut input = ParserString::from("aaaa");
+    input.set_fuel(Some(3));
+    let res = take("a").many().parse_limited(&mut input);
+    assert_eq!(res, Err(LimitErr::Fuel { offset: 2 }));
+    assert_eq!(input.fuel_exhausted(), Some(2));
+    assert_eq!(input.fuel(), Some(0));
+    assert_eq!(input.get(), "aa");
+    ```
+    */
+    pub fn set_fuel(&mut self, fuel: Option<usize>) {
+        self.fuel = Fuel { left: fuel, ..Fuel::default() };
+    }
+
+    ///Get the fuel left, or [`None`] if there is no limit.
+    pub fn fuel(&self) -> Option<usize> {
+        self.fuel.left
+    }
+
+    ///The amount of fuel used since it was last set.
+    pub fn fuel_used(&self) -> usize {
+        self.fuel.used
+    }
+
+    ///Where the fuel ran out, if it did.
+    pub fn fuel_exhausted(&self) -> Option<usize> {
+        self.fuel.exhausted
+    }
+
+    //uses one unit of fuel, halting the input if there is none left
+    pub(crate) fn burn(&mut self) {
+        self.fuel.used += 1;
+        match self.fuel.left {
+            Some(0) if self.is_halted() => {},
+            Some(0) => {
+                self.fuel.exhausted = Some(self.ptr);
+                self.halt();
+            },
+            Some(left) => self.fuel.left = Some(left - 1),
+            None => {},
+        }
+    }
+}
@@ -0,0 +1,313 @@
+/*!
+Introspecting the structure of composed parsers.
+
+Every parser can describe itself as a [`Grammar`] with [`Parser::grammar`]. The combinators in this
+crate report their structure (sequences, alternation, repetition), and literal builtins like
+[`take`](crate::builtins::take) report what they match. Plain functions and closures can't be
+looked into, so they show up as [`Grammar::Opaque`] unless given a name with [`Parser::named`] or a
+grammar with [`Parser::with_grammar`].
+
+```
+# use parsa::{Parser, ParserString};
+# use parsa::builtins::{take, word, BuiltinErr};
+let assign = word.convert_err::<BuiltinErr>().named("ident")
+    .after(take("="))
+    .chain(take("true").or(take("false")).many1());
+
+assert_eq!(assign.grammar().to_json(), concat!(
+    r#"{"type":"seq","items":["#,
+        r#"{"type":"named","name":"ident","inner":{"type":"opaque"}},"#,
+        r#"{"type":"literal","value":"="},"#,
+        r#"{"type":"repeat","min":1,"max":null,"inner":{"type":"alt","items":["#,
+            r#"{"type":"literal","value":"true"},{"type":"literal","value":"false"}"#,
+        r#"]}}"#,
+    r#"]}"#,
+));
+```
+*/
+
+use std::fmt::{Write, Display};
+
+#[cfg(feature = "proptest")]
+mod strategy;
+
+use crate::{Parser, ParserInput, Input};
+
+///The structure of a parser. See the [module docs](crate::grammar).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Grammar {
+    ///A parser whose structure is unknown
+    Opaque,
+    ///Matches this exact text
+    Literal(String),
+    ///A builtin parser that can't be broken down further, but whose input is known
+    Builtin(Builtin),
+    ///A parser given a name
+    Named {
+        ///The name of the parser
+        name: String,
+        ///What the parser is made of
+        inner: Box<Grammar>,
+    },
+    ///Parsers run one after another
+    Seq(Vec<Grammar>),
+    ///Parsers tried in order until one succeeds
+    Alt(Vec<Grammar>),
+    ///A parser run repeatedly
+    Repeat {
+        ///The minimum amount of repetitions
+        min: usize,
+        ///The maximum amount of repetitions, if any
+        max: Option<usize>,
+        ///The repeated parser
+        inner: Box<Grammar>,
+    },
+}
+
+///The kinds of builtin parser a [`Grammar::Builtin`] can stand for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Builtin {
+    ///A `word`
+    Word,
+    ///An `int`
+    Int,
+    ///A `float`
+    Float,
+    ///`whitespace`
+    Whitespace,
+    ///A URL, as parsed by `url_until`
+    Url,
+    ///Text up to a delimiter, as taken by `take_until`
+    Text,
+}
+
+impl Builtin {
+    ///The name this builtin is shown with.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Word => "word",
+            Self::Int => "int",
+            Self::Float => "float",
+            Self::Whitespace => "whitespace",
+            Self::Url => "url",
+            Self::Text => "text",
+        }
+    }
+}
+
+impl Grammar {
+    ///Constructs a [`Grammar::Named`].
+    pub fn named(name: impl Into<String>, inner: Grammar) -> Self {
+        Self::Named { name: name.into(), inner: Box::new(inner) }
+    }
+
+    ///Constructs a [`Grammar::Repeat`].
+    pub fn repeat(inner: Grammar, min: usize, max: Option<usize>) -> Self {
+        Self::Repeat { min, max, inner: Box::new(inner) }
+    }
+
+    ///Sequences two grammars, flattening nested sequences.
+    pub fn seq(self, other: Grammar) -> Self {
+        let mut items = match self {
+            Self::Seq(items) => items,
+            g => vec![g],
+        };
+        match other {
+            Self::Seq(rest) => items.extend(rest),
+            g => items.push(g),
+        }
+        Self::Seq(items)
+    }
+
+    ///Alternates two grammars, flattening nested alternation.
+    pub fn alt(self, other: Grammar) -> Self {
+        let mut items = match self {
+            Self::Alt(items) => items,
+            g => vec![g],
+        };
+        match other {
+            Self::Alt(rest) => items.extend(rest),
+            g => items.push(g),
+        }
+        Self::Alt(items)
+    }
+
+    ///Renders this grammar as JSON.
+    pub fn to_json(&self) -> String {
+        let mut out = String::new();
+        self.write_json(&mut out);
+        out
+    }
+
+    fn write_json(&self, out: &mut String) {
+        let list = |out: &mut String, ty: &str, items: &[Grammar]| {
+            let _ = write!(out, r#"{{"type":"{ty}","items":["#);
+            for (i, g) in items.iter().enumerate() {
+                if i > 0 { out.push(',') }
+                g.write_json(out);
+            }
+            out.push_str("]}");
+        };
+
+        match self {
+            Self::Opaque => out.push_str(r#"{"type":"opaque"}"#),
+            Self::Literal(lit) => {
+                let _ = write!(out, r#"{{"type":"literal","value":{}}}"#, json_str(lit));
+            },
+            Self::Builtin(b) => {
+                let _ = write!(out, r#"{{"type":"builtin","name":"{}"}}"#, b.name());
+            },
+            Self::Named { name, inner } => {
+                let _ = write!(out, r#"{{"type":"named","name":{},"inner":"#, json_str(name));
+                inner.write_json(out);
+                out.push('}');
+            },
+            Self::Seq(items) => list(out, "seq", items),
+            Self::Alt(items) => list(out, "alt", items),
+            Self::Repeat { min, max, inner } => {
+                let max = max.map_or("null".to_owned(), |m| m.to_string());
+                let _ = write!(out, r#"{{"type":"repeat","min":{min},"max":{max},"inner":"#);
+                inner.write_json(out);
+                out.push('}');
+            },
+        }
+    }
+
+    ///Renders this grammar as a Graphviz DOT digraph.
+    ///```
+    ///# use parsa::Parser;
+    ///# use parsa::builtins::take;
+    ///let dot = take("a").or(take("b")).grammar().to_dot();
+    ///assert_eq!(dot, "digraph grammar {\n    n0 [label=\"alt\"];\n    n1 [label=\"\\\"a\\\"\", shape=box];\n    n0 -> n1;\n    n2 [label=\"\\\"b\\\"\", shape=box];\n    n0 -> n2;\n}\n");
+    ///```
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("digraph grammar {\n");
+        let mut next_id = 0;
+        self.write_dot(&mut out, &mut next_id);
+        out.push_str("}\n");
+        out
+    }
+
+    fn write_dot(&self, out: &mut String, next_id: &mut usize) -> usize {
+        let id = *next_id;
+        *next_id += 1;
+
+        let (label, shape) = match self {
+            Self::Opaque => ("?".to_owned(), ""),
+            Self::Literal(lit) => (format!("{lit:?}"), ", shape=box"),
+            Self::Builtin(b) => (b.name().to_owned(), ", shape=ellipse"),
+            Self::Named { name, .. } => (name.clone(), ", shape=ellipse"),
+            Self::Seq(_) => ("seq".to_owned(), ""),
+            Self::Alt(_) => ("alt".to_owned(), ""),
+            Self::Repeat { min, max, .. } => (repeat_label(*min, *max), ""),
+        };
+        let _ = writeln!(out, "    n{id} [label={}{shape}];", json_str(&label));
+
+        let children: Vec<&Grammar> = match self {
+            Self::Named { inner, .. } if **inner == Self::Opaque => vec![],
+            Self::Named { inner, .. } | Self::Repeat { inner, .. } => vec![inner],
+            Self::Seq(items) | Self::Alt(items) => items.iter().collect(),
+            Self::Opaque | Self::Literal(_) | Self::Builtin(_) => vec![],
+        };
+        for child in children {
+            let child_id = child.write_dot(out, next_id);
+            let _ = writeln!(out, "    n{id} -> n{child_id};");
+        }
+        id
+    }
+}
+
+//EBNF-ish rendering, used by Parser::describe
+impl Display for Grammar {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        //sequences and alternation need parentheses inside a repeat, and alternation inside a
+        //sequence
+        let grouped = |g: &Grammar, f: &mut std::fmt::Formatter<'_>, alt_only: bool| match g {
+            Self::Alt(_) => write!(f, "({g})"),
+            Self::Seq(_) if !alt_only => write!(f, "({g})"),
+            g => write!(f, "{g}"),
+        };
+
+        match self {
+            Self::Opaque => write!(f, "?"),
+            Self::Literal(lit) => write!(f, "{lit:?}"),
+            Self::Builtin(b) => write!(f, "{}", b.name()),
+            Self::Named { name, .. } => write!(f, "{name}"),
+            Self::Seq(items) => {
+                for (i, g) in items.iter().enumerate() {
+                    if i > 0 { write!(f, " ")? }
+                    grouped(g, f, true)?;
+                }
+                Ok(())
+            },
+            Self::Alt(items) => {
+                for (i, g) in items.iter().enumerate() {
+                    if i > 0 { write!(f, " | ")? }
+                    write!(f, "{g}")?;
+                }
+                Ok(())
+            },
+            Self::Repeat { min, max, inner } => {
+                grouped(inner, f, false)?;
+                match (min, max) {
+                    (0, None) => write!(f, "*"),
+                    (1, None) => write!(f, "+"),
+                    (0, Some(1)) => write!(f, "?"),
+                    (min, None) => write!(f, "{{{min},}}"),
+                    (min, Some(max)) => write!(f, "{{{min},{max}}}"),
+                }
+            },
+        }
+    }
+}
+
+fn repeat_label(min: usize, max: Option<usize>) -> String {
+    match (min, max) {
+        (0, None) => "many".to_owned(),
+        (1, None) => "many1".to_owned(),
+        (min, None) => format!("repeat {min}.."),
+        (min, Some(max)) => format!("repeat {min}..={max}"),
+    }
+}
+
+pub(crate) fn json_str(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => { let _ = write!(out, "\\u{:04x}", c as u32); },
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+//a closure-backed parser that still reports a grammar. used by combinators that are implemented
+//as closures, so they don't hide the structure of what they wrap.
+pub(crate) struct Described<F> {
+    f: F,
+    grammar: Grammar,
+}
+
+impl<F> Described<F> {
+    pub(crate) fn new(f: F, grammar: Grammar) -> Self { Self { f, grammar } }
+}
+
+impl<T, E, I: ?Sized + Input, F: Fn(&mut ParserInput<I>) -> Result<T, E>> Parser<T, I> for Described<F> {
+    type Err = E;
+
+    fn parse(&self, s: &mut ParserInput<I>) -> Result<T, Self::Err> {
+        (self.f)(s)
+    }
+
+    fn grammar(&self) -> Grammar {
+        self.grammar.clone()
+    }
+}
@@ -0,0 +1,78 @@
+use proptest::{prelude::*, strategy::{BoxedStrategy, Just, Union}, collection::vec, string::string_regex};
+
+use super::{Grammar, Builtin};
+
+//the most repetitions generated for an unbounded repeat, past its minimum
+const EXTRA_REPEATS: usize = 4;
+
+impl Grammar {
+    /**
+    Builds a [`proptest`](mod@proptest) strategy generating inputs that match this grammar. Requires the
+    `proptest` feature.
+
+    Returns [`None`] if the grammar contains a [`Grammar::Opaque`] part, or an empty
+    [`Grammar::Alt`], which matches nothing. Builtins like `url_until` and `take_until` report a
+    [`Grammar::Builtin`] themselves, and plain function builtins like `int` can be given one with
+    [`Parser::with_grammar`](crate::Parser::with_grammar).
+
+    Generation works from structure alone, so inputs are only as valid as the grammar is
+    unambiguous. For example a `word` directly followed by another `word` will be generated
+    without a space between them.
+    ```
+    # use parsa::{Parser, ParserString};
+    # use parsa::grammar::{Grammar, Builtin};
+    # use parsa::builtins::{take, int, BuiltinErr};
+    # use proptest::{prelude::*, test_runner::TestRunner};
+    let n = int::<u8, _>.convert_err::<BuiltinErr>().with_grammar(Grammar::Builtin(Builtin::Int));
+    let list = take("[").convert_err::<BuiltinErr>()
+        .replace(n.after(take(" ")).many())
+        .after(take("]"));
+
+    let strategy = list.grammar().strategy().unwrap();
+    TestRunner::default().run(&strategy, |input| {
+        prop_assert!(list.parse(&mut ParserString::from(input)).is_ok());
+        Ok(())
+    }).unwrap();
+    ```
+    */
+    pub fn strategy(&self) -> Option<BoxedStrategy<String>> {
+        Some(match self {
+            Self::Opaque => return None,
+            Self::Literal(lit) => Just(lit.clone()).boxed(),
+            Self::Builtin(b) => builtin(*b)?,
+            Self::Named { inner, .. } => inner.strategy()?,
+            Self::Seq(items) => {
+                let mut out = Just(String::new()).boxed();
+                for item in items {
+                    out = (out, item.strategy()?)
+                        .prop_map(|(mut a, b)| { a.push_str(&b); a })
+                        .boxed();
+                }
+                out
+            },
+            Self::Alt(items) if items.is_empty() => return None,
+            Self::Alt(items) => {
+                let items = items.iter().map(Grammar::strategy).collect::<Option<Vec<_>>>()?;
+                Union::new(items).boxed()
+            },
+            Self::Repeat { min, max, inner } => {
+                let max = max.unwrap_or(min + EXTRA_REPEATS);
+                vec(inner.strategy()?, *min..=max)
+                    .prop_map(|parts| parts.concat())
+                    .boxed()
+            },
+        })
+    }
+}
+
+fn builtin(b: Builtin) -> Option<BoxedStrategy<String>> {
+    let regex = match b {
+        Builtin::Word => "[a-zA-Z0-9_]{1,10}",
+        Builtin::Int => "[0-9]{1,2}",
+        Builtin::Float => "[0-9]{1,3}\\.[0-9]{1,3}",
+        Builtin::Whitespace => " {0,3}",
+        Builtin::Url => "https?://[a-z]{1,8}\\.com(/[a-z]{1,5}){0,3}",
+        Builtin::Text => "[a-zA-Z0-9]{0,8}",
+    };
+    Some(string_regex(regex).ok()?.boxed())
+}
@@ -0,0 +1,164 @@
+/*!
+Reusing results across reparses of edited text.
+
+Wrapping a rule with [`Parser::memoized`] records its successful outputs in a [`Memo`], keyed by
+the offset they started at. After the text is edited, [`Memo::apply_edit`] throws away results
+that the edit could have affected and shifts the rest, so the next parse of the new text only
+reruns the rule in the regions that changed.
+
+```
+# use parsa::{Parser, ParserString};
+# use parsa::builtins::{word, whitespace};
+# use parsa::incremental::{Memo, Edit};
+let mut memo = Memo::new();
+let text = "alpha beta gamma";
+let words = |memo: &Memo<String>, text: &str| {
+    word.memoized(memo).after(whitespace).many().parse(&mut ParserString::from(text)).unwrap()
+};
+assert_eq!(words(&memo, text), vec!["alpha", "beta", "gamma"]);
+assert_eq!(memo.len(), 3);
+
+//replace "beta" with "delta"
+let edit = Edit::new(6, 4, 5);
+let text = edit.apply(text, "delta");
+memo.apply_edit(edit);
+assert_eq!(memo.len(), 2);
+
+assert_eq!(words(&memo, &text), vec!["alpha", "delta", "gamma"]);
+```
+
+# Caveats
+A result is only safe to reuse if it depends solely on the text it consumed, plus at most one
+character after it. Rules that look further ahead than that should not be memoized.
+[Diagnostics](ParserInput::diagnostics) emitted by a rule are not replayed when its result is
+reused.
+*/
+
+use std::{cell::RefCell, collections::BTreeMap, fmt::Debug};
+
+use crate::{Parser, ParserInput, Input};
+
+///A text edit, used to update a [`Memo`]. Offsets are in bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Edit {
+    ///Where the edit starts
+    pub start: usize,
+    ///How many bytes were removed
+    pub removed: usize,
+    ///How many bytes were inserted in their place
+    pub inserted: usize,
+}
+
+impl Edit {
+    ///Constructs an edit.
+    pub const fn new(start: usize, removed: usize, inserted: usize) -> Self { Self { start, removed, inserted } }
+
+    ///Applies this edit to `text`, replacing the removed range with `replacement`. Panics if the
+    ///length of `replacement` doesn't match [`Edit::inserted`], or the range is out of bounds.
+    pub fn apply(&self, text: &str, replacement: &str) -> String {
+        assert_eq!(replacement.len(), self.inserted);
+        let mut out = String::with_capacity(text.len() - self.removed + self.inserted);
+        out.push_str(&text[..self.start]);
+        out.push_str(replacement);
+        out.push_str(&text[self.start + self.removed..]);
+        out
+    }
+}
+
+///Memoized results of a single rule. See the [module docs](crate::incremental).
+pub struct Memo<T> {
+    //start -> (end, output)
+    entries: RefCell<BTreeMap<usize, (usize, T)>>,
+}
+
+impl<T> Memo<T> {
+    ///Constructs an empty memo.
+    pub const fn new() -> Self { Self { entries: RefCell::new(BTreeMap::new()) } }
+
+    ///The amount of results stored.
+    pub fn len(&self) -> usize {
+        self.entries.borrow().len()
+    }
+
+    ///Returns `true` if no results are stored.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    ///Removes all stored results.
+    pub fn clear(&mut self) {
+        self.entries.get_mut().clear();
+    }
+
+    ///Updates the stored results for an edit to the text. Results that end at or after the start of
+    ///the edit, and start before its end, are discarded. Results after the edit are shifted.
+    pub fn apply_edit(&mut self, edit: Edit) {
+        let entries = std::mem::take(self.entries.get_mut());
+        let removed_end = edit.start + edit.removed;
+
+        *self.entries.get_mut() = entries.into_iter()
+            .filter(|(start, (end, _))| *end < edit.start || *start > removed_end || (*start == removed_end && edit.removed > 0))
+            .map(|(start, (end, v))| {
+                if start >= removed_end {
+                    (start - edit.removed + edit.inserted, (end - edit.removed + edit.inserted, v))
+                } else {
+                    (start, (end, v))
+                }
+            })
+            .collect();
+    }
+}
+
+impl<T> Default for Memo<T> {
+    fn default() -> Self { Self::new() }
+}
+
+/**
+Reuses results of a parser stored in a [`Memo`], and stores new ones. See the
+[module docs](crate::incremental).
+*/
+pub struct Memoized<'a, T, P> {
+    p: P,
+    memo: &'a Memo<T>,
+}
+
+impl<T, P: Clone> Clone for Memoized<'_, T, P> {
+    fn clone(&self) -> Self { Self { p: self.p.clone(), memo: self.memo } }
+}
+
+impl<T, P: Debug> Debug for Memoized<'_, T, P> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Memoized").field("p", &self.p).finish_non_exhaustive()
+    }
+}
+
+impl<'a, T, P> Memoized<'a, T, P> {
+    ///Constructs this parser.
+    pub const fn new(p: P, memo: &'a Memo<T>) -> Self { Self { p, memo } }
+}
+
+impl<T: Clone, I: ?Sized + Input, P> Parser<T, I> for Memoized<'_, T, P>
+where
+    P: Parser<T, I>
+{
+    type Err = P::Err;
+
+    fn parse(&self, s: &mut ParserInput<I>) -> Result<T, Self::Err> {
+        let start = s.start();
+        let hit = self.memo.entries.borrow().get(&start).cloned();
+        if let Some((end, v)) = hit {
+            if end <= start + s.len() && s.full.is_boundary(end) {
+                s.seek(end);
+                return Ok(v)
+            }
+        }
+
+        let v = self.p.parse(s)?;
+        self.memo.entries.borrow_mut().insert(start, (s.start(), v.clone()));
+        Ok(v)
+    }
+
+    fn grammar(&self) -> crate::grammar::Grammar {
+        self.p.grammar()
+    }
+}
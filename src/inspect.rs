@@ -0,0 +1,140 @@
+/*!
+Hooks for observing a parse as it runs.
+
+An [`Inspector`] set on a [`ParserInput`] is told whenever a rule starts and finishes. Rules are
+[`Named`](crate::combinators::Named) parsers, reported by name, and the core combinators
+[`Chain`](crate::combinators::Chain), [`Or`](crate::combinators::Or),
+[`Many`](crate::combinators::Many) and [`Many1`](crate::combinators::Many1), reported as `chain`,
+`or`, `many` and `many1`.
+
+A [`Profiler`] counts calls, failures and time spent per rule, an [`AttemptTree`] records
+every rule that ran as a tree, and a [`Trace`] records every step as a log that can be saved and
+replayed later. With the `debugger` feature, `Debugger` prints each step as it happens, and can pause between them.
+
+```
+# use parsa::{Parser, ParserString};
+# use parsa::inspect::{Inspector, Cursor};
+# use parsa::builtins::{take, word, whitespace, BuiltinErr};
+#[derive(Default)]
+struct Log(Vec<String>);
+impl Inspector for Log {
+    fn exit(&mut self, rule: &str, s: &dyn Cursor, start: usize, ok: bool) {
+        self.0.push(format!("{rule} {start}..{} {ok}", s.offset()));
+    }
+}
+
+let mut input = ParserString::from("let x");
+input.set_inspector(Log::default());
+let _ = take("let").convert_err::<BuiltinErr>().named("keyword")
+    .after(whitespace)
+    .chain(word.named("ident"))
+    .parse(&mut input);
+
+let log = input.take_inspector::<Log>().unwrap();
+assert_eq!(log.0, vec!["keyword 0..3 true", "chain 0..4 true", "ident 4..5 true", "chain 0..5 true"]);
+```
+*/
+
+use std::{any::Any, ops::Range};
+
+use crate::{ParserInput, Input};
+
+mod profile;
+pub use profile::*;
+mod tree;
+pub use tree::*;
+mod replay;
+pub use replay::*;
+
+#[cfg(feature = "debugger")]
+mod debugger;
+#[cfg(feature = "debugger")]
+pub use debugger::Debugger;
+
+///Where a parse is, as shown to an [`Inspector`].
+pub trait Cursor {
+    ///The current position, relative to the "true" start.
+    fn offset(&self) -> usize;
+
+    ///Renders at most `max` units of the remaining input as text.
+    fn preview(&self, max: usize) -> String;
+
+    ///Renders the input in `range`, relative to the "true" start, as text.
+    fn text(&self, range: Range<usize>) -> String {
+        let _ = range;
+        String::new()
+    }
+}
+
+impl<I: ?Sized + Input> Cursor for ParserInput<I> {
+    fn offset(&self) -> usize {
+        self.start()
+    }
+
+    fn preview(&self, max: usize) -> String {
+        self.full.describe(self.start()..self.end, max)
+    }
+
+    fn text(&self, range: Range<usize>) -> String {
+        self.full.describe(range, usize::MAX)
+    }
+}
+
+///Observes rules as they run. See the [module docs](crate::inspect).
+///
+///Inspectors must be [`Send`] and [`Sync`] so that inputs can be shared between threads.
+pub trait Inspector: Any + Send + Sync {
+    ///Called before a rule runs.
+    fn enter(&mut self, rule: &str, s: &dyn Cursor) {
+        let _ = (rule, s);
+    }
+
+    ///Called after a rule runs, with where it started and whether it succeeded.
+    fn exit(&mut self, rule: &str, s: &dyn Cursor, start: usize, ok: bool) {
+        let _ = (rule, s, start, ok);
+    }
+
+    ///Called when the input is [rewound](ParserInput::rewind) from one offset to another.
+    fn rewind(&mut self, from: usize, to: usize) {
+        let _ = (from, to);
+    }
+}
+
+impl<S: ?Sized + Input> ParserInput<S> {
+    ///Sets the [`Inspector`] for this input, replacing any previous one.
+    pub fn set_inspector(&mut self, inspector: impl Inspector) {
+        self.inspector = Some(Box::new(inspector));
+    }
+
+    ///Removes the [`Inspector`] from this input, returning it if it is an `I`.
+    pub fn take_inspector<I: Inspector>(&mut self) -> Option<Box<I>> {
+        let inspector: Box<dyn Any> = self.inspector.take()?;
+        inspector.downcast().ok()
+    }
+
+    ///Get the [`Inspector`] of this input, if it is an `I`.
+    pub fn inspector<I: Inspector>(&self) -> Option<&I> {
+        let inspector: &dyn Any = self.inspector.as_deref()?;
+        inspector.downcast_ref()
+    }
+}
+
+//runs a rule, burning fuel and reporting it to the inspector if there is one
+pub(crate) fn rule<T, E, I: ?Sized + Input>(s: &mut ParserInput<I>, name: &str, f: impl FnOnce(&mut ParserInput<I>) -> Result<T, E>) -> Result<T, E> {
+    s.burn();
+    if s.inspector.is_none() {
+        return f(s)
+    }
+
+    let start = s.start();
+    if let Some(mut inspector) = s.inspector.take() {
+        inspector.enter(name, &*s);
+        s.inspector = Some(inspector);
+    }
+    let res = f(s);
+    if let Some(mut inspector) = s.inspector.take() {
+        inspector.exit(name, &*s, start, res.is_ok());
+        s.inspector = Some(inspector);
+    }
+    res
+}
@@ -0,0 +1,100 @@
+use std::io::{BufRead, BufReader, Write};
+
+use super::{Inspector, Cursor};
+
+//how much of the remaining string to show at each step
+const PREVIEW: usize = 16;
+
+/**
+An [`Inspector`] that prints every step of a parse: the rule being tried, the cursor, and the
+outcome. Requires the `debugger` feature.
+
+In [interactive](Debugger::interactive) mode, it waits for a line of input after each step. An
+empty line steps once, and `c` continues to the end without stopping.
+```
+# use parsa::{Parser, ParserString};
+# use parsa::inspect::Debugger;
+# use parsa::builtins::take;
+# use std::{sync::{Arc, Mutex}, io::Write};
+# #[derive(Clone, Default)]
+# struct Shared(Arc<Mutex<Vec<u8>>>);
+# impl Write for Shared {
+#     fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> { self.0.lock().unwrap().write(buf) }
+#     fn flush(&mut self) -> std::io::Result<()> { Ok(()) }
+# }
+# let out = Shared::default();
+let mut input = ParserString::from("ab");
+input.set_inspector(Debugger::to_writer(out.clone()));
+let _ = take("a").named("a").chain(take("c").named("c")).parse(&mut input);
+# let out = String::from_utf8(out.0.lock().unwrap().clone()).unwrap();
+assert_eq!(out, concat!(
+    "-> chain @ 0 \"ab\"\n",
+    "  -> a @ 0 \"ab\"\n",
+    "  <- a ok 0..1\n",
+    "  -> c @ 1 \"b\"\n",
+    "  <- c failed 1..2\n",
+    "<- chain failed 0..2\n",
+));
+```
+*/
+pub struct Debugger {
+    out: Box<dyn Write + Send + Sync>,
+    input: Option<Box<dyn BufRead + Send + Sync>>,
+    depth: usize,
+}
+
+impl Debugger {
+    ///Constructs a debugger printing to stderr.
+    pub fn new() -> Self { Self::to_writer(std::io::stderr()) }
+
+    ///Constructs a debugger printing to `out`.
+    pub fn to_writer(out: impl Write + Send + Sync + 'static) -> Self {
+        Self { out: Box::new(out), input: None, depth: 0 }
+    }
+
+    ///Wait for a line from stdin after each step.
+    pub fn interactive(self) -> Self {
+        self.interactive_with(BufReader::new(std::io::stdin()))
+    }
+
+    ///Wait for a line from `input` after each step.
+    pub fn interactive_with(mut self, input: impl BufRead + Send + Sync + 'static) -> Self {
+        self.input = Some(Box::new(input));
+        self
+    }
+
+    fn pause(&mut self) {
+        let Some(input) = &mut self.input else { return };
+        let _ = write!(self.out, "  (enter to step, c to continue) ");
+        let _ = self.out.flush();
+
+        let mut line = String::new();
+        match input.read_line(&mut line) {
+            Ok(0) | Err(_) => self.input = None,
+            Ok(_) if line.trim() == "c" => self.input = None,
+            Ok(_) => {},
+        }
+    }
+}
+
+impl Default for Debugger {
+    fn default() -> Self { Self::new() }
+}
+
+impl Inspector for Debugger {
+    fn enter(&mut self, rule: &str, s: &dyn Cursor) {
+        let preview = s.preview(PREVIEW);
+        let indent = "  ".repeat(self.depth);
+        let _ = writeln!(self.out, "{indent}-> {rule} @ {} {preview:?}", s.offset());
+        self.depth += 1;
+        self.pause();
+    }
+
+    fn exit(&mut self, rule: &str, s: &dyn Cursor, start: usize, ok: bool) {
+        self.depth = self.depth.saturating_sub(1);
+        let indent = "  ".repeat(self.depth);
+        let outcome = if ok { "ok" } else { "failed" };
+        let _ = writeln!(self.out, "{indent}<- {rule} {outcome} {start}..{}", s.offset());
+        self.pause();
+    }
+}
@@ -0,0 +1,92 @@
+use std::{collections::HashMap, fmt::Display, time::{Duration, Instant}};
+
+use super::{Inspector, Cursor};
+
+///Counters for a single rule, collected by a [`Profiler`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RuleStats {
+    ///How many times the rule ran
+    pub calls: usize,
+    ///How many of those runs failed
+    pub failures: usize,
+    ///Total time spent in the rule, including the rules it called
+    pub time: Duration,
+}
+
+/**
+An [`Inspector`] that counts how often each rule runs and fails, and how long it takes. Its
+[`Display`] impl prints a table of every rule, slowest first.
+```
+# use parsa::{Parser, ParserString};
+# use parsa::inspect::Profiler;
+# use parsa::builtins::{take, BuiltinErr};
+let mut input = ParserString::from("abab");
+input.set_inspector(Profiler::new());
+let _ = take("a").convert_err::<BuiltinErr>().named("a")
+    .or(take("b").named("b"))
+    .many()
+    .parse(&mut input);
+
+let profile = input.take_inspector::<Profiler>().unwrap();
+assert_eq!(profile.get("a").unwrap().calls, 5);
+assert_eq!(profile.get("a").unwrap().failures, 3);
+assert_eq!(profile.get("b").unwrap().calls, 3);
+assert_eq!(profile.get("b").unwrap().failures, 1);
+println!("{profile}");
+```
+*/
+#[derive(Debug, Default)]
+pub struct Profiler {
+    stats: HashMap<String, RuleStats>,
+    started: Vec<Instant>,
+}
+
+impl Profiler {
+    ///Constructs an empty profiler.
+    pub fn new() -> Self { Self::default() }
+
+    ///Get the counters for a rule, if it ran.
+    pub fn get(&self, rule: &str) -> Option<&RuleStats> {
+        self.stats.get(rule)
+    }
+
+    ///Every rule that ran and its counters, slowest first.
+    pub fn rules(&self) -> Vec<(&str, &RuleStats)> {
+        let mut rules: Vec<_> = self.stats.iter().map(|(k, v)| (k.as_str(), v)).collect();
+        rules.sort_by(|a, b| b.1.time.cmp(&a.1.time).then(a.0.cmp(b.0)));
+        rules
+    }
+
+    ///Clears all counters.
+    pub fn reset(&mut self) {
+        self.stats.clear();
+        self.started.clear();
+    }
+}
+
+impl Inspector for Profiler {
+    fn enter(&mut self, _rule: &str, _s: &dyn Cursor) {
+        self.started.push(Instant::now());
+    }
+
+    fn exit(&mut self, rule: &str, _s: &dyn Cursor, _start: usize, ok: bool) {
+        let elapsed = self.started.pop().map(|t| t.elapsed()).unwrap_or_default();
+        let stats = self.stats.entry(rule.to_owned()).or_default();
+        stats.calls += 1;
+        stats.failures += !ok as usize;
+        stats.time += elapsed;
+    }
+}
+
+impl Display for Profiler {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let rules = self.rules();
+        let width = rules.iter().map(|(name, _)| name.len()).max().unwrap_or(0).max(4);
+        writeln!(f, "{:width$}  {:>8}  {:>8}  {:>12}", "rule", "calls", "failures", "time")?;
+        for (name, stats) in rules {
+            let time = format!("{:?}", stats.time);
+            writeln!(f, "{name:width$}  {:>8}  {:>8}  {time:>12}", stats.calls, stats.failures)?;
+        }
+        Ok(())
+    }
+}
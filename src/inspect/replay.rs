@@ -0,0 +1,233 @@
+use std::{fmt::Display, str::FromStr};
+
+use thiserror::Error;
+
+use super::{Inspector, Cursor};
+
+///A single step of a parse, as recorded by a [`Trace`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Event {
+    ///A rule started
+    Enter {
+        ///The name of the rule
+        rule: String,
+        ///Where the rule started
+        offset: usize,
+    },
+    ///A rule finished
+    Exit {
+        ///The name of the rule
+        rule: String,
+        ///Where the rule started
+        start: usize,
+        ///Where the input was left when the rule finished
+        end: usize,
+        ///Whether the rule succeeded
+        ok: bool,
+    },
+    ///The input was rewound
+    Rewind {
+        ///Where the input was
+        from: usize,
+        ///Where the input was rewound to
+        to: usize,
+    },
+}
+
+impl Display for Event {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Enter { rule, offset } => write!(f, "> {offset} {}", escape(rule)),
+            Self::Exit { rule, start, end, ok } => {
+                let outcome = if *ok { "ok" } else { "fail" };
+                write!(f, "< {start} {end} {outcome} {}", escape(rule))
+            },
+            Self::Rewind { from, to } => write!(f, "^ {from} {to}"),
+        }
+    }
+}
+
+impl FromStr for Event {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let num = |s: Option<&str>| s.ok_or(())?.parse().map_err(|_| ());
+        let (kind, rest) = s.split_once(' ').ok_or(())?;
+        match kind {
+            ">" => {
+                let (offset, rule) = rest.split_once(' ').ok_or(())?;
+                Ok(Self::Enter { rule: unescape(rule), offset: num(Some(offset))? })
+            },
+            "<" => {
+                let mut parts = rest.splitn(4, ' ');
+                let start = num(parts.next())?;
+                let end = num(parts.next())?;
+                let ok = match parts.next() {
+                    Some("ok") => true,
+                    Some("fail") => false,
+                    _ => return Err(()),
+                };
+                Ok(Self::Exit { rule: unescape(parts.next().ok_or(())?), start, end, ok })
+            },
+            "^" => {
+                let (from, to) = rest.split_once(' ').ok_or(())?;
+                Ok(Self::Rewind { from: num(Some(from))?, to: num(Some(to))? })
+            },
+            _ => Err(()),
+        }
+    }
+}
+
+fn escape(rule: &str) -> String {
+    rule.replace('\\', "\\\\").replace('\n', "\\n").replace('\r', "\\r")
+}
+
+fn unescape(rule: &str) -> String {
+    let mut out = String::with_capacity(rule.len());
+    let mut chars = rule.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue
+        }
+        match chars.next() {
+            Some('n') => out.push('\n'),
+            Some('r') => out.push('\r'),
+            Some(c) => out.push(c),
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+/**
+An [`Inspector`] that records every step of a parse: each rule starting and finishing, and each
+time the input is rewound. Its [`Display`] impl writes a compact log with one [`Event`] per line,
+which can be read back with [`FromStr`](std::str::FromStr) and fed to another inspector with [`Trace::replay`]. This
+makes it possible to ship a trace from wherever a parse misbehaved, and diagnose it somewhere
+else, or compare it to a known good run in a test.
+```
+# use parsa::{Parser, ParserString};
+# use parsa::inspect::{Trace, AttemptTree};
+# use parsa::builtins::{take, BuiltinErr};
+let mut input = ParserString::from("b");
+input.set_inspector(Trace::new());
+let _ = take("a").convert_err::<BuiltinErr>().named("a")
+    .or(take("b").named("b"))
+    .parse(&mut input);
+
+let trace = input.take_inspector::<Trace>().unwrap();
+let log = trace.to_string();
+assert_eq!(log, "> 0 or\n> 0 a\n< 0 1 fail a\n^ 1 0\n> 0 b\n< 0 1 ok b\n< 0 1 ok or\n");
+
+//later, somewhere else
+let trace: Trace = log.parse().unwrap();
+let mut tree = AttemptTree::new();
+trace.replay(&mut tree);
+assert_eq!(tree.attempts()[0].children[1].rule, "b");
+```
+*/
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Trace {
+    events: Vec<Event>,
+}
+
+impl Trace {
+    ///Constructs an empty trace.
+    pub fn new() -> Self { Self::default() }
+
+    ///Get the recorded events, in order.
+    pub fn events(&self) -> &[Event] {
+        &self.events
+    }
+
+    ///Take the recorded events, leaving the trace empty.
+    pub fn take_events(&mut self) -> Vec<Event> {
+        std::mem::take(&mut self.events)
+    }
+
+    ///Feeds every recorded event to `inspector`, as if it had watched the parse itself. The
+    ///original input isn't available, so [`Cursor::preview`] and [`Cursor::text`] are empty.
+    pub fn replay(&self, inspector: &mut dyn Inspector) {
+        for event in &self.events {
+            match event {
+                Event::Enter { rule, offset } => inspector.enter(rule, &Replayed(*offset)),
+                Event::Exit { rule, start, end, ok } => inspector.exit(rule, &Replayed(*end), *start, *ok),
+                Event::Rewind { from, to } => inspector.rewind(*from, *to),
+            }
+        }
+    }
+
+    /**
+    Returns the index of the first event where this trace and `other` differ, or [`None`] if they
+    are the same.
+    ```
+    # use parsa::inspect::Trace;
+    let a: Trace = "> 0 x\n< 0 1 ok x\n".parse().unwrap();
+    let b: Trace = "> 0 x\n< 0 0 fail x\n".parse().unwrap();
+    assert_eq!(a.divergence(&a), None);
+    assert_eq!(a.divergence(&b), Some(1));
+    ```
+    */
+    pub fn divergence(&self, other: &Trace) -> Option<usize> {
+        let mismatch = self.events.iter().zip(&other.events).position(|(a, b)| a != b);
+        match mismatch {
+            Some(i) => Some(i),
+            None if self.events.len() != other.events.len() => Some(self.events.len().min(other.events.len())),
+            None => None,
+        }
+    }
+}
+
+impl Display for Trace {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.events.iter().try_for_each(|event| writeln!(f, "{event}"))
+    }
+}
+
+impl FromStr for Trace {
+    type Err = TraceErr;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let events = s.lines().enumerate()
+            .filter(|(_, line)| !line.is_empty())
+            .map(|(i, line)| line.parse().map_err(|_| TraceErr { line: i + 1 }))
+            .collect::<Result<_, _>>()?;
+        Ok(Self { events })
+    }
+}
+
+impl Inspector for Trace {
+    fn enter(&mut self, rule: &str, s: &dyn Cursor) {
+        self.events.push(Event::Enter { rule: rule.to_owned(), offset: s.offset() });
+    }
+
+    fn exit(&mut self, rule: &str, s: &dyn Cursor, start: usize, ok: bool) {
+        self.events.push(Event::Exit { rule: rule.to_owned(), start, end: s.offset(), ok });
+    }
+
+    fn rewind(&mut self, from: usize, to: usize) {
+        self.events.push(Event::Rewind { from, to });
+    }
+}
+
+///Indicates that a [`Trace`] log couldn't be read.
+#[derive(Debug, Clone, Copy, Error, PartialEq, Eq)]
+#[error("malformed trace event on line {line}")]
+pub struct TraceErr {
+    ///The line of the malformed event, starting at 1
+    pub line: usize,
+}
+
+//a cursor that only knows where it is, for replaying
+struct Replayed(usize);
+
+impl Cursor for Replayed {
+    fn offset(&self) -> usize {
+        self.0
+    }
+
+    fn preview(&self, _: usize) -> String {
+        String::new()
+    }
+}
@@ -0,0 +1,134 @@
+use std::fmt::{Display, Write};
+
+use crate::grammar::json_str;
+use super::{Inspector, Cursor};
+
+///A rule that ran, as recorded by an [`AttemptTree`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Attempt {
+    ///The name of the rule
+    pub rule: String,
+    ///Where the rule started
+    pub start: usize,
+    ///Where the input was left when the rule finished
+    pub end: usize,
+    ///Whether the rule succeeded
+    pub ok: bool,
+    ///The text from `start` to `end`
+    pub text: String,
+    ///The rules this one ran, in order
+    pub children: Vec<Attempt>,
+}
+
+impl Attempt {
+    ///Renders this attempt and everything under it as JSON.
+    pub fn to_json(&self) -> String {
+        let mut out = String::new();
+        self.write_json(&mut out);
+        out
+    }
+
+    fn write_json(&self, out: &mut String) {
+        let _ = write!(out, r#"{{"rule":{},"start":{},"end":{},"ok":{},"text":{},"children":["#,
+            json_str(&self.rule), self.start, self.end, self.ok, json_str(&self.text));
+        for (i, child) in self.children.iter().enumerate() {
+            if i > 0 { out.push(',') }
+            child.write_json(out);
+        }
+        out.push_str("]}");
+    }
+
+    fn write_tree(&self, f: &mut std::fmt::Formatter<'_>, depth: usize) -> std::fmt::Result {
+        let outcome = if self.ok { "ok" } else { "failed" };
+        writeln!(f, "{}{} {outcome} {}..{} {:?}", "  ".repeat(depth), self.rule, self.start, self.end, self.text)?;
+        self.children.iter().try_for_each(|child| child.write_tree(f, depth + 1))
+    }
+}
+
+impl Display for Attempt {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.write_tree(f, 0)
+    }
+}
+
+/**
+An [`Inspector`] that records every rule that runs as a tree of [`Attempt`]s: where each started
+and ended, whether it succeeded, and the text it covered. The tree can be rendered as JSON for
+offline inspection, or with [`Display`] as an indented outline, which suits snapshot tests.
+```
+# use parsa::{Parser, ParserString};
+# use parsa::inspect::AttemptTree;
+# use parsa::builtins::{take, BuiltinErr};
+let mut input = ParserString::from("ab");
+input.set_inspector(AttemptTree::new());
+let _ = take("a").convert_err::<BuiltinErr>().named("a")
+    .chain(take("c").named("c").or(take("b").named("b")))
+    .parse(&mut input);
+
+let tree = input.take_inspector::<AttemptTree>().unwrap();
+assert_eq!(tree.to_string(), concat!(
+    "chain ok 0..2 \"ab\"\n",
+    "  a ok 0..1 \"a\"\n",
+    "  or ok 1..2 \"b\"\n",
+    "    c failed 1..2 \"b\"\n",
+    "    b ok 1..2 \"b\"\n",
+));
+assert!(tree.to_json().starts_with(r#"[{"rule":"chain","start":0,"end":2,"ok":true,"text":"ab","children":["#));
+```
+*/
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AttemptTree {
+    roots: Vec<Attempt>,
+    //rules that have started but not finished, innermost last
+    open: Vec<Attempt>,
+}
+
+impl AttemptTree {
+    ///Constructs an empty tree.
+    pub fn new() -> Self { Self::default() }
+
+    ///Get the outermost rules that finished, in order.
+    pub fn attempts(&self) -> &[Attempt] {
+        &self.roots
+    }
+
+    ///Take the outermost rules that finished, leaving the tree empty.
+    pub fn take_attempts(&mut self) -> Vec<Attempt> {
+        std::mem::take(&mut self.roots)
+    }
+
+    ///Renders every finished rule as a JSON array.
+    pub fn to_json(&self) -> String {
+        let mut out = String::from("[");
+        for (i, attempt) in self.roots.iter().enumerate() {
+            if i > 0 { out.push(',') }
+            attempt.write_json(&mut out);
+        }
+        out.push(']');
+        out
+    }
+}
+
+impl Display for AttemptTree {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.roots.iter().try_for_each(|attempt| attempt.write_tree(f, 0))
+    }
+}
+
+impl Inspector for AttemptTree {
+    fn enter(&mut self, rule: &str, s: &dyn Cursor) {
+        let start = s.offset();
+        self.open.push(Attempt { rule: rule.to_owned(), start, end: start, ok: false, text: String::new(), children: vec![] });
+    }
+
+    fn exit(&mut self, _: &str, s: &dyn Cursor, start: usize, ok: bool) {
+        let Some(mut attempt) = self.open.pop() else { return };
+        attempt.end = s.offset();
+        attempt.ok = ok;
+        attempt.text = s.text(start..attempt.end.max(start));
+        match self.open.last_mut() {
+            Some(parent) => parent.children.push(attempt),
+            None => self.roots.push(attempt),
+        }
+    }
+}
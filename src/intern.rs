@@ -0,0 +1,129 @@
+/*!
+Interning identifiers as cheap symbols.
+
+Wrapping a parser with [`Parser::interned`] stores each distinct string it produces once in an
+[`Interner`], and returns a [`Symbol`] in its place. Symbols are `Copy`, and comparing or hashing
+them doesn't touch the text. Combined with a [`ParserStr`](crate::ParserStr), whose parsers
+return borrowed slices, identifiers are parsed without allocating per occurrence.
+
+```
+# use parsa::{Parser, ParserStr};
+# use parsa::builtins::borrowed::{word, whitespace};
+# use parsa::intern::Interner;
+let text = String::from("let x = x + y");
+let interner = Interner::new();
+let idents = word.interned(&interner).after(whitespace).many();
+
+let symbols = idents.parse(&mut ParserStr::from(text.as_str())).unwrap();
+assert_eq!(symbols.len(), 6);
+assert_eq!(symbols[1], symbols[3]);
+assert_eq!(interner.len(), 5);
+assert_eq!(interner.resolve(symbols[5]).as_deref(), Some("y"));
+```
+*/
+
+use std::{cell::RefCell, collections::HashMap, fmt::Debug, rc::Rc};
+
+use crate::{Parser, ParserInput, Input};
+
+///A handle to a string stored in an [`Interner`]. Symbols from different interners shouldn't be
+///mixed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Symbol(u32);
+
+impl Symbol {
+    ///The position of this symbol's string in its interner. Symbols are numbered from 0 in the
+    ///order their strings were first interned.
+    pub const fn index(self) -> usize { self.0 as usize }
+}
+
+///Storage for interned strings. See the [module docs](crate::intern).
+#[derive(Default)]
+pub struct Interner {
+    symbols: RefCell<HashMap<Rc<str>, Symbol>>,
+    strings: RefCell<Vec<Rc<str>>>,
+}
+
+impl Interner {
+    ///Constructs an empty interner.
+    pub fn new() -> Self { Self::default() }
+
+    ///Returns the symbol for `text`, storing it if it hasn't been seen before. Panics if more than
+    ///`u32::MAX` strings are stored.
+    pub fn intern(&self, text: &str) -> Symbol {
+        if let Some(&symbol) = self.symbols.borrow().get(text) {
+            return symbol
+        }
+        let mut strings = self.strings.borrow_mut();
+        let symbol = Symbol(u32::try_from(strings.len()).expect("too many interned strings"));
+        let text: Rc<str> = Rc::from(text);
+        strings.push(text.clone());
+        self.symbols.borrow_mut().insert(text, symbol);
+        symbol
+    }
+
+    ///Returns the symbol for `text` if it has been interned, without storing it.
+    pub fn get(&self, text: &str) -> Option<Symbol> {
+        self.symbols.borrow().get(text).copied()
+    }
+
+    ///Returns the string behind `symbol`, or [`None`] if it didn't come from this interner.
+    pub fn resolve(&self, symbol: Symbol) -> Option<Rc<str>> {
+        self.strings.borrow().get(symbol.index()).cloned()
+    }
+
+    ///The amount of distinct strings stored.
+    pub fn len(&self) -> usize {
+        self.strings.borrow().len()
+    }
+
+    ///Returns `true` if no strings are stored.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Debug for Interner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.strings.borrow().iter()).finish()
+    }
+}
+
+/**
+Interns the output of a parser, returning its [`Symbol`]. See the [module docs](crate::intern).
+*/
+pub struct Interned<'a, T, P> {
+    p: P,
+    interner: &'a Interner,
+    _phantom: std::marker::PhantomData<T>,
+}
+
+impl<T, P: Clone> Clone for Interned<'_, T, P> {
+    fn clone(&self) -> Self { Self { p: self.p.clone(), interner: self.interner, _phantom: std::marker::PhantomData } }
+}
+
+impl<T, P: Debug> Debug for Interned<'_, T, P> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Interned").field("p", &self.p).finish_non_exhaustive()
+    }
+}
+
+impl<'a, T, P> Interned<'a, T, P> {
+    ///Constructs this parser.
+    pub const fn new(p: P, interner: &'a Interner) -> Self { Self { p, interner, _phantom: std::marker::PhantomData } }
+}
+
+impl<T: AsRef<str>, I: ?Sized + Input, P> Parser<Symbol, I> for Interned<'_, T, P>
+where
+    P: Parser<T, I>
+{
+    type Err = P::Err;
+
+    fn parse(&self, s: &mut ParserInput<I>) -> Result<Symbol, Self::Err> {
+        self.p.parse(s).map(|text| self.interner.intern(text.as_ref()))
+    }
+
+    fn grammar(&self) -> crate::grammar::Grammar {
+        self.p.grammar()
+    }
+}
@@ -3,11 +3,45 @@
 
 mod parser;
 pub use parser::*;
+mod diagnostics;
+pub use diagnostics::*;
+mod suggest;
+pub use suggest::*;
+mod backtrack;
+pub use backtrack::*;
+mod fuel;
+use fuel::Fuel;
+mod limits;
+pub use limits::*;
+mod misuse;
+pub use misuse::*;
+mod bytes;
+pub use bytes::*;
+mod tokens;
+pub use tokens::*;
+mod borrowed;
+pub use borrowed::*;
+mod encoding;
+mod location;
+pub use location::*;
+mod unparse;
+pub use unparse::*;
 
 pub mod combinators;
+pub mod incremental;
+pub mod intern;
+pub mod grammar;
+pub mod inspect;
+pub mod stream;
+#[cfg(feature = "peg")]
+pub mod peg;
 #[cfg(feature = "builtins")] 
 pub mod builtins;
 
+///Derives [`ParsableBytes`] for structs with a fixed binary layout. Requires the `derive` feature.
+#[cfg(feature = "derive")]
+pub use parsa_derive::ParsableBytes;
+
 ///Implicit [`Infallible`] conversions.
 ///
 ///[`Infallible`]: std::convert::Infallible
@@ -16,19 +50,125 @@ pub use nevermore::FromNever;
 #[cfg(test)]
 mod tests;
 
-use std::{cell::Cell, fmt::{Debug, Display}};
+use std::{fmt::{Debug, Display}, ops::Range};
+
+/**
+A shrinking window over some input, consumed by [`Parser`]s. `I` is the kind of input: [`str`]
+for text ([`ParserString`]), `[u8]` for binary data ([`ParserBytes`]), [`Bits`] for bit fields
+([`ParserBits`]), [`Tokens`] for the output of a lexer ([`ParserTokens`]), or [`Borrowed`] text
+that outputs can borrow from ([`ParserStr`]). Other kinds of input can be added by implementing
+[`Input`].
+
+Positions and lengths are counted in the units the input is indexed by: bytes for text and binary
+data, bits for bit fields, and tokens for tokens. Everything that doesn't depend on the kind of
+input, like [checkpoints](ParserInput::checkpoint) and [diagnostics](ParserInput::diagnostics), is
+shared, and every combinator works the same way over any of them.
+
+Inputs are [`Send`] and [`Sync`], so one can be read from scoped threads, for example to find
+the regions each thread should parse.
+*/
+pub struct ParserInput<I: ?Sized> {
+    full: Box<I>,
+    ptr: usize,
+    //where the input ends, which can be before the end of `full`
+    end: usize,
+    //where the input ended before it was halted, if it was
+    halted: Option<usize>,
+    diagnostics: Vec<Diagnostic>,
+    expected: Expected,
+    inspector: Option<Box<dyn inspect::Inspector>>,
+    backtrack: Backtrack,
+    fuel: Fuel,
+    sizes: Sizes,
+    misuse: Misuse,
+    tab_width: TabWidth,
+}
+
 ///A shrinking-window read-only string.
 ///
 ///String slices can be taken from the front, and reset, with zero
 ///allocations or copies.
-pub struct ParserString {
-    full: Box<str>,
-    ptr: Cell<usize>,
+pub type ParserString = ParserInput<str>;
+
+///Something a [`ParserInput`] can be made over. Implement this to parse a kind of input not
+///provided by this crate, and construct inputs with [`ParserInput::new`].
+pub trait Input {
+    ///The length of the input, in the units it is indexed by.
+    fn size(&self) -> usize;
+
+    ///Returns `true` if `offset` is a valid position to move to.
+    fn is_boundary(&self, offset: usize) -> bool;
+
+    ///The position `n` items after `offset`, or [`None`] if that is past the end. Items are
+    ///characters for text, and single units otherwise.
+    fn advance(&self, offset: usize, n: usize) -> Option<usize> {
+        offset.checked_add(n).filter(|&end| end <= self.size())
+    }
+
+    ///Renders at most `max` units of `range` as text, for messages and debugging.
+    fn describe(&self, range: Range<usize>, max: usize) -> String;
+}
+
+impl Input for str {
+    fn size(&self) -> usize { self.len() }
+
+    fn is_boundary(&self, offset: usize) -> bool { self.is_char_boundary(offset) }
+
+    fn advance(&self, offset: usize, n: usize) -> Option<usize> {
+        let rest = self.get(offset..)?;
+        match rest.char_indices().nth(n) {
+            Some((i, _)) => Some(offset + i),
+            None => (rest.chars().count() == n).then_some(self.len()),
+        }
+    }
+
+    fn describe(&self, range: Range<usize>, max: usize) -> String {
+        self.get(range).unwrap_or_default().chars().take(max).collect()
+    }
+}
+
+///A saved position in a [`ParserString`], created with [`ParserInput::checkpoint`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Checkpoint {
+    ptr: usize,
+    diagnostics: usize,
+}
+
+///A range of the string, relative to the "true" start, as passed to [`Parser::and_then_spanned`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Span {
+    ///Where the range starts
+    pub start: usize,
+    ///Where the range ends, exclusive
+    pub end: usize,
+}
+
+impl Span {
+    ///Constructs a span.
+    pub fn new(start: usize, end: usize) -> Self { Self { start, end } }
+
+    ///The length of the span, in bytes.
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    ///Returns `true` if the span covers nothing.
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+}
+
+impl From<Span> for std::ops::Range<usize> {
+    fn from(value: Span) -> Self {
+        value.start..value.end
+    }
 }
 
-fn update<T: Copy, F: Fn(T) -> T>(cell: &Cell<T>, f: F) {
-    let a = cell.get();
-    cell.set(f(a));
+impl Checkpoint {
+    ///The position this checkpoint was taken at, relative to the "true" start.
+    pub fn offset(&self) -> usize {
+        self.ptr
+    }
 }
 
 impl ParserString {
@@ -46,19 +186,18 @@ impl ParserString {
     ///assert_eq!(input.take(1), "🌏");
     ///```
     pub fn take(&mut self, n: usize) -> &str {
-        let offs = self.get().chars()
+        let offs: usize = self.get().chars()
             .take(n).map(char::len_utf8).sum();
+        let start = self.ptr;
+        self.ptr += offs;
 
-        let (front, _) = self.get().split_at(offs);
-
-        update(&self.ptr, |ptr| ptr + offs);
+        assert!(self.ptr <= self.end);
 
-        assert!(self.ptr.get() <= self.full.len());
-
-        front
+        &self.full[start..self.ptr]
     }
 
-    ///Splits the string at `n`, shrinking it. Returns [`None`] if `n` is larger than the remaining slice.
+    ///Splits the string at `n`, shrinking it. Returns [`None`] if `n` is larger than the remaining
+    ///slice, or if the text would be longer than the [output limit](SizeLimits::max_output).
     ///```rust
     ///# use parsa::ParserString;
     ///let mut input = ParserString::from("abc123");
@@ -67,16 +206,37 @@ impl ParserString {
     ///
     ///```
     pub fn try_take(&mut self, n: usize) -> Option<&str> {
-        if self.ptr.get() + n > self.full.len() {
+        if self.ptr + n > self.end {
             return None;
         }
 
-        let offs = self.get().chars()
+        let offs: usize = self.get().chars()
             .take(n).map(char::len_utf8).sum();
+        if !self.allow_output(offs) { return None }
 
-        let (front, _) = self.get().split_at(offs);
-        update(&self.ptr, |ptr| ptr + offs);
-        Some(front)
+        let start = self.ptr;
+        self.ptr += offs;
+        Some(&self.full[start..self.ptr])
+    }
+
+    ///Splits off the next `n` grapheme clusters, or fewer if the string ends first. Unlike
+    ///[`ParserString::take`], this never splits a user-perceived character, like an emoji with a
+    ///skin tone modifier or a letter with combining marks.
+    ///
+    ///Requires the `unicode-segmentation` feature.
+    ///```rust
+    ///# use parsa::ParserString;
+    ///let mut input = ParserString::from("🇳🇴e\u{301}!");
+    ///assert_eq!(input.take_graphemes(2), "🇳🇴e\u{301}");
+    ///assert_eq!(input.take_graphemes(2), "!");
+    ///```
+    #[cfg(feature = "unicode-segmentation")]
+    pub fn take_graphemes(&mut self, n: usize) -> &str {
+        use unicode_segmentation::UnicodeSegmentation;
+        let len = self.get().grapheme_indices(true).nth(n).map_or(self.len(), |(i, _)| i);
+        let start = self.ptr;
+        self.ptr += len;
+        &self.full[start..self.ptr]
     }
 
     ///Rewinds the string slice `n` spaces. Panics if `n` is larger than the taken space.    
@@ -97,7 +257,7 @@ impl ParserString {
     ///considered undefined behavior. This will never cause memory-unsafety, but can cause
     ///unpredictable things to happen.
     pub unsafe fn give(&mut self, n: usize) {
-        *self.ptr.get_mut() -= n;
+        self.ptr -= n;
     }
 
     ///Set the current start position manually.
@@ -110,7 +270,7 @@ impl ParserString {
     ///assert_eq!(input.get(), "123");
     ///```
     pub unsafe fn set_ptr(&mut self, ptr: usize) {
-        self.ptr.set(ptr);
+        self.ptr = ptr;
     }
 
     ///Get a reference to the string slice.
@@ -122,10 +282,185 @@ impl ParserString {
     ///assert_eq!(input.get(), "c123");
     ///```
     pub fn get(&self) -> &str {
-        &self.full[self.ptr.get()..]
+        &self.full[self.ptr..self.end]
     }
 
-    ///Get the length of the string.
+    ///Get the next `n` characters without consuming them, or fewer if the string ends first.
+    ///```rust
+    ///# use parsa::ParserString;
+    ///let input = ParserString::from("🗻∈🌏");
+    ///assert_eq!(input.peek(2), "🗻∈");
+    ///assert_eq!(input.peek(5), "🗻∈🌏");
+    ///```
+    pub fn peek(&self, n: usize) -> &str {
+        let end = self.get().char_indices().nth(n).map_or(self.len(), |(i, _)| i);
+        &self.get()[..end]
+    }
+
+    ///Get the text consumed since `checkpoint` was taken. Panics if the string has been rewound
+    ///to before it.
+    ///```rust
+    ///# use parsa::ParserString;
+    ///let mut input = ParserString::from("abc123");
+    ///let cp = input.checkpoint();
+    ///let _ = input.take(3);
+    ///assert_eq!(input.since(cp), "abc");
+    ///```
+    pub fn since(&self, checkpoint: Checkpoint) -> &str {
+        &self.full[checkpoint.ptr..self.ptr]
+    }
+
+    ///Renders the input as `"consumed|remaining"`, with the cursor as `|` and control characters
+    ///escaped. Useful when stepping through parsers in tests. See
+    ///[`ParserString::cursor_window`] for long inputs.
+    ///```rust
+    ///# use parsa::ParserString;
+    ///let mut input = ParserString::from("let x\n= 1");
+    ///input.take(4);
+    ///assert_eq!(input.cursor_view(), r#""let |x\n= 1""#);
+    ///```
+    pub fn cursor_view(&self) -> String {
+        self.cursor_window(usize::MAX)
+    }
+
+    ///Like [`ParserString::cursor_view`], but showing at most `radius` characters on either
+    ///side of the cursor, with `…` where text was left out.
+    ///```rust
+    ///# use parsa::ParserString;
+    ///let mut input = ParserString::from("0123456789");
+    ///input.take(5);
+    ///assert_eq!(input.cursor_window(2), r#"…"34|56"…"#);
+    ///assert_eq!(input.cursor_window(5), r#""01234|56789""#);
+    ///```
+    pub fn cursor_window(&self, radius: usize) -> String {
+        fn escape(out: &mut String, text: impl Iterator<Item = char>) {
+            for c in text {
+                match c.is_control() {
+                    true => out.extend(c.escape_debug()),
+                    false => out.push(c),
+                }
+            }
+        }
+
+        let consumed = &self.full[..self.ptr];
+        let remaining = self.get();
+        let before = consumed.chars().rev().take(radius).count();
+        let after = remaining.chars().take(radius).count();
+
+        let mut out = String::new();
+        if before < consumed.chars().count() {
+            out.push('…');
+        }
+        out.push('"');
+        escape(&mut out, consumed.chars().skip(consumed.chars().count() - before));
+        out.push('|');
+        escape(&mut out, remaining.chars().take(after));
+        out.push('"');
+        if remaining.chars().nth(after).is_some() {
+            out.push('…');
+        }
+        out
+    }
+}
+
+impl<I: ?Sized + Input> ParserInput<I> {
+    ///Constructs an input over `full`, starting at the beginning.
+    pub fn new(full: Box<I>) -> Self {
+        Self {
+            end: full.size(),
+            full,
+            ptr: 0,
+            halted: None,
+            diagnostics: vec![],
+            expected: Expected::default(),
+            inspector: None,
+            backtrack: Backtrack::default(),
+            fuel: Fuel::default(),
+            sizes: Sizes::default(),
+            misuse: Misuse::default(),
+            tab_width: TabWidth::default(),
+        }
+    }
+
+    //moves everything over to a different view of the same input, translating offsets with
+    //`offset`
+    #[cfg(feature = "builtins")]
+    fn reinterpret<J: ?Sized + Input>(self, full: impl FnOnce(Box<I>) -> Box<J>, offset: impl Fn(usize) -> usize) -> ParserInput<J> {
+        let mut diagnostics = self.diagnostics;
+        for d in &mut diagnostics {
+            d.offset = offset(d.offset);
+        }
+        let mut expected = self.expected;
+        expected.offset = offset(expected.offset);
+        let mut backtrack = self.backtrack;
+        backtrack.exceeded = backtrack.exceeded.map(&offset);
+        let mut fuel = self.fuel;
+        fuel.exhausted = fuel.exhausted.map(&offset);
+        let mut sizes = self.sizes;
+        sizes.exceeded = sizes.exceeded.map(|e| e.map_offset(&offset));
+
+        ParserInput {
+            full: full(self.full),
+            ptr: offset(self.ptr),
+            end: offset(self.end),
+            halted: self.halted.map(&offset),
+            diagnostics,
+            expected,
+            inspector: self.inspector,
+            backtrack,
+            fuel,
+            sizes,
+            misuse: Misuse { committed: None, ..self.misuse },
+            tab_width: self.tab_width,
+        }
+    }
+
+    //where a region of `n` items starting here would end, if there's enough input
+    pub(crate) fn region_end(&self, n: usize) -> Option<usize> {
+        self.full.advance(self.start(), n).filter(|&end| end <= self.end)
+    }
+
+    //runs `f` with the input cut off at `end`
+    pub(crate) fn bounded<T>(&mut self, end: usize, f: impl FnOnce(&mut Self) -> T) -> T {
+        let outer = self.end;
+        let halted = self.halted.is_some();
+        self.end = end.min(outer);
+        let out = f(self);
+        match &mut self.halted {
+            //halted inside, so resuming should go back to the outer end
+            Some(end) if !halted => *end = outer,
+            _ => self.end = outer,
+        }
+        out
+    }
+
+    //stops the parse where it is, after a limit was exceeded. nothing is left to consume and the
+    //input can't be rewound, so the parse winds down quickly without moving the cursor. it still
+    //isn't `is_empty`, so a parse that ends here doesn't pass for a complete one
+    pub(crate) fn halt(&mut self) {
+        self.halted.get_or_insert(self.end);
+        self.end = self.ptr;
+    }
+
+    //undoes `halt`, once the limit has been reported
+    pub(crate) fn resume(&mut self) {
+        if let Some(end) = self.halted.take() {
+            self.end = end;
+        }
+    }
+
+    pub(crate) fn is_halted(&self) -> bool {
+        self.halted.is_some()
+    }
+
+    //moves the cursor to `ptr`, unless the input is halted
+    pub(crate) fn seek(&mut self, ptr: usize) {
+        if !self.is_halted() {
+            self.ptr = ptr;
+        }
+    }
+
+    ///Get the length of the remaining input.
     ///```rust
     ///# use parsa::ParserString;
     ///let mut input = ParserString::from("abc123");
@@ -133,10 +468,29 @@ impl ParserString {
     ///assert_eq!(input.len(), 4);
     ///```
     pub fn len(&self) -> usize {
-        self.full.len() - self.ptr.get()
+        self.end - self.ptr
+    }
+
+    ///Returns `true` if there is nothing left to consume. A string stopped by an exceeded limit
+    ///(see [`ParserInput::set_fuel`]) is never empty, as the rest of it was never parsed.
+    ///```rust
+    ///# use parsa::{Parser, ParserString};
+    ///# use parsa::builtins::take;
+    ///let mut input = ParserString::from("abc");
+    ///let _ = input.take(3);
+    ///assert!(input.is_empty());
+    ///
+    ///let mut input = ParserString::from("aaa");
+    ///input.set_fuel(Some(2));
+    ///let _ = take("a").many().parse(&mut input);
+    ///assert_eq!(input.len(), 0);
+    ///assert!(!input.is_empty());
+    ///```
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0 && !self.is_halted()
     }
 
-    ///Get the current start of the string, relative to the "true" start.
+    ///Get the current start of the input, relative to the "true" start.
     ///```rust
     ///# use parsa::ParserString;
     ///let mut input = ParserString::from("abc123");
@@ -144,25 +498,57 @@ impl ParserString {
     ///assert_eq!(input.start(), 2);
     ///```
     pub fn start(&self) -> usize {
-        self.ptr.get()
+        self.ptr
+    }
+
+    ///Save the current position, to be restored later with [`ParserInput::rewind`].
+    ///```rust
+    ///# use parsa::ParserString;
+    ///let mut input = ParserString::from("abc123");
+    ///let cp = input.checkpoint();
+    ///let _ = input.take(3);
+    ///input.warn("discarded on rewind");
+    ///
+    ///input.rewind(cp);
+    ///assert_eq!(input.get(), "abc123");
+    ///assert!(input.diagnostics().is_empty());
+    ///```
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint { ptr: self.ptr, diagnostics: self.diagnostics.len() }
+    }
+
+    ///Restore a position saved with [`ParserInput::checkpoint`], discarding any diagnostics
+    ///emitted since. Checkpoints should only be used with the input that created them.
+    ///
+    ///Rewinding counts towards the [backtrack limit](ParserInput::set_backtrack_limit), and has
+    ///no effect once the [fuel](ParserInput::set_fuel) has run out or a
+    ///[size limit](ParserInput::set_size_limits) is exceeded.
+    pub fn rewind(&mut self, checkpoint: Checkpoint) {
+        let ptr = self.ptr;
+        if self.is_halted() {
+            return
+        }
+        if ptr > checkpoint.ptr && !self.backtrack.record(ptr, ptr - checkpoint.ptr) {
+            self.halt();
+            return
+        }
+        self.ptr = checkpoint.ptr;
+        self.diagnostics.truncate(checkpoint.diagnostics);
+        if let Some(inspector) = self.inspector.as_mut().filter(|_| ptr != checkpoint.ptr) {
+            inspector.rewind(ptr, checkpoint.ptr);
+        }
     }
 }
 
 impl From<&str> for ParserString {
     fn from(value: &str) -> Self {
-        Self {
-            full: Box::from(value),
-            ptr: Cell::new(0),
-        }
+        Self::from(value.to_owned())
     }
 }
 
 impl From<String> for ParserString {
     fn from(value: String) -> Self {
-        Self {
-            full: value.into_boxed_str(),
-            ptr: Cell::new(0),
-        }
+        Self::new(value.into_boxed_str())
     }
 }
 
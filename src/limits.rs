@@ -0,0 +1,219 @@
+use std::fmt::Display;
+
+use nevermore::FromNever;
+
+use crate::{ParserInput, Input};
+
+/**
+Hard limits on how much a parse may read and produce, set with [`ParserInput::set_size_limits`]
+or the [`SizeLimited`](crate::combinators::SizeLimited) combinator. These are a last line of
+defense when parsing hostile input, on top of whatever the grammar itself allows.
+```
+# use parsa::SizeLimits;
+let limits = SizeLimits::input(1 << 20).items(1000).output(256);
+assert_eq!(limits.max_input, Some(1 << 20));
+assert_eq!(limits.max_items, Some(1000));
+assert_eq!(limits.max_output, Some(256));
+```
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct SizeLimits {
+    ///The maximum length of the remaining string, in bytes, when the limits are set
+    pub max_input: Option<usize>,
+    ///The maximum amount of items a single repetition, like [`Many`](crate::combinators::Many),
+    ///may collect
+    pub max_items: Option<usize>,
+    ///The maximum length, in bytes, of text split off the string at once, like with
+    ///[`ParserString::try_take`](crate::ParserString::try_take)
+    pub max_output: Option<usize>,
+}
+
+impl SizeLimits {
+    ///Limits that never trigger.
+    pub const NONE: Self = Self { max_input: None, max_items: None, max_output: None };
+
+    ///Limit the length of the input.
+    pub const fn input(max: usize) -> Self {
+        Self { max_input: Some(max), ..Self::NONE }
+    }
+
+    ///Limit the amount of items collected by a repetition.
+    pub const fn items(self, max: usize) -> Self {
+        Self { max_items: Some(max), ..self }
+    }
+
+    ///Limit the length of text split off the string at once.
+    pub const fn output(self, max: usize) -> Self {
+        Self { max_output: Some(max), ..self }
+    }
+}
+
+///Which of the [`SizeLimits`] was exceeded, and where.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SizeExceeded {
+    ///The input was too long
+    Input {
+        ///The length of the input
+        len: usize,
+        ///The limit
+        max: usize,
+    },
+    ///A repetition collected too many items
+    Items {
+        ///Where the string was when the limit was reached
+        offset: usize,
+        ///The limit
+        max: usize,
+    },
+    ///Too much text was split off at once
+    Output {
+        ///Where the text would have started
+        offset: usize,
+        ///The length of the text
+        len: usize,
+        ///The limit
+        max: usize,
+    },
+}
+
+impl SizeExceeded {
+    #[cfg(feature = "builtins")]
+    pub(crate) fn map_offset(self, f: impl Fn(usize) -> usize) -> Self {
+        match self {
+            Self::Input { .. } => self,
+            Self::Items { offset, max } => Self::Items { offset: f(offset), max },
+            Self::Output { offset, len, max } => Self::Output { offset: f(offset), len, max },
+        }
+    }
+}
+
+impl Display for SizeExceeded {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Input { len, max } => write!(f, "input is {len} bytes long, but at most {max} are allowed"),
+            Self::Items { offset, max } => write!(f, "more than {max} items at offset {offset}"),
+            Self::Output { offset, len, max } => write!(f, "{len} bytes of text at offset {offset}, but at most {max} are allowed"),
+        }
+    }
+}
+impl std::error::Error for SizeExceeded {}
+
+///Indicates that a parser run with [`Parser::parse_limited`](crate::Parser::parse_limited) has
+///failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, FromNever)]
+pub enum LimitErr<E> {
+    ///The parser failed
+    Inner(E),
+    ///The [fuel](ParserInput::set_fuel) ran out
+    Fuel {
+        ///Where the string was when the fuel ran out
+        offset: usize,
+    },
+    ///One of the [size limits](ParserInput::set_size_limits) was exceeded
+    Size(SizeExceeded),
+    ///The [backtrack limit](ParserInput::set_backtrack_limit) was exceeded
+    Backtrack {
+        ///Where the string was when the limit was exceeded
+        offset: usize,
+    },
+}
+
+impl<E: Display> Display for LimitErr<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Inner(e) => write!(f, "{e}"),
+            Self::Fuel { offset } => write!(f, "ran out of fuel at offset {offset}"),
+            Self::Size(e) => write!(f, "{e}"),
+            Self::Backtrack { offset } => write!(f, "backtrack limit exceeded at offset {offset}"),
+        }
+    }
+}
+impl<E: std::error::Error> std::error::Error for LimitErr<E> {}
+
+//limits for a string
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct Sizes {
+    pub(crate) limits: SizeLimits,
+    pub(crate) exceeded: Option<SizeExceeded>,
+}
+
+impl<I: ?Sized + Input> ParserInput<I> {
+    /**
+    Set hard [`SizeLimits`] for this string from now on. If the remaining string is already longer
+    than [`SizeLimits::max_input`], the limit is exceeded immediately. Once any limit is exceeded,
+    nothing more can be consumed and the string can't be rewound, so the parse winds down quickly,
+    leaving a string that isn't [empty](ParserInput::is_empty).
+    Run the parser with [`Parser::parse_limited`](crate::Parser::parse_limited) to fail with
+    [`LimitErr::Size`] when that happens, or use [`SizeLimited`](crate::combinators::SizeLimited)
+    to limit only part of a parser.
+    ```
+    # use parsa::{Parser, ParserString, SizeLimits, SizeExceeded, LimitErr};
+    # use parsa::builtins::take;
+    let mut input = ParserString::from("aaaa");
+    input.set_size_limits(SizeLimits::NONE.items(2));
+    let res = take("a").many().parse_limited(&mut input);
+    assert_eq!(res, Err(LimitErr::Size(SizeExceeded::Items { offset: 2, max: 2 })));
+    assert_eq!(input.get(), "aa");
+
+    let mut input = ParserString::from("aaaa");
+    input.set_size_limits(SizeLimits::input(3));
+    assert_eq!(input.size_exceeded(), Some(SizeExceeded::Input { len: 4, max: 3 }));
+    ```
+    */
+    pub fn set_size_limits(&mut self, limits: SizeLimits) {
+        self.sizes = Sizes { limits, exceeded: None };
+        if let Some(max) = limits.max_input.filter(|&max| self.len() > max) {
+            self.exceed(SizeExceeded::Input { len: self.len(), max });
+        }
+    }
+
+    ///Get the current [`SizeLimits`].
+    pub fn size_limits(&self) -> SizeLimits {
+        self.sizes.limits
+    }
+
+    ///Which limit was exceeded, if any.
+    pub fn size_exceeded(&self) -> Option<SizeExceeded> {
+        self.sizes.exceeded
+    }
+
+    //records an exceeded limit, halting the input
+    pub(crate) fn exceed(&mut self, exceeded: SizeExceeded) {
+        if !self.is_halted() {
+            self.sizes.exceeded = Some(exceeded);
+            self.halt();
+        }
+    }
+
+    //checks if a repetition that has collected `count` items may collect another
+    pub(crate) fn allow_item(&mut self, count: usize) -> bool {
+        match self.sizes.limits.max_items {
+            Some(max) if count >= max => {
+                self.exceed(SizeExceeded::Items { offset: self.ptr, max });
+                false
+            },
+            _ => true,
+        }
+    }
+
+    //checks if `len` bytes may be split off the string
+    pub(crate) fn allow_output(&mut self, len: usize) -> bool {
+        match self.sizes.limits.max_output {
+            Some(max) if len > max => {
+                self.exceed(SizeExceeded::Output { offset: self.ptr, len, max });
+                false
+            },
+            _ => true,
+        }
+    }
+
+    //the limit that halted the string, if one did
+    pub(crate) fn limit_err<E>(&self) -> Option<LimitErr<E>> {
+        if !self.is_halted() {
+            return None
+        }
+        let fuel = self.fuel.exhausted.map(|offset| LimitErr::Fuel { offset });
+        let backtrack = self.backtrack.exceeded.map(|offset| LimitErr::Backtrack { offset });
+        fuel.or(self.sizes.exceeded.map(LimitErr::Size)).or(backtrack)
+    }
+}
@@ -0,0 +1,138 @@
+use std::fmt::Display;
+
+use crate::{ParserInput, ParserString, Input};
+
+/**
+How a tab advances the column in a [`Location`], set with [`ParserInput::set_tab_width`]. Pick
+whatever matches the editor the text is viewed in, so reported columns and the carets in
+[`ParserString::snippet`] line up with what the reader sees.
+```
+# use parsa::{ParserString, TabWidth};
+let mut input = ParserString::from("\tx = 1\n  \ty");
+assert_eq!(input.location(1).column, 2);
+
+input.set_tab_width(TabWidth::Columns(4));
+assert_eq!(input.location(1).column, 5);
+assert_eq!(input.location(10).column, 7);
+
+input.set_tab_width(TabWidth::Stop(4));
+assert_eq!(input.location(10).column, 5);
+```
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TabWidth {
+    ///A tab always advances this many columns
+    Columns(usize),
+    ///A tab advances to the next multiple of this many columns
+    Stop(usize),
+}
+
+impl Default for TabWidth {
+    fn default() -> Self { Self::Columns(1) }
+}
+
+impl TabWidth {
+    //the column after a tab at 0-based `column`
+    fn advance(self, column: usize) -> usize {
+        match self {
+            Self::Columns(n) => column + n,
+            Self::Stop(n) => (column / n.max(1) + 1) * n.max(1),
+        }
+    }
+}
+
+///A line and column in text, both counted from 1. Columns count characters, with tabs counted
+///according to the [`TabWidth`]. See [`ParserString::location`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Location {
+    ///The line, from 1
+    pub line: usize,
+    ///The column, from 1
+    pub column: usize,
+}
+
+impl Display for Location {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+impl<I: ?Sized + Input> ParserInput<I> {
+    ///Set how tabs advance the column in [`Location`]s. See [`TabWidth`].
+    pub fn set_tab_width(&mut self, width: TabWidth) {
+        self.tab_width = width;
+    }
+
+    ///Get how tabs advance the column in [`Location`]s.
+    pub fn tab_width(&self) -> TabWidth {
+        self.tab_width
+    }
+}
+
+impl ParserString {
+    //`offset` clamped to the end and moved back to the start of the character it falls in
+    fn char_offset(&self, offset: usize) -> usize {
+        let mut offset = offset.min(self.full.len());
+        while !self.full.is_char_boundary(offset) { offset -= 1 }
+        offset
+    }
+
+    //where the line containing `offset` starts and ends, not including the line ending. lines
+    //end at "\r\n", "\r" or "\n"
+    fn line_bounds(&self, offset: usize) -> (usize, usize) {
+        //an offset between the two halves of a "\r\n" is still on the line it ends
+        let before = match self.full[..offset].strip_suffix('\r') {
+            Some(before) if self.full[offset..].starts_with('\n') => before,
+            _ => &self.full[..offset],
+        };
+        let start = before.rfind(['\r', '\n']).map_or(0, |i| i + 1);
+        let end = self.full[start..].find(['\r', '\n']).map_or(self.full.len(), |i| start + i);
+        (start, end)
+    }
+
+    ///Get the line and column of a byte offset, relative to the "true" start, like the offset of
+    ///a [`Diagnostic`](crate::Diagnostic). Offsets past the end are clamped to it, and offsets
+    ///inside a character are moved back to its start. Lines end at `\r\n`, `\r` or `\n`.
+    ///```rust
+    ///# use parsa::{ParserString, Location};
+    ///let input = ParserString::from("let x =\n  1 +");
+    ///assert_eq!(input.location(10), Location { line: 2, column: 3 });
+    ///assert_eq!(input.location(10).to_string(), "2:3");
+    ///```
+    pub fn location(&self, offset: usize) -> Location {
+        let offset = self.char_offset(offset);
+        let (start, _) = self.line_bounds(offset);
+        let head = &self.full[..start];
+        let line = head.matches(['\r', '\n']).count() - head.matches("\r\n").count() + 1;
+        let column = self.full[start..offset].chars().fold(0, |col, c| match c {
+            '\t' => self.tab_width.advance(col),
+            _ => col + 1,
+        });
+        Location { line, column: column + 1 }
+    }
+
+    ///Renders the line containing a byte offset, with a caret under it. Tabs are expanded to
+    ///spaces according to the [`TabWidth`], so the caret lines up however the line is indented.
+    ///```rust
+    ///# use parsa::{ParserString, TabWidth};
+    ///let mut input = ParserString::from("a\n\tb = ?\n");
+    ///input.set_tab_width(TabWidth::Stop(4));
+    ///assert_eq!(input.snippet(7), "    b = ?\n        ^");
+    ///```
+    pub fn snippet(&self, offset: usize) -> String {
+        let offset = self.char_offset(offset);
+        let (start, end) = self.line_bounds(offset);
+        let mut line = String::new();
+        for c in self.full[start..end].chars() {
+            match c {
+                '\t' => {
+                    let to = self.tab_width.advance(line.chars().count());
+                    line.extend(std::iter::repeat_n(' ', to - line.chars().count()));
+                },
+                c => line.push(c),
+            }
+        }
+        let caret = " ".repeat(self.location(offset).column - 1);
+        format!("{line}\n{caret}^")
+    }
+}
@@ -0,0 +1,75 @@
+use crate::{ParserInput, Input, Checkpoint, Diagnostic, Severity};
+
+/**
+What to do when a parser fails after consuming input, set with [`ParserInput::set_misuse_check`].
+
+Combinators that try several alternatives, like [`Or`](crate::combinators::Or), rewind failed
+parsers with [`Parser::try_parse`](crate::Parser::try_parse), so consuming before failing is
+harmless there. Elsewhere it silently shifts where the next parser starts, which is rarely
+intended. With a check enabled, every [`try_parse`](crate::Parser::try_parse) reports parsers
+that do this, by name if they are [named](crate::Parser::named). Parsers that consume before
+failing on purpose can be marked [`committed`](crate::Parser::committed).
+
+Checks only run with `debug_assertions` enabled, and do nothing in release builds.
+```
+# use parsa::{Parser, ParserString, MisuseCheck, Severity};
+# use parsa::builtins::take;
+let mut input = ParserString::from("let y");
+input.set_misuse_check(MisuseCheck::Warn);
+
+let _ = take("let ").replace(take("x")).named("binding").or(take("fn")).parse(&mut input);
+# if !input.diagnostics().is_empty() {
+let warning = &input.diagnostics()[0];
+assert_eq!(warning.severity, Severity::Warning);
+assert_eq!(warning.message, "binding failed at offset 5 after consuming input from offset 0");
+# }
+```
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum MisuseCheck {
+    ///Don't check
+    #[default]
+    Off,
+    ///Record a [`Severity::Warning`] where the parser started
+    Warn,
+    ///Panic
+    Panic,
+}
+
+//settings and state for the check
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct Misuse {
+    pub(crate) check: MisuseCheck,
+    //where a committed parser last failed
+    pub(crate) committed: Option<usize>,
+}
+
+impl<I: ?Sized + Input> ParserInput<I> {
+    ///Set what to do when a parser fails after consuming input. See [`MisuseCheck`].
+    pub fn set_misuse_check(&mut self, check: MisuseCheck) {
+        self.misuse = Misuse { check, committed: None };
+    }
+
+    ///Get the current misuse check.
+    pub fn misuse_check(&self) -> MisuseCheck {
+        self.misuse.check
+    }
+
+    //reports a parser that failed at `end` after starting at `start`, if it consumed input and
+    //wasn't committed. expects the input to have been rewound to `start` already
+    pub(crate) fn check_misuse(&mut self, start: Checkpoint, end: usize, name: impl FnOnce() -> String) {
+        if !cfg!(debug_assertions) || self.misuse.check == MisuseCheck::Off {
+            return
+        }
+        if end <= start.offset() || self.misuse.committed == Some(end) {
+            return
+        }
+
+        let message = format!("{} failed at offset {end} after consuming input from offset {}", name(), start.offset());
+        match self.misuse.check {
+            MisuseCheck::Off => (),
+            MisuseCheck::Warn => self.emit(Diagnostic { severity: Severity::Warning, message, offset: start.offset() }),
+            MisuseCheck::Panic => panic!("{message}"),
+        }
+    }
+}
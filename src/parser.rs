@@ -1,4 +1,6 @@
-use crate::{combinators::*, ParserString};
+use std::fmt::Display;
+
+use crate::{combinators::*, ParserString, ParserInput, Input, BacktrackLimit, SizeLimits, LimitErr, Span, Diagnostic, Severity, Recovered, Suggestions, grammar::{Grammar, Described}};
 
 use paste::paste;
 
@@ -17,32 +19,168 @@ macro_rules! delegate {
 }
 
 
+///A type-erased error, as produced by [`Parser::erase_err`].
+pub type DynErr = Box<dyn std::error::Error + Send + Sync>;
+
 ///All parsers implement this trait. Any function or closure with the signature 
 ///`Fn(&mut ParserString) -> Result<T, E>` implements Parser.
-pub trait Parser<T>: Sized {
+///
+///`I` is the kind of input parsed, [`str`] by default. Parsers over other kinds of input, like
+///[`ParserBytes`](crate::ParserBytes), take a `&mut ParserInput<I>` instead, and combine the same
+///way.
+pub trait Parser<T, I: ?Sized + Input = str>: Sized {
     ///The error type this parser can return
     type Err;
-    ///Run this parser, using a [`ParserString`].
-    fn parse(&self, s: &mut ParserString) -> Result<T, Self::Err>;
+    ///Run this parser, using a [`ParserInput`].
+    fn parse(&self, s: &mut ParserInput<I>) -> Result<T, Self::Err>;
 
     ///Run this parser without affecting the string on failure. In other words, the string will be
     ///"rewinded" on failure.
-    fn try_parse(&self, s: &mut ParserString) -> Result<T, Self::Err> {
-        let i = s.start();
-        self.parse(s).map_err(|err| {
-            unsafe { s.set_ptr(i) };
-            err
+    fn try_parse(&self, s: &mut ParserInput<I>) -> Result<T, Self::Err> {
+        let cp = s.checkpoint();
+        self.parse(s).inspect_err(|_| {
+            let end = s.start();
+            s.rewind(cp);
+            s.check_misuse(cp, end, || match self.grammar() {
+                Grammar::Named { name, .. } => name,
+                _ => "parser".to_owned(),
+            });
         })
     }
 
+    ///Describe the structure of this parser. See the [`grammar`](crate::grammar) module.
+    ///Returns [`Grammar::Opaque`] unless overridden.
+    fn grammar(&self) -> Grammar {
+        Grammar::Opaque
+    }
+
+    ///Describe this parser in an EBNF-like syntax, built from its [`grammar`](Parser::grammar).
+    ///Named parts are shown by name, and if this parser itself is named, it is shown as a rule
+    ///definition.
+    ///```rust
+    ///# use parsa::{Parser, ParserString};
+    ///# use parsa::builtins::{take, word, BuiltinErr};
+    ///let value = take("true").or(take("false")).map(String::from).convert_err::<BuiltinErr>()
+    ///    .or(word.named("number"));
+    ///let assign = word.convert_err::<BuiltinErr>().named("ident")
+    ///    .after(take("="))
+    ///    .chain(value.after(take(",")).many1())
+    ///    .named("assign");
+    ///
+    ///assert_eq!(assign.describe(), r#"assign = ident "=" (("true" | "false" | number) ",")+"#);
+    ///```
+    fn describe(&self) -> String {
+        match self.grammar() {
+            Grammar::Named { name, inner } if *inner != Grammar::Opaque => format!("{name} = {inner}"),
+            g => g.to_string(),
+        }
+    }
+
+    ///Run this parser and report what would have been accepted where it got stuck, for
+    ///tab-completion or "did you mean" messages. If the parser succeeds and consumes the whole
+    ///string, there is nothing to suggest.
+    ///```rust
+    ///# use parsa::{Parser, ParserString, Expectation};
+    ///# use parsa::builtins::{take, whitespace};
+    ///let cmd = take("git").after(whitespace)
+    ///    .replace(take("commit").or(take("checkout")).or(take("push")));
+    ///
+    ///let mut input = ParserString::from("git pull");
+    ///let suggestions = cmd.suggest(&mut input);
+    ///assert_eq!(suggestions.offset, 4);
+    ///assert_eq!(suggestions.expected, vec![
+    ///    Expectation::Literal("commit".into()),
+    ///    Expectation::Literal("checkout".into()),
+    ///    Expectation::Literal("push".into()),
+    ///]);
+    ///```
+    fn suggest(&self, s: &mut ParserInput<I>) -> Suggestions {
+        let _ = s.take_expected();
+        let res = self.parse(s);
+        let expected = s.take_expected();
+
+        if res.is_ok() && s.is_empty() || expected.items.is_empty() {
+            return Suggestions { offset: s.start(), expected: vec![], rest: s.full.describe(s.start()..s.end, usize::MAX) }
+        }
+        let rest = s.full.describe(expected.offset..s.end, usize::MAX);
+        Suggestions { offset: expected.offset, expected: expected.items, rest }
+    }
+
+    ///Run this parser to completion without ever failing, for tooling that needs the best value it
+    ///can get. Errors are collected rather than returned: those recovered from inside the grammar
+    ///(see [`RecoverWith::report`]), a top-level failure, which is replaced using `fill`, or any
+    ///input left over after a success.
+    ///```rust
+    ///# use parsa::{Parser, ParserString};
+    ///# use parsa::builtins::{int, whitespace};
+    ///let nums = int::<i32, _>.after(whitespace).many1();
+    ///
+    ///let mut input = ParserString::from("1 2 x");
+    ///let res = nums.parse_tolerant(&mut input, |_| vec![]);
+    ///assert_eq!(res.value, vec![1, 2]);
+    ///assert_eq!(res.errors().next().unwrap().to_string(), "error at offset 4: unexpected trailing input");
+    ///
+    ///let mut input = ParserString::from("x");
+    ///let res = nums.parse_tolerant(&mut input, |_| vec![]);
+    ///assert!(res.value.is_empty());
+    ///assert_eq!(res.errors().count(), 1);
+    ///```
+    fn parse_tolerant(&self, s: &mut ParserInput<I>, fill: impl FnOnce(Self::Err) -> T) -> Recovered<T> 
+    where Self::Err: Display
+    {
+        let offset = s.start();
+        let value = match self.parse(s) {
+            Ok(v) if !s.is_empty() => {
+                let offset = s.start();
+                s.emit(Diagnostic { severity: Severity::Error, message: "unexpected trailing input".into(), offset });
+                v
+            },
+            Ok(v) => v,
+            Err(e) => {
+                s.emit(Diagnostic { severity: Severity::Error, message: e.to_string(), offset });
+                fill(e)
+            },
+        };
+        Recovered { value, diagnostics: s.take_diagnostics() }
+    }
+
+    ///Run this parser, failing with a [`LimitErr`] if a limit set on the input stopped it, like
+    ///the [fuel](ParserInput::set_fuel) running out. A parser that is stopped may still return
+    ///[`Ok`] with whatever it had parsed so far, so use this instead of [`Parser::parse`] whenever
+    ///the input has limits. The string is left where the parse stopped.
+    ///```rust
+    ///# use parsa::{Parser, ParserString, LimitErr};
+    ///# use parsa::builtins::take;
+    ///let p = take("a").many();
+    ///
+    ///let mut input = ParserString::from("aaaa");
+    ///input.set_fuel(Some(2));
+    ///assert_eq!(p.parse_limited(&mut input), Err(LimitErr::Fuel { offset: 1 }));
+    ///assert_eq!(input.get(), "aaa");
+    ///
+    ///let mut input = ParserString::from("aaaa");
+    ///input.set_fuel(Some(100));
+    ///assert_eq!(p.parse_limited(&mut input), Ok(vec!["a"; 4]));
+    ///```
+    fn parse_limited(&self, s: &mut ParserInput<I>) -> Result<T, LimitErr<Self::Err>> {
+        let res = self.parse(s);
+        match s.limit_err() {
+            Some(e) => {
+                s.resume();
+                Err(e)
+            },
+            None => res.map_err(LimitErr::Inner),
+        }
+    }
+
     delegate! {
-        [U, P2: Parser<U, Err = E>, E: Into<Self::Err>] 
+        [U, P2: Parser<U, I, Err = E>, E: Into<Self::Err>] 
         Chain<T, U, Self, P2>, 
         (self, other: P2)
     } 
 
     delegate! {
-        [P2: Parser<T, Err = E>, E: Into<Self::Err>]
+        [P2: Parser<T, I, Err = E>, E: Into<Self::Err>]
         Or<T, E, Self, P2>, 
         (self, other: P2)
     }
@@ -57,49 +195,374 @@ pub trait Parser<T>: Sized {
         Many1<T, Self>,
         (self, )
     }
+    delegate! {
+        []
+        Repeated<T, Self>,
+        (self, )
+    }
+    delegate! {
+        [F: Fn(Self::Err) -> T]
+        RecoverWith<T, Self::Err, Self, F>,
+        (self, f: F)
+    }
+    delegate! {
+        [P2: Parser<T, I, Err = E>, E: Into<Self::Err>, F: Fn(Self::Err) -> P2]
+        OrElse<T, Self, F>,
+        (self, f: F)
+    }
+    delegate! {
+        [F: Fn() -> T]
+        InsertMissing<T, Self, F>,
+        (self, f: F)
+    }
+    delegate! {
+        [U, S: Parser<U, I>]
+        SepBy<T, U, Self, S>,
+        (self, sep: S)
+    }
+    delegate! {
+        []
+        MustConsume<T, Self>,
+        (self, )
+    }
+    ///Constructs a [`StackSafe`] combinator. Requires the `stacker` feature.
+    #[cfg(feature = "stacker")]
+    fn stack_safe(self) -> StackSafe<T, Self> {
+        StackSafe::new(self)
+    }
+    delegate! {
+        [U, Q: Parser<U, I>]
+        NotFollowedBy<T, U, Self, Q>,
+        (self, q: Q)
+    }
+    delegate! {
+        [U, Q: Parser<U, I, Err = E>, E: Into<Self::Err>]
+        AndAlso<T, U, Self, Q>,
+        (self, q: Q)
+    }
+    delegate! {
+        []
+        BacktrackLimited<T, Self>,
+        (self, limit: BacktrackLimit)
+    }
+    delegate! {
+        []
+        FuelLimited<T, Self>,
+        (self, fuel: usize)
+    }
+    delegate! {
+        []
+        SizeLimited<T, Self>,
+        (self, limits: SizeLimits)
+    }
+    delegate! {
+        [U, W: Parser<U, I>]
+        Lexeme<T, U, Self, W>,
+        (self, trivia: W)
+    }
+    delegate! {
+        []
+        DidYouMean<T, Self>,
+        (self, )
+    }
+    delegate! {
+        []
+        Committed<T, Self>,
+        (self, )
+    }
+    delegate! {
+        [N: Into<String>]
+        Named<T, Self>,
+        (self, name: N)
+    }
+    delegate! {
+        []
+        WithGrammar<T, Self>,
+        (self, grammar: Grammar)
+    }
+    delegate! {
+        [N, V, L: Parser<N, I>]
+        Tlv<T, N, V, Self, L, I, Self::Err>,
+        (self, len: L)
+    }
 
-    ///Apply a function to the output of this parser on success.
-    fn map<U: 'static>(self, f: impl Fn(T) -> U + 'static) -> impl Parser<U, Err = Self::Err> {
-        move |s: &mut ParserString| {
+    ///Apply a function to the output of this parser on success. The function may borrow from
+    ///its surroundings, like a table built before the grammar.
+    ///```rust
+    ///# use std::collections::HashMap;
+    ///# use parsa::{Parser, ParserString};
+    ///# use parsa::builtins::word;
+    ///let keywords = HashMap::from([("if", 0), ("else", 1)]);
+    ///let keyword = word.map(|w| keywords.get(w.as_str()).copied());
+    ///
+    ///assert_eq!(keyword.parse(&mut ParserString::from("else")), Ok(Some(1)));
+    ///```
+    fn map<U>(self, f: impl Fn(T) -> U) -> impl Parser<U, I, Err = Self::Err> {
+        let grammar = self.grammar();
+        Described::new(move |s: &mut ParserInput<I>| {
             self.parse(s).map(&f)
-        }
+        }, grammar)
+    }
+    ///Converts borrowed output into owned data with [`ToOwned`], like `&str` into [`String`] or
+    ///`&[T]` into [`Vec<T>`]. Useful with [zero-copy builtins](crate::builtins::borrowed) when the
+    ///output needs to outlive the input.
+    ///
+    ///This isn't called `to_owned`, since string literals are parsers too, and it would make
+    ///`"text".to_owned()` ambiguous wherever [`Parser`] is in scope.
+    ///```rust
+    ///# use parsa::{Parser, ParserStr};
+    ///# use parsa::builtins::borrowed::word;
+    ///fn first_word(text: &str) -> Option<String> {
+    ///    word.into_owned().parse(&mut ParserStr::from(text)).ok()
+    ///}
+    ///assert_eq!(first_word("abc 123"), Some("abc".to_owned()));
+    ///```
+    fn into_owned<U>(self) -> impl Parser<U::Owned, I, Err = Self::Err>
+    where
+        T: std::ops::Deref<Target = U>,
+        U: ?Sized + ToOwned,
+    {
+        self.map(|v| v.deref().to_owned())
     }
     ///Apply a function to the [`Err`] output of this parser on failure.
-    fn map_err<E: 'static>(self, f: impl Fn(Self::Err) -> E + 'static) -> impl Parser<T, Err = E> {
-        move |s: &mut ParserString| {
+    fn map_err<E>(self, f: impl Fn(Self::Err) -> E) -> impl Parser<T, I, Err = E> {
+        let grammar = self.grammar();
+        Described::new(move |s: &mut ParserInput<I>| {
             self.parse(s).map_err(&f)
-        }
+        }, grammar)
+    }
+    ///Like [`Parser::map_err`], but the function also receives the [`Span`] this parser
+    ///attempted, from where it started to where the input was left when it failed. Parsers that
+    ///rewind on failure leave an empty span at where they started.
+    ///```rust
+    ///# use parsa::{Parser, ParserString, Span};
+    ///# use parsa::builtins::take;
+    ///#[derive(Debug, PartialEq)]
+    ///struct AppErr { message: String, span: Span }
+    ///let closing = take("let ").replace(take("in"))
+    ///    .map_err_with_span(|e, span| AppErr { message: e.to_string(), span });
+    ///
+    ///let err = closing.parse(&mut ParserString::from("let x")).unwrap_err();
+    ///assert_eq!(err.span, Span::new(0, 4));
+    ///```
+    fn map_err_with_span<E>(self, f: impl Fn(Self::Err, Span) -> E) -> impl Parser<T, I, Err = E> {
+        let grammar = self.grammar();
+        Described::new(move |s: &mut ParserInput<I>| {
+            let start = s.start();
+            self.parse(s).map_err(|e| f(e, Span::new(start, s.start().max(start))))
+        }, grammar)
     }
     ///Applies a function to the output of this parser on success, using [error coercion rules](crate::combinators#error-coercion-rules).
-    fn and_then<U: 'static, E: Into<Self::Err>>(self, f: impl Fn(T) -> Result<U, E> + 'static) -> impl Parser<U, Err = Self::Err> {
-        move |s: &mut ParserString| -> Result<U, Self::Err> {
+    fn and_then<U, E: Into<Self::Err>>(self, f: impl Fn(T) -> Result<U, E>) -> impl Parser<U, I, Err = Self::Err> {
+        let grammar = self.grammar();
+        Described::new(move |s: &mut ParserInput<I>| -> Result<U, Self::Err> {
             match self.parse(s) {
                 Ok(v) => f(v).map_err(Into::into),
                 Err(e) => Err(e),
             }
-        }
+        }, grammar)
+    }
+
+    ///Like [`Parser::and_then`], but the function also receives the [`Span`] this parser consumed,
+    ///so errors produced from the output can point at the text they came from.
+    ///```rust
+    ///# use parsa::{Parser, ParserString, Span};
+    ///# use parsa::builtins::{word, WordErr};
+    ///# #[derive(Debug, PartialEq)]
+    ///enum NumErr { Word, Overflow(Span) }
+    ///# impl From<WordErr> for NumErr { fn from(_: WordErr) -> Self { NumErr::Word } }
+    ///let byte = word.convert_err::<NumErr>()
+    ///    .and_then_spanned(|w, span| w.parse::<u8>().map_err(|_| NumErr::Overflow(span)));
+    ///
+    ///let mut input = ParserString::from("12 300");
+    ///input.take(3);
+    ///assert_eq!(byte.parse(&mut input), Err(NumErr::Overflow(Span::new(3, 6))));
+    ///```
+    fn and_then_spanned<U, E: Into<Self::Err>>(self, f: impl Fn(T, Span) -> Result<U, E>) -> impl Parser<U, I, Err = Self::Err> {
+        let grammar = self.grammar();
+        Described::new(move |s: &mut ParserInput<I>| -> Result<U, Self::Err> {
+            let start = s.start();
+            let v = self.parse(s)?;
+            f(v, Span::new(start, s.start())).map_err(Into::into)
+        }, grammar)
+    }
+
+    ///Like [`Parser::map`], but the function also receives the [`Span`] this parser consumed.
+    ///Useful for building syntax trees that point back at the source.
+    ///```rust
+    ///# use parsa::{Parser, ParserString, Span};
+    ///# use parsa::builtins::{word, whitespace};
+    ///let ident = word.map_with_span(|name, span| (name, span));
+    ///let mut input = ParserString::from("let x");
+    ///let names = ident.after(whitespace).many().parse(&mut input).unwrap();
+    ///assert_eq!(names[1], ("x".to_owned(), Span::new(4, 5)));
+    ///```
+    fn map_with_span<U>(self, f: impl Fn(T, Span) -> U) -> impl Parser<U, I, Err = Self::Err> {
+        let grammar = self.grammar();
+        Described::new(move |s: &mut ParserInput<I>| {
+            let start = s.start();
+            let v = self.parse(s)?;
+            Ok(f(v, Span::new(start, s.start())))
+        }, grammar)
     }
 
     ///Similar to [`Chain`], but only keeps the output of the first parser.
-    fn after<U, P2: Parser<U, Err = E>, E: Into<Self::Err>>(self, other: P2) -> impl Parser<T, Err = Self::Err> {
+    fn after<U, P2: Parser<U, I, Err = E>, E: Into<Self::Err>>(self, other: P2) -> impl Parser<T, I, Err = Self::Err> {
         let p = self.chain(other);
-        move |s: &mut ParserString| {
+        let grammar = p.grammar();
+        Described::new(move |s: &mut ParserInput<I>| {
             p.parse(s).map(|(x, _)| x)
-        }
+        }, grammar)
     }
 
     ///Similar to [`Chain`], but only keeps the output of the second parser.
-    fn replace<U, P2: Parser<U, Err = E>, E: Into<Self::Err>>(self, other: P2) -> impl Parser<U, Err = Self::Err> {
+    fn replace<U, P2: Parser<U, I, Err = E>, E: Into<Self::Err>>(self, other: P2) -> impl Parser<U, I, Err = Self::Err> {
         let p = self.chain(other);
-        move |s: &mut ParserString| {
+        let grammar = p.grammar();
+        Described::new(move |s: &mut ParserInput<I>| {
             p.parse(s).map(|(_, x)| x)
-        }
+        }, grammar)
+    }
+
+    ///Constructs a [`Memoized`](crate::incremental::Memoized) combinator.
+    fn memoized(self, memo: &crate::incremental::Memo<T>) -> crate::incremental::Memoized<'_, T, Self> {
+        crate::incremental::Memoized::new(self, memo)
+    }
+
+    ///Constructs an [`Interned`](crate::intern::Interned) combinator.
+    fn interned(self, interner: &crate::intern::Interner) -> crate::intern::Interned<'_, T, Self>
+    where T: AsRef<str>
+    {
+        crate::intern::Interned::new(self, interner)
     }
 
     ///Explicitly sets the target error type of this parser. Can help with type inference.
-    fn convert_err<E: From<Self::Err> + 'static>(self) -> impl Parser<T, Err = E> {
+    fn convert_err<E: From<Self::Err>>(self) -> impl Parser<T, I, Err = E> {
         self.map_err(|e| e.into())
     }
+
+    ///Boxes the error of this parser into a [`DynErr`]. Any other [`Error`](std::error::Error) type
+    ///coerces into it, so this is useful for prototyping before designing an error type.
+    ///```rust
+    ///# use parsa::{Parser, ParserString};
+    ///# use parsa::builtins::{word, whitespace, take, int};
+    ///let mut input = ParserString::from("val = 123");
+    ///let val = word.erase_err()
+    ///    .after(whitespace)
+    ///    .after(take("=").after(whitespace))
+    ///    .replace(int::<i32, _>)
+    ///    .parse(&mut input)
+    ///    .unwrap();
+    ///assert_eq!(val, 123);
+    ///
+    ///let mut input = ParserString::from("val = abc");
+    ///let err = take("val = ").erase_err().replace(int::<i32, _>).parse(&mut input).unwrap_err();
+    ///assert_eq!(err.to_string(), "error parsing int: invalid digit found in string");
+    ///```
+    fn erase_err(self) -> impl Parser<T, I, Err = DynErr> 
+    where Self::Err: std::error::Error + Send + Sync + 'static
+    {
+        self.map_err(|e| -> DynErr { Box::new(e) })
+    }
+
+    /**
+    Borrows this parser as a parser itself, so it can be used in several places without being
+    moved or rebuilt. Parsers can also be shared by wrapping them in an [`Rc`](std::rc::Rc) or
+    [`Arc`](std::sync::Arc), which are parsers too.
+    ```rust
+    # use parsa::{Parser, ParserString};
+    # use parsa::builtins::{word, whitespace};
+    let item = word.after(whitespace).named("item");
+    let pair = item.by_ref().chain(item.by_ref());
+    let list = item.by_ref().many();
+
+    assert_eq!(pair.parse(&mut ParserString::from("a b")).unwrap(), ("a".into(), "b".into()));
+    assert_eq!(list.parse(&mut ParserString::from("a b c")).unwrap(), ["a", "b", "c"]);
+    ```
+    */
+    fn by_ref(&self) -> ByRef<'_, T, Self> {
+        ByRef::new(self)
+    }
+
+    ///Box this parser as a [`DynParser`], so that parsers of different types can be stored
+    ///together.
+    fn boxed<'a>(self) -> Box<dyn DynParser<T, Self::Err, I> + 'a>
+    where Self: 'a
+    {
+        Box::new(self)
+    }
+}
+
+/**
+The object-safe core of [`Parser`], without any of its builder methods. Every parser implements
+this, so it can be used to store parsers of different types together, like in a dispatch table.
+Boxed and borrowed `DynParser`s are parsers themselves, and can be combined as normal. Its methods
+are named differently from [`Parser`]'s, so that having both in scope is never ambiguous.
+```rust
+# use std::{collections::HashMap, num::ParseIntError};
+# use parsa::{Parser, DynParser, ParserString};
+# use parsa::builtins::{int, whitespace, IntErr};
+let mut commands: HashMap<&str, Box<dyn DynParser<i64, IntErr<ParseIntError>>>> = HashMap::new();
+commands.insert("double", int::<i64, _>.map(|n| n * 2).boxed());
+commands.insert("negate", int::<i64, _>.map(|n| -n).boxed());
+
+let mut input = ParserString::from("negate 5");
+let name = input.take(6).to_owned();
+whitespace(&mut input).unwrap();
+assert_eq!(commands[name.as_str()].parse(&mut input).unwrap(), -5);
+```
+*/
+pub trait DynParser<T, E, I: ?Sized + Input = str> {
+    ///Run this parser. See [`Parser::parse`].
+    fn parse_dyn(&self, s: &mut ParserInput<I>) -> Result<T, E>;
+
+    ///Describe the structure of this parser. See [`Parser::grammar`].
+    fn grammar_dyn(&self) -> Grammar;
+}
+
+impl<T, I: ?Sized + Input, P: Parser<T, I>> DynParser<T, P::Err, I> for P {
+    fn parse_dyn(&self, s: &mut ParserInput<I>) -> Result<T, P::Err> {
+        self.parse(s)
+    }
+
+    fn grammar_dyn(&self) -> Grammar {
+        self.grammar()
+    }
+}
+
+impl<T, E, I: ?Sized + Input> Parser<T, I> for Box<dyn DynParser<T, E, I> + '_> {
+    type Err = E;
+    fn parse(&self, s: &mut ParserInput<I>) -> Result<T, E> {
+        (**self).parse_dyn(s)
+    }
+
+    fn grammar(&self) -> Grammar {
+        (**self).grammar_dyn()
+    }
+}
+
+impl<T, E, I: ?Sized + Input> Parser<T, I> for &dyn DynParser<T, E, I> {
+    type Err = E;
+    fn parse(&self, s: &mut ParserInput<I>) -> Result<T, E> {
+        (**self).parse_dyn(s)
+    }
+
+    fn grammar(&self) -> Grammar {
+        (**self).grammar_dyn()
+    }
+}
+
+///Makes a parser from an [`FnMut`] closure, which can update state as it parses. Plain [`Fn`]
+///closures are parsers already. See [`FromFnMut`].
+pub fn from_fn_mut<T, E, I: ?Sized + Input, F>(f: F) -> FromFnMut<F>
+where F: FnMut(&mut ParserInput<I>) -> Result<T, E>
+{
+    FromFnMut::new(f)
+}
+
+///Tries every alternative and keeps the one that consumed the most. See [`Longest`].
+pub fn longest<T, I: ?Sized + Input, A: Alternatives<T, I>>(alts: A) -> Longest<T, A> {
+    Longest::new(alts)
 }
 
 ///Parse an instance of this type, Similar to [`FromStr`].
@@ -116,9 +579,65 @@ pub trait Parsable: Sized {
     }
 }
 
-impl<T, E, F: Fn(&mut ParserString) -> Result<T, E>> Parser<T> for F {
+///Parse an instance of this type from binary data. The [`ParserBytes`](crate::ParserBytes)
+///counterpart of [`Parsable`].
+///
+///Implemented for single bytes and arrays of other `ParsableBytes` types. Wider numbers have no
+///natural byte order, so read them with a builtin like [`be_u16`](crate::builtins::be_u16). With
+///the `derive` feature, `#[derive(ParsableBytes)]` writes impls like the one below, with attributes
+///for byte order and padding.
+///```rust
+///# use parsa::{Parser, ParserBytes, ParsableBytes};
+///# use parsa::builtins::{be_u16, ReadErr};
+///struct Header { version: u8, magic: [u8; 2], len: u16 }
+///impl ParsableBytes for Header {
+///    type Err = ReadErr;
+///    fn parse(s: &mut ParserBytes) -> Result<Self, Self::Err> {
+///        Ok(Header {
+///            version: u8::parse(s)?,
+///            magic: <[u8; 2]>::parse(s)?,
+///            len: be_u16(s)?,
+///        })
+///    }
+///}
+///
+///let mut input = ParserBytes::from(&[1, b'P', b'K', 0x01, 0x00]);
+///let header = Header::parse(&mut input).unwrap();
+///assert_eq!((header.version, &header.magic, header.len), (1, b"PK", 256));
+///```
+pub trait ParsableBytes: Sized {
+    ///The error type this parser can return
+    type Err;
+    ///Run this parser, using a [`ParserBytes`](crate::ParserBytes).
+    fn parse(s: &mut ParserInput<[u8]>) -> Result<Self, Self::Err>;
+
+    ///Run this parser without affecting the input on failure.
+    fn try_parse(s: &mut ParserInput<[u8]>) -> Result<Self, Self::Err> {
+        Self::parse.try_parse(s)
+    }
+}
+
+impl<T, E, I: ?Sized + Input, F: Fn(&mut ParserInput<I>) -> Result<T, E>> Parser<T, I> for F {
     type Err = E;
-    fn parse(&self, s: &mut ParserString) -> Result<T, Self::Err> {
+    fn parse(&self, s: &mut ParserInput<I>) -> Result<T, Self::Err> {
         self(s)
     }
 }
+
+macro_rules! shared {
+    ($($ptr:ty),*) => { $(
+        impl<T, I: ?Sized + Input, P: Parser<T, I>> Parser<T, I> for $ptr {
+            type Err = P::Err;
+            fn parse(&self, s: &mut ParserInput<I>) -> Result<T, Self::Err> {
+                (**self).parse(s)
+            }
+
+            fn grammar(&self) -> Grammar {
+                (**self).grammar()
+            }
+        }
+    )* };
+}
+//`&P` and `Box<P>` can't be included, as references to and boxes of closures are closures too.
+//Use `Parser::by_ref` instead.
+shared!(std::rc::Rc<P>, std::sync::Arc<P>);
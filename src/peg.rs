@@ -0,0 +1,643 @@
+/*!
+Parsers built at runtime from a PEG grammar, for when the syntax isn't known until the program
+runs, like extraction rules supplied by a user.
+
+A [`Peg`] is compiled from text made of rules, each a name, `=` (or `<-`) and an expression.
+Expressions are built from:
+
+| Syntax | Meaning |
+|---|---|
+| `"abc"` or `'abc'` | Exact text, with `\n`, `\r`, `\t`, `\\`, `\"`, `\'` and `\u{..}` escapes |
+| `[a-z_]`, `[^0-9]` | A character in (or not in) the given ranges |
+| `.` | Any character |
+| `name` | Another rule |
+| `a b` | `a`, then `b` |
+| `a / b` or `a \| b` | `a`, or `b` if `a` fails |
+| `a*`, `a+`, `a?` | `a` any amount of times, at least once, or at most once |
+| `a{n}`, `a{n,}`, `a{n,m}` | `a` exactly `n` times, at least `n` times, or `n` to `m` times |
+| `&a`, `!a` | Succeeds if `a` would (or wouldn't) match here, without consuming anything |
+| `(a)` | Grouping |
+
+Rules may optionally end with `;`, and `#` starts a comment that runs to the end of the line.
+Choice is ordered and repetition is greedy, as usual for PEGs. Left recursive rules are rejected.
+
+Matching a rule produces a [`Node`] for it, holding a node for each rule it matched in turn.
+Rules whose name starts with `_` are silent: they match as usual, but their children are added to
+the parent instead of a node of their own.
+
+Requires the `peg` feature.
+```
+# use parsa::{Parser, ParserString};
+# use parsa::peg::Peg;
+let peg = Peg::new(r#"
+    pair  = key _ws "=" _ws value
+    key   = [a-z_]+
+    value = [0-9]+ / "true" / "false"
+    _ws   = " "*
+"#).unwrap();
+
+let text = "retries = 3";
+let pair = peg.rule("pair").unwrap().parse(&mut ParserString::from(text)).unwrap();
+assert_eq!(pair.children.len(), 2);
+assert_eq!(pair.children[0].text(text), "retries");
+assert_eq!(pair.children[1].rule, "value");
+assert_eq!(pair.children[1].text(text), "3");
+```
+
+Rules report to [inspectors](crate::inspect) by name, and use [fuel](ParserInput::set_fuel) like
+the core combinators, so a hostile grammar or input can be bounded.
+*/
+
+use std::{collections::HashMap, fmt::Display, str::FromStr};
+
+use thiserror::Error;
+
+use crate::{Parser, ParserInput, ParserString, Span, Expectation, grammar::Grammar, inspect::rule};
+
+///A rule matched by a [`Peg`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Node {
+    ///The name of the rule
+    pub rule: String,
+    ///The text the rule matched
+    pub span: Span,
+    ///The rules this one matched, in order, not counting silent ones
+    pub children: Vec<Node>,
+}
+
+impl Node {
+    ///Get the text this node matched, given the text that was parsed.
+    pub fn text<'a>(&self, source: &'a str) -> &'a str {
+        &source[self.span.start..self.span.end]
+    }
+
+    ///Get the children of this node that matched `rule`.
+    pub fn children_named<'a>(&'a self, rule: &'a str) -> impl Iterator<Item = &'a Node> {
+        self.children.iter().filter(move |node| node.rule == rule)
+    }
+
+    fn write_tree(&self, f: &mut std::fmt::Formatter<'_>, depth: usize) -> std::fmt::Result {
+        writeln!(f, "{}{} {}..{}", "  ".repeat(depth), self.rule, self.span.start, self.span.end)?;
+        self.children.iter().try_for_each(|child| child.write_tree(f, depth + 1))
+    }
+}
+
+///Renders this node and everything under it as an indented tree.
+impl Display for Node {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.write_tree(f, 0)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Expr {
+    Literal(String),
+    Class { ranges: Vec<(char, char)>, negated: bool },
+    Any,
+    Rule(usize),
+    Seq(Vec<Expr>),
+    Choice(Vec<Expr>),
+    Repeat { inner: Box<Expr>, min: usize, max: Option<usize> },
+    And(Box<Expr>),
+    Not(Box<Expr>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Def {
+    name: String,
+    body: Option<Expr>,
+}
+
+/**
+A grammar compiled at runtime. See the [module docs](crate::peg).
+*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Peg {
+    rules: Vec<Def>,
+    index: HashMap<String, usize>,
+}
+
+impl Peg {
+    /**
+    Compiles a grammar, checking that every rule it refers to is defined exactly once, and that no
+    rule can call itself without consuming anything.
+    ```
+    # use parsa::peg::{Peg, PegErr};
+    assert_eq!(Peg::new("a = b"), Err(PegErr::Undefined { name: "b".to_owned() }));
+    assert_eq!(Peg::new("a = 'x' a / b\nb = a"), Err(PegErr::LeftRecursive { name: "a".to_owned() }));
+    assert_eq!(Peg::new("a = ('x'"), Err(PegErr::Expected { expected: "`)`", offset: 8 }));
+    ```
+    */
+    pub fn new(source: &str) -> Result<Self, PegErr> {
+        let mut peg = Self { rules: vec![], index: HashMap::new() };
+        let s = &mut ParserString::from(source);
+        blank(s);
+        while !s.is_empty() {
+            let name = ident(s).ok_or_else(|| expected(s, "a rule name"))?;
+            blank(s);
+            match s.get() {
+                rest if rest.starts_with('=') => { s.take(1); },
+                rest if rest.starts_with("<-") => { s.take(2); },
+                _ => return Err(expected(s, "`=`")),
+            }
+            blank(s);
+            let i = peg.lookup(&name);
+            let body = peg.choice(s)?;
+            if peg.rules[i].body.replace(body).is_some() {
+                return Err(PegErr::Duplicate { name })
+            }
+            if s.get().starts_with(';') {
+                s.take(1);
+                blank(s);
+            }
+        }
+
+        if let Some(def) = peg.rules.iter().find(|def| def.body.is_none()) {
+            return Err(PegErr::Undefined { name: def.name.clone() })
+        }
+        peg.check_left_recursion()?;
+        Ok(peg)
+    }
+
+    ///Get a parser for the rule called `name`, if there is one.
+    pub fn rule(&self, name: &str) -> Option<PegRule<'_>> {
+        self.index.get(name).map(|&index| PegRule { peg: self, index })
+    }
+
+    ///Get the names of every rule, in the order they were first mentioned.
+    pub fn rules(&self) -> impl Iterator<Item = &str> {
+        self.rules.iter().map(|def| def.name.as_str())
+    }
+
+    //gets the index of a rule, adding an undefined one if it hasn't been mentioned yet
+    fn lookup(&mut self, name: &str) -> usize {
+        if let Some(&i) = self.index.get(name) {
+            return i
+        }
+        self.rules.push(Def { name: name.to_owned(), body: None });
+        self.index.insert(name.to_owned(), self.rules.len() - 1);
+        self.rules.len() - 1
+    }
+
+    fn choice(&mut self, s: &mut ParserString) -> Result<Expr, PegErr> {
+        let mut alts = vec![self.seq(s)?];
+        while s.get().starts_with(['/', '|']) {
+            s.take(1);
+            blank(s);
+            alts.push(self.seq(s)?);
+        }
+        Ok(if alts.len() == 1 { alts.remove(0) } else { Expr::Choice(alts) })
+    }
+
+    fn seq(&mut self, s: &mut ParserString) -> Result<Expr, PegErr> {
+        let mut items = vec![];
+        //the name of the next rule ends this one
+        while !at_definition(s) {
+            match self.prefix(s)? {
+                Some(item) => items.push(item),
+                None => break,
+            }
+        }
+        match items.len() {
+            0 => Err(expected(s, "an expression")),
+            1 => Ok(items.remove(0)),
+            _ => Ok(Expr::Seq(items)),
+        }
+    }
+
+    fn prefix(&mut self, s: &mut ParserString) -> Result<Option<Expr>, PegErr> {
+        let predicate = s.get().chars().next().filter(|c| matches!(c, '&' | '!'));
+        if predicate.is_some() {
+            s.take(1);
+            blank(s);
+        }
+        let Some(inner) = self.suffix(s)? else {
+            return match predicate {
+                Some(_) => Err(expected(s, "an expression")),
+                None => Ok(None),
+            }
+        };
+        Ok(Some(match predicate {
+            Some('&') => Expr::And(Box::new(inner)),
+            Some(_) => Expr::Not(Box::new(inner)),
+            None => inner,
+        }))
+    }
+
+    fn suffix(&mut self, s: &mut ParserString) -> Result<Option<Expr>, PegErr> {
+        let Some(inner) = self.primary(s)? else { return Ok(None) };
+        let (min, max) = match s.get().chars().next() {
+            Some('*') => (0, None),
+            Some('+') => (1, None),
+            Some('?') => (0, Some(1)),
+            Some('{') => {
+                s.take(1);
+                let min = number(s)?;
+                let max = match s.get().chars().next() {
+                    Some('}') => Some(min),
+                    Some(',') if s.get()[1..].starts_with('}') => { s.take(1); None },
+                    Some(',') => { s.take(1); Some(number(s)?) },
+                    _ => return Err(expected(s, "`,` or `}`")),
+                };
+                if !s.get().starts_with('}') || max.is_some_and(|max| max < min) {
+                    return Err(expected(s, "`}`"))
+                }
+                (min, max)
+            },
+            _ => return Ok(Some(inner)),
+        };
+        s.take(1);
+        blank(s);
+        Ok(Some(Expr::Repeat { inner: Box::new(inner), min, max }))
+    }
+
+    fn primary(&mut self, s: &mut ParserString) -> Result<Option<Expr>, PegErr> {
+        let expr = match s.get().chars().next() {
+            Some(quote @ ('"' | '\'')) => {
+                s.take(1);
+                let mut lit = String::new();
+                loop {
+                    match s.get().chars().next() {
+                        Some(c) if c == quote => break,
+                        Some(_) => lit.push(escaped(s)?),
+                        None => return Err(expected(s, "a closing quote")),
+                    }
+                }
+                s.take(1);
+                Expr::Literal(lit)
+            },
+            Some('[') => {
+                s.take(1);
+                let negated = s.get().starts_with('^');
+                if negated { s.take(1); }
+                let mut ranges = vec![];
+                while !s.get().starts_with(']') {
+                    if s.is_empty() {
+                        return Err(expected(s, "`]`"))
+                    }
+                    let lo = escaped(s)?;
+                    let hi = match s.get().starts_with('-') && !s.get()[1..].starts_with(']') {
+                        true => { s.take(1); escaped(s)? },
+                        false => lo,
+                    };
+                    ranges.push((lo, hi));
+                }
+                s.take(1);
+                Expr::Class { ranges, negated }
+            },
+            Some('.') => {
+                s.take(1);
+                Expr::Any
+            },
+            Some('(') => {
+                s.take(1);
+                blank(s);
+                let inner = self.choice(s)?;
+                if s.try_take(1) != Some(")") {
+                    return Err(expected(s, "`)`"))
+                }
+                inner
+            },
+            _ => match ident(s) {
+                Some(name) => Expr::Rule(self.lookup(&name)),
+                None => return Ok(None),
+            },
+        };
+        blank(s);
+        Ok(Some(expr))
+    }
+
+    fn check_left_recursion(&self) -> Result<(), PegErr> {
+        //which rules can match without consuming anything
+        let mut nullable = vec![false; self.rules.len()];
+        loop {
+            let mut changed = false;
+            for (i, def) in self.rules.iter().enumerate() {
+                if !nullable[i] && def.body.as_ref().is_some_and(|body| body.nullable(&nullable)) {
+                    nullable[i] = true;
+                    changed = true;
+                }
+            }
+            if !changed { break }
+        }
+
+        //which rules each rule can call before consuming anything
+        let calls: Vec<Vec<usize>> = self.rules.iter().map(|def| {
+            let mut out = vec![];
+            if let Some(body) = &def.body { body.left_calls(&nullable, &mut out) }
+            out
+        }).collect();
+
+        //0 is unvisited, 1 is on the current path, 2 is done
+        fn visit(i: usize, calls: &[Vec<usize>], state: &mut [u8]) -> Option<usize> {
+            state[i] = 1;
+            for &next in &calls[i] {
+                match state[next] {
+                    1 => return Some(next),
+                    0 => if let Some(found) = visit(next, calls, state) { return Some(found) },
+                    _ => {},
+                }
+            }
+            state[i] = 2;
+            None
+        }
+        let mut state = vec![0; self.rules.len()];
+        for i in 0..self.rules.len() {
+            if state[i] == 0 {
+                if let Some(found) = visit(i, &calls, &mut state) {
+                    return Err(PegErr::LeftRecursive { name: self.rules[found].name.clone() })
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Peg {
+    type Err = PegErr;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::new(s)
+    }
+}
+
+impl Expr {
+    fn nullable(&self, rules: &[bool]) -> bool {
+        match self {
+            Self::Literal(lit) => lit.is_empty(),
+            Self::Class { .. } | Self::Any => false,
+            Self::Rule(i) => rules[*i],
+            Self::Seq(items) => items.iter().all(|e| e.nullable(rules)),
+            Self::Choice(alts) => alts.iter().any(|e| e.nullable(rules)),
+            Self::Repeat { inner, min, .. } => *min == 0 || inner.nullable(rules),
+            Self::And(_) | Self::Not(_) => true,
+        }
+    }
+
+    fn left_calls(&self, rules: &[bool], out: &mut Vec<usize>) {
+        match self {
+            Self::Rule(i) => out.push(*i),
+            Self::Seq(items) => for item in items {
+                item.left_calls(rules, out);
+                if !item.nullable(rules) { break }
+            },
+            Self::Choice(alts) => alts.iter().for_each(|e| e.left_calls(rules, out)),
+            Self::Repeat { inner, .. } | Self::And(inner) | Self::Not(inner) => inner.left_calls(rules, out),
+            Self::Literal(_) | Self::Class { .. } | Self::Any => {},
+        }
+    }
+
+    fn grammar(&self, peg: &Peg) -> Grammar {
+        match self {
+            Self::Literal(lit) => Grammar::Literal(lit.clone()),
+            Self::Rule(i) => Grammar::named(&peg.rules[*i].name, Grammar::Opaque),
+            Self::Seq(items) => Grammar::Seq(items.iter().map(|e| e.grammar(peg)).collect()),
+            Self::Choice(alts) => Grammar::Alt(alts.iter().map(|e| e.grammar(peg)).collect()),
+            Self::Repeat { inner, min, max } => Grammar::repeat(inner.grammar(peg), *min, *max),
+            Self::Class { .. } | Self::Any | Self::And(_) | Self::Not(_) => Grammar::Opaque,
+        }
+    }
+}
+
+fn expected(s: &ParserString, expected: &'static str) -> PegErr {
+    PegErr::Expected { expected, offset: s.start() }
+}
+
+//checks if the next rule starts here, without consuming anything
+fn at_definition(s: &mut ParserString) -> bool {
+    let cp = s.checkpoint();
+    let found = ident(s).is_some() && {
+        blank(s);
+        s.get().starts_with('=') || s.get().starts_with("<-")
+    };
+    s.rewind(cp);
+    found
+}
+
+//skips whitespace and comments
+fn blank(s: &mut ParserString) {
+    loop {
+        let n = s.get().len() - s.get().trim_start().len();
+        s.take(s.get()[..n].chars().count());
+        if !s.get().starts_with('#') { break }
+        let line = s.get().find('\n').unwrap_or(s.len());
+        s.take(s.get()[..line].chars().count());
+    }
+}
+
+fn ident(s: &mut ParserString) -> Option<String> {
+    let len = s.get().find(|c: char| !(c.is_alphanumeric() || c == '_')).unwrap_or(s.len());
+    if len == 0 || s.get().starts_with(|c: char| c.is_ascii_digit()) {
+        return None
+    }
+    Some(s.take(s.get()[..len].chars().count()).to_owned())
+}
+
+fn number(s: &mut ParserString) -> Result<usize, PegErr> {
+    let len = s.get().find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let n = s.get()[..len].parse().map_err(|_| expected(s, "a number"))?;
+    s.take(len);
+    Ok(n)
+}
+
+//reads a character of a literal or class, resolving escapes
+fn escaped(s: &mut ParserString) -> Result<char, PegErr> {
+    let c = s.get().chars().next().ok_or_else(|| expected(s, "a character"))?;
+    s.take(1);
+    if c != '\\' {
+        return Ok(c)
+    }
+    let escape = s.get().chars().next().ok_or_else(|| expected(s, "an escape"))?;
+    s.take(1);
+    Ok(match escape {
+        'n' => '\n',
+        'r' => '\r',
+        't' => '\t',
+        'u' => {
+            let end = s.get().find('}').filter(|_| s.get().starts_with('{')).ok_or_else(|| expected(s, "`{..}`"))?;
+            let code = u32::from_str_radix(&s.get()[1..end], 16).ok().and_then(char::from_u32);
+            let c = code.ok_or_else(|| expected(s, "a unicode scalar value"))?;
+            s.take(s.get()[..=end].chars().count());
+            c
+        },
+        c => c,
+    })
+}
+
+/**
+Matches a single rule of a [`Peg`], producing a [`Node`]. Created with [`Peg::rule`]. The rule's
+node is always produced, even if it is silent.
+```
+# use parsa::{Parser, ParserString};
+# use parsa::peg::{Peg, MatchErr};
+let peg = Peg::new("list = '[' item (',' item)* ']'\nitem = [0-9]+").unwrap();
+let list = peg.rule("list").unwrap();
+
+let node = list.parse(&mut ParserString::from("[1,22,3]")).unwrap();
+assert_eq!(node.to_string(), "list 0..8\n  item 1..2\n  item 3..5\n  item 6..7\n");
+
+let err = list.parse(&mut ParserString::from("[1,22,]")).unwrap_err();
+assert_eq!(err, MatchErr { rule: "list".to_owned(), offset: 6 });
+```
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct PegRule<'a> {
+    peg: &'a Peg,
+    index: usize,
+}
+
+impl PegRule<'_> {
+    ///The name of this rule.
+    pub fn name(&self) -> &str {
+        &self.peg.rules[self.index].name
+    }
+}
+
+impl Parser<Node> for PegRule<'_> {
+    type Err = MatchErr;
+
+    fn parse(&self, s: &mut ParserInput<str>) -> Result<Node, Self::Err> {
+        let mut run = Run { peg: self.peg, furthest: s.start() };
+        let mut out = vec![];
+        match run.call(self.index, true, s, &mut out) {
+            true => Ok(out.remove(0)),
+            false => Err(MatchErr { rule: self.name().to_owned(), offset: run.furthest }),
+        }
+    }
+
+    fn grammar(&self) -> Grammar {
+        let def = &self.peg.rules[self.index];
+        Grammar::named(&def.name, def.body.as_ref().map_or(Grammar::Opaque, |body| body.grammar(self.peg)))
+    }
+}
+
+//the state of a single match
+struct Run<'a> {
+    peg: &'a Peg,
+    furthest: usize,
+}
+
+impl Run<'_> {
+    fn fail(&mut self, s: &mut ParserString, expectation: Expectation) -> bool {
+        self.furthest = self.furthest.max(s.start());
+        s.expect(s.start(), expectation);
+        false
+    }
+
+    fn call(&mut self, i: usize, keep: bool, s: &mut ParserString, out: &mut Vec<Node>) -> bool {
+        let peg = self.peg;
+        let def = &peg.rules[i];
+        let Some(body) = &def.body else { return false };
+        let mut call = |s: &mut ParserString| rule(s, &def.name, |s| {
+            let start = s.start();
+            let mut children = vec![];
+            if !self.attempt(body, s, &mut children) {
+                return Err(())
+            }
+            match keep || !def.name.starts_with('_') {
+                true => out.push(Node { rule: def.name.clone(), span: Span::new(start, s.start()), children }),
+                false => out.extend(children),
+            }
+            Ok(())
+        }).is_ok();
+        #[cfg(feature = "stacker")]
+        return crate::combinators::stack_safe(|| call(s));
+        #[cfg(not(feature = "stacker"))]
+        call(s)
+    }
+
+    //matches `e`, restoring the input and output if it fails
+    fn attempt(&mut self, e: &Expr, s: &mut ParserString, out: &mut Vec<Node>) -> bool {
+        let cp = s.checkpoint();
+        let len = out.len();
+        let ok = self.eval(e, s, out);
+        if !ok {
+            s.rewind(cp);
+            out.truncate(len);
+        }
+        ok
+    }
+
+    fn eval(&mut self, e: &Expr, s: &mut ParserString, out: &mut Vec<Node>) -> bool {
+        match e {
+            Expr::Literal(lit) => match s.get().starts_with(lit.as_str()) {
+                true => { s.take(lit.chars().count()); true },
+                false => self.fail(s, Expectation::Literal(lit.clone())),
+            },
+            Expr::Class { ranges, negated } => {
+                let next = s.get().chars().next();
+                match next.is_some_and(|c| ranges.iter().any(|&(lo, hi)| (lo..=hi).contains(&c)) != *negated) {
+                    true => { s.take(1); true },
+                    false => self.fail(s, Expectation::Named("a character in a class".to_owned())),
+                }
+            },
+            Expr::Any => match s.get().is_empty() {
+                false => { s.take(1); true },
+                true => self.fail(s, Expectation::Named("any character".to_owned())),
+            },
+            Expr::Rule(i) => self.call(*i, false, s, out),
+            Expr::Seq(items) => items.iter().all(|item| self.attempt(item, s, out)),
+            Expr::Choice(alts) => alts.iter().any(|alt| self.attempt(alt, s, out)),
+            Expr::Repeat { inner, min, max } => {
+                let mut found = 0;
+                while max.is_none_or(|max| found < max) && s.allow_item(found) {
+                    let start = s.start();
+                    if !self.attempt(inner, s, out) { break }
+                    found += 1;
+                    //matching nothing would match nothing forever
+                    if s.start() == start { break }
+                }
+                found >= *min
+            },
+            Expr::And(inner) | Expr::Not(inner) => {
+                let cp = s.checkpoint();
+                let furthest = self.furthest;
+                let matched = self.attempt(inner, s, &mut vec![]);
+                s.rewind(cp);
+                self.furthest = furthest;
+                matched == matches!(e, Expr::And(_))
+            },
+        }
+    }
+}
+
+///Indicates that a [`Peg`] couldn't be compiled.
+#[derive(Debug, Clone, Error, PartialEq, Eq)]
+pub enum PegErr {
+    ///The grammar is malformed
+    #[error("expected {expected} at offset {offset}")]
+    Expected {
+        ///What was expected
+        expected: &'static str,
+        ///Where it was expected
+        offset: usize,
+    },
+    ///A rule is defined more than once
+    #[error("rule `{name}` is defined more than once")]
+    Duplicate {
+        ///The name of the rule
+        name: String,
+    },
+    ///A rule is used, but never defined
+    #[error("rule `{name}` is not defined")]
+    Undefined {
+        ///The name of the rule
+        name: String,
+    },
+    ///A rule can call itself without consuming anything, which would never end
+    #[error("rule `{name}` is left recursive")]
+    LeftRecursive {
+        ///The name of the rule
+        name: String,
+    },
+}
+
+///Indicates that a [`PegRule`] failed to match.
+#[derive(Debug, Clone, Error, PartialEq, Eq)]
+#[error("rule `{rule}` failed to match, furthest at offset {offset}")]
+pub struct MatchErr {
+    ///The name of the rule
+    pub rule: String,
+    ///The furthest offset the match got to before failing
+    pub offset: usize,
+}
@@ -0,0 +1,259 @@
+/*!
+Parsing records from readers too large to load at once.
+
+[`Records`] reads its input in chunks, splits it into records at a boundary, like `"\n"`, and
+parses each one separately, yielding the results as an iterator. A record that straddles two
+chunks is carried over until its boundary is read, so memory use depends on the size of the
+largest record rather than of the input.
+
+```
+# use parsa::{Parser, ParserString};
+# use parsa::builtins::{word, whitespace, int, BuiltinErr};
+# use parsa::stream::Records;
+let data = "alice 30\nbob 25\ncarol x\n";
+let person = word.convert_err::<BuiltinErr>().after(whitespace).chain(int::<u32, _>);
+
+let records: Vec<_> = Records::new(data.as_bytes(), person, "\n").chunk_size(4).collect();
+assert_eq!(records.len(), 3);
+assert_eq!(records[1].as_ref().unwrap(), &("bob".to_owned(), 25));
+let err = records[2].as_ref().unwrap_err();
+assert_eq!(err.to_string(), "record 2 at byte 16: error parsing int: invalid digit found in string");
+```
+
+With the `rayon` feature, `par_records` parses records of text that is already in memory across
+a thread pool.
+*/
+
+use std::{fmt::{Debug, Display}, fs::File, io::{BufReader, Read}, marker::PhantomData, path::Path};
+
+use crate::{Parser, ParserString};
+
+///An error from a [`Records`] iterator.
+#[derive(Debug)]
+pub enum RecordErr<E> {
+    ///Reading failed. The iterator ends after this.
+    Io(std::io::Error),
+    ///A record wasn't valid UTF-8
+    Utf8 {
+        ///The index of the record
+        record: usize,
+        ///The byte offset the record starts at
+        offset: usize,
+    },
+    ///The parser failed, or didn't consume the whole record
+    Parse {
+        ///The parser's error, or [`None`] if it left input over
+        err: Option<E>,
+        ///The index of the record
+        record: usize,
+        ///The byte offset the record starts at
+        offset: usize,
+    },
+}
+
+impl<E: Display> Display for RecordErr<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "{e}"),
+            Self::Utf8 { record, offset } => write!(f, "record {record} at byte {offset}: invalid UTF-8"),
+            Self::Parse { err: Some(err), record, offset } => write!(f, "record {record} at byte {offset}: {err}"),
+            Self::Parse { err: None, record, offset } => write!(f, "record {record} at byte {offset}: unexpected trailing input"),
+        }
+    }
+}
+impl<E: Debug + Display> std::error::Error for RecordErr<E> {}
+
+/**
+An iterator of records parsed from a reader. See the [module docs](crate::stream).
+
+Each record is the text before the next occurrence of the boundary, which isn't included. The
+last record doesn't need a boundary after it, and empty input yields nothing. The parser must
+consume its whole record.
+*/
+pub struct Records<R, T, P> {
+    reader: R,
+    p: P,
+    boundary: Vec<u8>,
+    chunk_size: usize,
+    buf: Vec<u8>,
+    //where the next record starts in `buf`
+    pos: usize,
+    //how far past `pos` has been searched for a boundary
+    scanned: usize,
+    //the byte offset of `buf[0]`
+    consumed: usize,
+    record: usize,
+    eof: bool,
+    t: PhantomData<T>,
+}
+
+impl<R: Read, T, P: Parser<T>> Records<R, T, P> {
+    ///Constructs an iterator of records in `reader`, parsed with `p`, and separated by
+    ///`boundary`. Panics if `boundary` is empty.
+    pub fn new(reader: R, p: P, boundary: &str) -> Self {
+        assert!(!boundary.is_empty(), "record boundary can't be empty");
+        Self {
+            reader, p, boundary: boundary.as_bytes().to_vec(), chunk_size: 64 * 1024,
+            buf: vec![], pos: 0, scanned: 0, consumed: 0, record: 0, eof: false, t: PhantomData,
+        }
+    }
+
+    ///Sets how many bytes are read at a time, 64 KiB by default. Panics if `size` is 0.
+    pub fn chunk_size(mut self, size: usize) -> Self {
+        assert!(size > 0, "chunk size can't be 0");
+        self.chunk_size = size;
+        self
+    }
+
+    //the length of the next complete record in `buf`, and of its boundary. only searches what
+    //wasn't searched before, so a record spanning many chunks is searched once
+    fn next_record(&mut self) -> Option<(usize, usize)> {
+        let (rest, n) = (&self.buf[self.pos..], self.boundary.len());
+        let found = rest[self.scanned..].windows(n).position(|w| w == self.boundary);
+        let len = rest.len();
+        match found {
+            Some(at) => {
+                let len = self.scanned + at;
+                self.scanned = 0;
+                Some((len, n))
+            },
+            None if self.eof && len > 0 => {
+                self.scanned = 0;
+                Some((len, 0))
+            },
+            None => {
+                //a boundary could still start in the last `n - 1` bytes
+                self.scanned = (len + 1).saturating_sub(n);
+                None
+            },
+        }
+    }
+
+    //reads another chunk, dropping records that were already parsed
+    fn fill(&mut self) -> std::io::Result<()> {
+        self.buf.drain(..self.pos);
+        self.consumed += self.pos;
+        self.pos = 0;
+
+        let len = self.buf.len();
+        self.buf.resize(len + self.chunk_size, 0);
+        let read = loop {
+            match self.reader.read(&mut self.buf[len..]) {
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+                res => break res,
+            }
+        };
+        self.buf.truncate(len + *read.as_ref().unwrap_or(&0));
+        self.eof = matches!(read, Ok(0));
+        read.map(|_| ())
+    }
+}
+
+impl<T, P: Parser<T>> Records<BufReader<File>, T, P> {
+    ///Opens a file and constructs an iterator of its records. See [`Records::new`].
+    pub fn open(path: impl AsRef<Path>, p: P, boundary: &str) -> std::io::Result<Self> {
+        Ok(Self::new(BufReader::new(File::open(path)?), p, boundary))
+    }
+}
+
+impl<R: Read, T, P: Parser<T>> Iterator for Records<R, T, P> {
+    type Item = Result<T, RecordErr<P::Err>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (len, boundary) = loop {
+            if let Some(found) = self.next_record() {
+                break found
+            }
+            if self.eof {
+                return None
+            }
+            if let Err(e) = self.fill() {
+                self.eof = true;
+                self.buf.clear();
+                self.pos = 0;
+                self.scanned = 0;
+                return Some(Err(RecordErr::Io(e)))
+            }
+        };
+
+        let record = self.record;
+        let offset = self.consumed + self.pos;
+        let text = std::str::from_utf8(&self.buf[self.pos..self.pos + len]);
+        self.pos += len + boundary;
+        self.record += 1;
+
+        let Ok(text) = text else { return Some(Err(RecordErr::Utf8 { record, offset })) };
+        let mut s = ParserString::from(text);
+        Some(match self.p.parse(&mut s) {
+            Ok(_) if !s.is_empty() => Err(RecordErr::Parse { err: None, record, offset }),
+            Ok(v) => Ok(v),
+            Err(e) => Err(RecordErr::Parse { err: Some(e), record, offset }),
+        })
+    }
+}
+
+impl<R, T, P: Debug> Debug for Records<R, T, P> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Records")
+            .field("p", &self.p)
+            .field("record", &self.record)
+            .field("offset", &(self.consumed + self.pos))
+            .finish_non_exhaustive()
+    }
+}
+
+//splits `input` at `boundary`, with the byte offset of each record
+#[cfg(feature = "rayon")]
+fn split_records<'a>(input: &'a str, boundary: &str) -> Vec<(usize, &'a str)> {
+    let mut records = vec![];
+    let mut start = 0;
+    for (at, _) in input.match_indices(boundary) {
+        records.push((start, &input[start..at]));
+        start = at + boundary.len();
+    }
+    if start < input.len() {
+        records.push((start, &input[start..]));
+    }
+    records
+}
+
+/**
+Splits `input` into records at `boundary`, like [`Records`], and parses them in parallel on the
+[rayon](https://docs.rs/rayon) thread pool. Results are returned in input order, with an error
+for each record that failed.
+
+Requires the `rayon` feature.
+```
+# use parsa::{Parser, ParserString};
+# use parsa::builtins::int;
+# use parsa::stream::{par_records, RecordErr};
+let log = "200\n404\nerr\n500\n";
+let codes = par_records(log, "\n", &int::<u16, _>);
+assert_eq!(codes.len(), 4);
+assert_eq!(codes[1].as_ref().ok(), Some(&404));
+assert!(matches!(codes[2], Err(RecordErr::Parse { record: 2, offset: 8, .. })));
+```
+*/
+#[cfg(feature = "rayon")]
+pub fn par_records<T, P>(input: &str, boundary: &str, p: &P) -> Vec<Result<T, RecordErr<P::Err>>>
+where
+    T: Send,
+    P: Parser<T> + Sync,
+    P::Err: Send,
+{
+    use rayon::prelude::*;
+
+    assert!(!boundary.is_empty(), "record boundary can't be empty");
+    split_records(input, boundary)
+        .into_par_iter()
+        .enumerate()
+        .map(|(record, (offset, text))| {
+            let mut s = ParserString::from(text);
+            match p.parse(&mut s) {
+                Ok(_) if !s.is_empty() => Err(RecordErr::Parse { err: None, record, offset }),
+                Ok(v) => Ok(v),
+                Err(e) => Err(RecordErr::Parse { err: Some(e), record, offset }),
+            }
+        })
+        .collect()
+}
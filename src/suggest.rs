@@ -0,0 +1,136 @@
+use std::fmt::Display;
+
+use crate::{ParserInput, Input};
+
+///Something a parser expected to find, recorded when it failed. See [`Parser::suggest`](crate::Parser::suggest).
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Expectation {
+    ///Exact text, as from [`take`](crate::builtins::take)
+    Literal(String),
+    ///A class of token, from a [`Named`](crate::combinators::Named) parser
+    Named(String),
+}
+
+impl Display for Expectation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Literal(lit) => write!(f, "{lit:?}"),
+            Self::Named(name) => write!(f, "{name}"),
+        }
+    }
+}
+
+///What was expected at the furthest point a parse failed.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Expected {
+    ///Where the expectations were recorded, relative to the "true" start
+    pub offset: usize,
+    ///What would have been accepted there, in the order they were tried
+    pub items: Vec<Expectation>,
+}
+
+impl<I: ?Sized + Input> ParserInput<I> {
+    ///Records that `expectation` would have been accepted at `offset`. Only the expectations
+    ///at the furthest offset are kept, and they survive rewinding.
+    pub fn expect(&mut self, offset: usize, expectation: Expectation) {
+        if offset > self.expected.offset || self.expected.items.is_empty() {
+            self.expected.offset = offset;
+            self.expected.items.clear();
+        } else if offset < self.expected.offset {
+            return
+        }
+        if !self.expected.items.contains(&expectation) {
+            self.expected.items.push(expectation);
+        }
+    }
+
+    ///Get the expectations recorded at the furthest failure so far.
+    pub fn expected(&self) -> &Expected {
+        &self.expected
+    }
+
+    ///Remove and return the expectations recorded so far.
+    pub fn take_expected(&mut self) -> Expected {
+        std::mem::take(&mut self.expected)
+    }
+}
+
+///The result of [`Parser::suggest`](crate::Parser::suggest).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Suggestions {
+    ///Where the parse got stuck, relative to the "true" start
+    pub offset: usize,
+    ///What would have been accepted there
+    pub expected: Vec<Expectation>,
+    ///The text from `offset` to the end of the string
+    pub rest: String,
+}
+
+impl Suggestions {
+    ///Literals that could complete the text at [`Suggestions::offset`]: those that start with what
+    ///remains of the string.
+    ///```rust
+    ///# use parsa::{Parser, ParserString};
+    ///# use parsa::builtins::take;
+    ///let cmd = take("commit").or(take("checkout")).or(take("push"));
+    ///let mut input = ParserString::from("co");
+    ///assert_eq!(cmd.suggest(&mut input).completions(), vec!["commit"]);
+    ///```
+    pub fn completions(&self) -> Vec<&str> {
+        self.expected.iter()
+            .filter_map(|e| match e {
+                Expectation::Literal(lit) if lit.starts_with(&self.rest) => Some(lit.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+impl Suggestions {
+    /**
+    The literal closest to the word at [`Suggestions::offset`] by edit distance, if any is close
+    enough to likely be a typo of it: within a third of its length, and at least one edit.
+    ```rust
+    # use parsa::{Parser, ParserString};
+    # use parsa::builtins::take;
+    let cmd = take("commit").or(take("checkout")).or(take("push"));
+    let mut input = ParserString::from("comit -m");
+    assert_eq!(cmd.suggest(&mut input).closest(), Some("commit"));
+
+    let mut input = ParserString::from("merge");
+    assert_eq!(cmd.suggest(&mut input).closest(), None);
+    ```
+    */
+    pub fn closest(&self) -> Option<&str> {
+        closest(&self.expected, &self.rest)
+    }
+}
+
+//the literal in `expected` closest to the first word of `rest`, if it's close enough
+pub(crate) fn closest<'a>(expected: &'a [Expectation], rest: &str) -> Option<&'a str> {
+    let word = rest.split(char::is_whitespace).next().unwrap_or_default();
+    expected.iter()
+        .filter_map(|e| match e {
+            Expectation::Literal(lit) => Some((edit_distance(lit, word), lit.as_str())),
+            _ => None,
+        })
+        .filter(|&(d, lit)| d <= (lit.chars().count() / 3).max(1))
+        .min_by_key(|&(d, _)| d)
+        .map(|(_, lit)| lit)
+}
+
+//levenshtein distance, in characters
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diag = row[0];
+        row[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let next = (diag + usize::from(ca != cb)).min(row[j] + 1).min(row[j + 1] + 1);
+            diag = row[j + 1];
+            row[j + 1] = next;
+        }
+    }
+    row[b.len()]
+}
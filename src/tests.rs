@@ -71,3 +71,422 @@ fn weird_many_bug() {
         .many().parse(&mut input).unwrap();
     assert_eq!(vec, vec!["abc", "123"]);
 }
+
+#[test]
+fn url_parts() {
+    use crate::builtins::{url, Url, Authority, UrlErr};
+
+    let mut input = ParserString::from("file:///etc/hosts");
+    assert_eq!(url(&mut input), Ok(Url {
+        scheme: "file".to_owned(),
+        authority: Some(Authority { userinfo: None, host: String::new(), port: None }),
+        path: "/etc/hosts".to_owned(),
+        query: None,
+        fragment: None,
+    }));
+
+    let mut input = ParserString::from("http://[::1]:80");
+    let authority = url(&mut input).unwrap().authority.unwrap();
+    assert_eq!(authority.host, "[::1]");
+    assert_eq!(authority.port, Some(80));
+
+    let mut input = ParserString::from("http://host:99999/");
+    assert_eq!(url(&mut input), Err(UrlErr::InvalidPort));
+    assert_eq!(input.get(), "http://host:99999/");
+
+    let mut input = ParserString::from("1http://x");
+    assert_eq!(url(&mut input), Err(UrlErr::NoScheme));
+}
+
+#[test]
+fn take_errors() {
+    let mut input = ParserString::from("∈🌏 rest");
+    assert_eq!(take("∈🌏").parse(&mut input), Ok("∈🌏"));
+
+    let err = take("rest").parse(&mut input).unwrap_err();
+    assert_eq!(err, TakeErr::NoMatch { expected: "rest", found: " res".to_owned() });
+
+    let err = take("long").parse(&mut input).unwrap_err();
+    assert_eq!(err, TakeErr::NoSpace { expected: "long", found: "t".to_owned() });
+    assert_eq!(input.get(), "t");
+
+    let long = "x".repeat(10_000);
+    let err = take("ab").parse(&mut ParserString::from(long.as_str())).unwrap_err();
+    assert_eq!(err.found(), "xx");
+}
+
+#[test]
+fn many_stops_without_progress() {
+    let mut input = ParserString::from("abc");
+    let out = whitespace.many().parse(&mut input).unwrap();
+    assert!(out.is_empty());
+    assert_eq!(input.diagnostics().len(), 1);
+    assert_eq!(input.get(), "abc");
+}
+
+#[test]
+fn memo_edits() {
+    use crate::incremental::{Memo, Edit};
+
+    let mut memo = Memo::new();
+    let parse = |memo: &Memo<String>, text: &str| {
+        word.memoized(memo).after(whitespace).many().parse(&mut ParserString::from(text)).unwrap()
+    };
+    assert_eq!(parse(&memo, "ab cd ef"), vec!["ab", "cd", "ef"]);
+
+    //inserting directly after a word invalidates it, since it could have extended it
+    let edit = Edit::new(2, 0, 1);
+    let text = edit.apply("ab cd ef", "x");
+    memo.apply_edit(edit);
+    assert_eq!(memo.len(), 2);
+    assert_eq!(parse(&memo, &text), vec!["abx", "cd", "ef"]);
+
+    //inserting directly before a word invalidates it too
+    let edit = Edit::new(4, 0, 1);
+    let text = edit.apply(&text, "y");
+    memo.apply_edit(edit);
+    assert_eq!(memo.len(), 2);
+    assert_eq!(parse(&memo, &text), vec!["abx", "ycd", "ef"]);
+}
+
+#[test]
+fn whitespace_before_last_char() {
+    let mut input = ParserString::from(" x");
+    assert_eq!(whitespace(&mut input), Ok(1));
+    assert_eq!(input.get(), "x");
+
+    let mut input = ParserString::from("  éa");
+    assert_eq!(whitespace(&mut input), Ok(2));
+    assert_eq!(input.get(), "éa");
+}
+
+#[test]
+fn word_before_multibyte_whitespace() {
+    let mut input = ParserString::from("ab\u{3000}cd");
+    assert!(word(&mut input).is_ok_and(|w| w == "ab"));
+    assert_eq!(input.get(), "\u{3000}cd");
+}
+
+#[test]
+fn byte_combinators() {
+    use crate::ParserBytes;
+
+    let byte = |want: u8| move |s: &mut ParserBytes| match s.get().first() {
+        Some(&b) if b == want => { s.take(1); Ok(b) },
+        _ => Err(()),
+    };
+    let p = byte(1).or(byte(2)).many1().chain(byte(0).named("end"));
+
+    let mut input = ParserBytes::from(&[1, 2, 1, 0, 9]);
+    assert_eq!(p.parse(&mut input), Ok((vec![1, 2, 1], 0)));
+    assert_eq!(input.get(), &[9]);
+
+    let mut input = ParserBytes::from(&[1, 3]);
+    assert_eq!(p.parse(&mut input), Err(()));
+    assert_eq!(input.expected().offset, 1);
+}
+
+#[test]
+fn varint_edges() {
+    use crate::{ParserBytes, builtins::{varint, sleb128, VarintErr}};
+
+    let max = [0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01];
+    assert_eq!(varint(&mut ParserBytes::from(&max)), Ok(u64::MAX));
+    let over = [0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x02];
+    assert_eq!(varint(&mut ParserBytes::from(&over)), Err(VarintErr::Overflow));
+    assert_eq!(varint(&mut ParserBytes::from(&[0xff; 11])), Err(VarintErr::Overflow));
+
+    let min = [0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x7f];
+    assert_eq!(sleb128(&mut ParserBytes::from(&min)), Ok(i64::MIN));
+    let max = [0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x00];
+    assert_eq!(sleb128(&mut ParserBytes::from(&max)), Ok(i64::MAX));
+    assert_eq!(sleb128(&mut ParserBytes::from(&[0x02])), Ok(2));
+    assert_eq!(sleb128(&mut ParserBytes::from(&[0x7e])), Ok(-2));
+}
+
+#[cfg(debug_assertions)]
+#[test]
+fn misuse_warns() {
+    use crate::MisuseCheck;
+
+    let mut input = ParserString::from("let y");
+    input.set_misuse_check(MisuseCheck::Warn);
+    let _ = take("let ").replace(take("x")).named("binding").or(take("fn")).parse(&mut input);
+    assert_eq!(input.diagnostics()[0].message, "binding failed at offset 5 after consuming input from offset 0");
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(expected = "parser failed at offset 5 after consuming input from offset 0")]
+fn misuse_panics() {
+    use crate::MisuseCheck;
+
+    let mut input = ParserString::from("let y");
+    input.set_misuse_check(MisuseCheck::Panic);
+    let _ = take("let ").replace(take("x")).or(take("fn")).parse(&mut input);
+}
+
+#[test]
+fn recursive_static_parser() {
+    use crate::{static_parser, builtins::CharErr};
+
+    //nesting depth of balanced parentheses
+    static_parser! {
+        static DEPTH: usize, CharErr = '('.replace(DEPTH.by_ref().many()).after(')')
+            .map(|inner| inner.into_iter().max().unwrap_or(0) + 1);
+    }
+
+    assert_eq!(DEPTH.parse(&mut ParserString::from("(()(()))")), Ok(3));
+    assert!(DEPTH.parse(&mut ParserString::from("(()")).is_err());
+}
+
+#[test]
+fn local_static_parsers() {
+    use crate::{combinators::StaticParser, builtins::{CharErr, WordErr}};
+
+    //distinct parsers that may share a stack address
+    #[inline(never)]
+    fn run<T: 'static, E: 'static>(p: StaticParser<T, E>, input: &str) -> Result<T, E> {
+        let p = std::hint::black_box(p);
+        p.parse(&mut ParserString::from(input))
+    }
+
+    for _ in 0..2 {
+        assert_eq!(run::<char, CharErr>(StaticParser::new(|| Box::new('1')), "1"), Ok('1'));
+        assert_eq!(run::<String, WordErr>(StaticParser::new(|| Box::new(word)), "ab"), Ok("ab".into()));
+        assert_eq!(run::<char, CharErr>(StaticParser::new(|| Box::new('2')), "2"), Ok('2'));
+    }
+}
+
+#[test]
+fn records_straddle_chunks() {
+    use crate::stream::{Records, RecordErr};
+
+    //multi-byte boundary and characters split across single-byte chunks
+    let data = "añb\r\n\r\nçd\r\ne";
+    let records: Vec<_> = Records::new(data.as_bytes(), word, "\r\n").chunk_size(1).collect();
+    assert_eq!(records.len(), 4);
+    assert_eq!(records[0].as_ref().unwrap(), "añb");
+    assert!(matches!(records[1], Err(RecordErr::Parse { err: Some(WordErr), record: 1, offset: 6 })));
+    assert_eq!(records[2].as_ref().unwrap(), "çd");
+    assert_eq!(records[3].as_ref().unwrap(), "e");
+
+    //partial boundaries at the end of a chunk are searched again once the rest arrives
+    for size in 1..=4 {
+        let records = Records::new("ab--c--|de--|--|f".as_bytes(), word, "--|").chunk_size(size);
+        let records: Vec<_> = records.map(|r| r.unwrap_or_default()).collect();
+        assert_eq!(records, ["ab--c", "de", "", "f"]);
+    }
+}
+
+#[test]
+fn repeated_bounds() {
+    use crate::combinators::RepeatedErr;
+
+    let p = 'a'.repeated().separated_by(',').at_least(2).at_most(3);
+    let mut input = ParserString::from("a,a,a,a");
+    assert_eq!(p.parse(&mut input), Ok(vec!['a'; 3]));
+    assert_eq!(input.get(), ",a");
+
+    let mut input = ParserString::from("a;a");
+    assert_eq!(p.parse(&mut input), Err(RepeatedErr::TooFew { min: 2, found: 1 }));
+
+    assert_eq!(p.grammar().to_string(), r#""a" ("," "a"){1,2}"#);
+}
+
+#[test]
+fn inputs_are_thread_safe() {
+    use crate::{ParserBytes, ParserTokens, ParserStr};
+
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<ParserString>();
+    assert_send_sync::<ParserBytes>();
+    assert_send_sync::<ParserTokens<char>>();
+    assert_send_sync::<ParserStr<'static>>();
+}
+
+#[test]
+fn size_limits() {
+    use crate::{SizeLimits, SizeExceeded, LimitErr, combinators::SizeErr};
+
+    let p = 'a'.repeated().separated_by(',').size_limited(SizeLimits::NONE.items(2));
+    let mut input = ParserString::from("a,a,a");
+    assert_eq!(p.parse(&mut input), Err(SizeErr::Exceeded(SizeExceeded::Items { offset: 3, max: 2 })));
+
+    let p = 'a'.sep_by(',').size_limited(SizeLimits::NONE.items(2));
+    let mut input = ParserString::from("a,a,a");
+    assert_eq!(p.parse(&mut input), Err(SizeErr::Exceeded(SizeExceeded::Items { offset: 3, max: 2 })));
+
+    let limits = SizeLimits::NONE.output(3);
+    let mut input = ParserString::from("abcd");
+    assert_eq!(take("abc").size_limited(limits).parse(&mut input), Ok("abc"));
+    let mut input = ParserString::from("abcd");
+    let res = take("abcd").size_limited(limits).parse(&mut input);
+    assert_eq!(res, Err(SizeErr::Exceeded(SizeExceeded::Output { offset: 0, len: 4, max: 3 })));
+
+    //without SizeLimited, the parse stops in place and the driver reports it
+    let mut input = ParserString::from("ab abcd");
+    input.set_size_limits(limits);
+    let res = take("ab").chain(whitespace).chain(take("abcd")).parse_limited(&mut input);
+    assert_eq!(res, Err(LimitErr::Size(SizeExceeded::Output { offset: 3, len: 4, max: 3 })));
+    assert_eq!(input.get(), "abcd");
+
+    //splitting text off directly isn't limited
+    let mut input = ParserString::from("abcd");
+    input.set_size_limits(limits);
+    assert_eq!(input.take(4), "abcd");
+    assert_eq!(input.size_exceeded(), None);
+}
+
+#[test]
+fn fuel_halts_in_place() {
+    use crate::{LimitErr, builtins::{length_prefixed, int}};
+
+    //running out inside a bounded region stops there, rather than at the end of the region
+    let p = length_prefixed(int::<usize, _>.after(whitespace), take("a").many());
+    let mut input = ParserString::from("4 aaaa rest");
+    input.set_fuel(Some(4));
+    assert!(matches!(p.parse_limited(&mut input), Err(LimitErr::Fuel { offset: 4 })));
+    assert_eq!(input.get(), "aa rest");
+
+    let mut input = ParserString::from("4 aaaa rest");
+    input.set_fuel(Some(100));
+    assert!(p.parse_limited(&mut input).is_ok());
+}
+
+#[test]
+fn trace_round_trip() {
+    use crate::inspect::{Trace, Event};
+
+    let mut input = ParserString::from("ab");
+    input.set_inspector(Trace::new());
+    let _ = take("a").named("a\\b\nc").many().parse(&mut input);
+    let trace = input.take_inspector::<Trace>().unwrap();
+
+    let read: Trace = trace.to_string().parse().unwrap();
+    assert_eq!(read, *trace);
+    assert_eq!(read.events()[1], Event::Enter { rule: "a\\b\nc".to_owned(), offset: 0 });
+    assert_eq!("> x y".parse::<Trace>().unwrap_err().line, 1);
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn json_nesting_limit() {
+    use crate::builtins::json::{value, ndjson, JsonErr, MAX_DEPTH};
+    use crate::stream::RecordErr;
+
+    let deep = format!("{}{}", "[".repeat(200_000), "]".repeat(200_000));
+    assert_eq!(value(&mut ParserString::from(deep.as_str())), Err(JsonErr::TooDeep { offset: MAX_DEPTH }));
+
+    let nested = format!("{}{}", "{\"a\":[".repeat(MAX_DEPTH / 2), "]}".repeat(MAX_DEPTH / 2));
+    assert!(value(&mut ParserString::from(nested.as_str())).is_ok());
+
+    //a line that is too deep doesn't end the stream
+    let log = format!("{deep}\n[1]\n");
+    let lines: Vec<_> = ndjson(log.as_bytes()).collect();
+    assert!(matches!(lines[0], Err(RecordErr::Parse { err: Some(JsonErr::TooDeep { .. }), .. })));
+    assert!(lines[1].is_ok());
+}
+
+#[cfg(feature = "peg")]
+#[test]
+fn peg_interpreter() {
+    use crate::peg::{Peg, PegErr};
+
+    let peg: Peg = r#"
+        # identifiers that aren't keywords
+        idents <- _sp (ident _sp)* !. ;
+        ident <- !keyword [a-zA-Z_] [a-zA-Z0-9_]{0,7} ;
+        keyword <- ("if" / "else") ![a-zA-Z0-9_] ;
+        _sp <- [ \t\u{a0}]* ;
+    "#.parse().unwrap();
+    assert_eq!(peg.rules().collect::<Vec<_>>(), ["idents", "_sp", "ident", "keyword"]);
+
+    let idents = peg.rule("idents").unwrap();
+    let text = "  iffy\u{a0}x1  abcdefgh ";
+    let node = idents.parse(&mut ParserString::from(text)).unwrap();
+    let names: Vec<_> = node.children_named("ident").map(|n| n.text(text)).collect();
+    assert_eq!(names, ["iffy", "x1", "abcdefgh"]);
+
+    assert!(idents.parse(&mut ParserString::from("a if b")).is_err());
+    assert!(idents.parse(&mut ParserString::from("a-b")).is_err());
+
+    assert_eq!(peg.rule("keyword").unwrap().grammar().to_string(), "keyword");
+    assert_eq!(Peg::new("a = !a 'x'"), Err(PegErr::LeftRecursive { name: "a".to_owned() }));
+    assert_eq!(Peg::new("a = 'x'\na = 'y'"), Err(PegErr::Duplicate { name: "a".to_owned() }));
+}
+
+#[cfg(feature = "proptest")]
+#[test]
+fn builtin_strategies() {
+    use crate::grammar::{Grammar, Builtin};
+
+    assert!(Grammar::Alt(vec![]).strategy().is_none());
+    assert!(word.named("word").grammar().strategy().is_none());
+    assert!(word.with_grammar(Grammar::Builtin(Builtin::Word)).grammar().strategy().is_some());
+    assert!(crate::builtins::url_until(&[]).grammar().strategy().is_some());
+}
+
+#[cfg(feature = "proptest")]
+#[test]
+fn fail_strategy() {
+    use crate::builtins::{fail, TakeErr};
+
+    let err = TakeErr::NoMatch { expected: "a", found: String::new() };
+    assert!(fail::<&str, _, str>(err.clone()).grammar().strategy().is_none());
+    assert!(fail(err).or(take("a")).grammar().strategy().is_some());
+}
+
+#[test]
+fn locations_across_line_endings() {
+    use crate::Location;
+
+    let input = ParserString::from("a\rb\r\nc\nd");
+    assert_eq!(input.location(2), Location { line: 2, column: 1 });
+    assert_eq!(input.location(4), Location { line: 2, column: 3 });
+    assert_eq!(input.location(5), Location { line: 3, column: 1 });
+    assert_eq!(input.location(7), Location { line: 4, column: 1 });
+    assert_eq!(input.snippet(2), "b\n^");
+
+    let input = ParserString::from("aé");
+    assert_eq!(input.location(2), Location { line: 1, column: 2 });
+    assert_eq!(input.snippet(2), "aé\n ^");
+}
+
+#[test]
+fn halted_input_is_not_empty() {
+    use crate::{SizeLimits, BacktrackLimit};
+
+    let p = take("a").many();
+
+    let mut input = ParserString::from("aaaa");
+    input.set_fuel(Some(3));
+    assert!(p.parse(&mut input).is_ok());
+    assert!(!input.is_empty());
+
+    let mut input = ParserString::from("aaaa");
+    input.set_size_limits(SizeLimits::NONE.items(2));
+    assert!(p.parse(&mut input).is_ok());
+    assert!(!input.is_empty());
+
+    let mut input = ParserString::from("abab");
+    input.set_backtrack_limit(BacktrackLimit::NONE.events(1));
+    assert!(take("ab").after(take("c")).or(take("ab")).many().parse(&mut input).is_ok());
+    assert!(input.backtrack_exceeded().is_some());
+    assert!(!input.is_empty());
+
+    let mut input = ParserString::from("aaaa");
+    input.set_fuel(Some(3));
+    let res = p.parse_tolerant(&mut input, |_| vec![]);
+    assert_eq!(res.errors().count(), 1);
+}
+
+#[test]
+fn number_errors_keep_their_cause() {
+    use crate::builtins::{int, float, ErrKind, BuiltinErr};
+
+    let err = int::<u8, _>.convert_err::<BuiltinErr>().parse(&mut ParserString::from("300")).unwrap_err();
+    assert_eq!(err.kind, ErrKind::Int("error parsing int: number too large to fit in target type".into()));
+    let err = float::<f32, _>.convert_err::<BuiltinErr>().parse(&mut ParserString::from("1.x")).unwrap_err();
+    assert_eq!(err.to_string(), "error parsing float: invalid float literal");
+}
@@ -0,0 +1,106 @@
+use std::{fmt::Debug, ops::Range};
+
+use crate::{ParserInput, Input, Checkpoint};
+
+/**
+A sequence of tokens, produced by a separate lexing step. Positions into it are counted in tokens.
+```rust
+# use parsa::{Parser, ParserTokens};
+#[derive(Debug, Clone, PartialEq)]
+enum Tok { Num(i64), Plus }
+#[derive(Debug, PartialEq, parsa::FromNever)]
+struct Unexpected;
+
+let num = |s: &mut ParserTokens<Tok>| match s.get().first() {
+    Some(&Tok::Num(n)) => { s.take(1); Ok(n) },
+    _ => Err(Unexpected),
+};
+let plus = |s: &mut ParserTokens<Tok>| match s.get().first() {
+    Some(Tok::Plus) => { s.take(1); Ok(()) },
+    _ => Err(Unexpected),
+};
+let sum = num.chain(plus.replace(num).many()).map(|(n, rest)| n + rest.iter().sum::<i64>());
+
+let mut input = ParserTokens::from(vec![Tok::Num(1), Tok::Plus, Tok::Num(2), Tok::Plus, Tok::Num(3)]);
+assert_eq!(sum.parse(&mut input), Ok(6));
+```
+*/
+#[repr(transparent)]
+pub struct Tokens<T>([T]);
+
+impl<T> Tokens<T> {
+    fn from_boxed(tokens: Box<[T]>) -> Box<Self> {
+        //SAFETY: Tokens is a transparent wrapper around [T]
+        unsafe { Box::from_raw(Box::into_raw(tokens) as *mut Self) }
+    }
+}
+
+impl<T: Debug> Input for Tokens<T> {
+    fn size(&self) -> usize { self.0.len() }
+
+    fn is_boundary(&self, offset: usize) -> bool { offset <= self.0.len() }
+
+    fn describe(&self, range: Range<usize>, max: usize) -> String {
+        let end = range.end.min(range.start.saturating_add(max)).min(self.0.len());
+        let tokens = self.0.get(range.start..end).unwrap_or_default();
+        tokens.iter().map(|t| format!("{t:?}")).collect::<Vec<_>>().join(" ")
+    }
+}
+
+///A shrinking-window read-only slice of tokens. See [`Tokens`].
+pub type ParserTokens<T> = ParserInput<Tokens<T>>;
+
+impl<T: Debug> ParserTokens<T> {
+    ///Splits off `n` tokens, shrinking the slice. Panics if `n` is larger than the remaining slice.
+    pub fn take(&mut self, n: usize) -> &[T] {
+        let start = self.ptr;
+        assert!(start + n <= self.end);
+        self.ptr = start + n;
+        &self.full.0[start..start + n]
+    }
+
+    ///Splits off `n` tokens, shrinking the slice. Returns [`None`] if `n` is larger than the
+    ///remaining slice.
+    pub fn try_take(&mut self, n: usize) -> Option<&[T]> {
+        let start = self.ptr;
+        if start + n > self.end {
+            return None;
+        }
+        self.ptr = start + n;
+        Some(&self.full.0[start..start + n])
+    }
+
+    ///Rewinds the slice `n` tokens. Panics if `n` is larger than the taken space.
+    pub fn give(&mut self, n: usize) {
+        self.ptr -= n;
+    }
+
+    ///Get a reference to the remaining tokens.
+    pub fn get(&self) -> &[T] {
+        &self.full.0[self.ptr..self.end]
+    }
+
+    ///Get the tokens consumed since `checkpoint` was taken. Panics if the input has been rewound
+    ///to before it.
+    pub fn since(&self, checkpoint: Checkpoint) -> &[T] {
+        &self.full.0[checkpoint.offset()..self.ptr]
+    }
+}
+
+impl<T: Debug> From<Vec<T>> for ParserTokens<T> {
+    fn from(value: Vec<T>) -> Self {
+        Self::new(Tokens::from_boxed(value.into_boxed_slice()))
+    }
+}
+
+impl<T: Debug + Clone> From<&[T]> for ParserTokens<T> {
+    fn from(value: &[T]) -> Self {
+        Self::from(value.to_vec())
+    }
+}
+
+impl<T: Debug> Debug for ParserTokens<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.get())
+    }
+}
@@ -0,0 +1,91 @@
+use std::fmt::Write;
+
+/**
+Render an instance of this type back to text, the counterpart of [`Parsable`](crate::Parsable).
+For a type implementing both, parsing the output of [`Unparse::unparse`] should give back an
+equal value, so a document can be parsed, modified and printed again with one source of truth
+for its syntax.
+
+Implemented for primitives, strings and [`Option`]s, using their [`Display`](std::fmt::Display)
+impls (or nothing, for [`None`]), and for pairs, which are written one after the other like the
+output of [`Chain`](crate::combinators::Chain).
+```rust
+# use parsa::{Parsable, Parser, ParserString, Unparse};
+# use parsa::builtins::{word, whitespace, take, BuiltinErr};
+#[derive(Debug, PartialEq)]
+struct Assign { name: String, value: String }
+
+impl Parsable for Assign {
+    type Err = BuiltinErr;
+    fn parse(s: &mut ParserString) -> Result<Self, Self::Err> {
+        let name = word.convert_err::<BuiltinErr>()
+            .after(whitespace)
+            .after(take("=").after(whitespace))
+            .parse(s)?;
+        Ok(Assign { name, value: word(s)? })
+    }
+}
+
+impl Unparse for Assign {
+    fn unparse(&self, out: &mut dyn std::fmt::Write) -> std::fmt::Result {
+        (&self.name, " = ").unparse(out)?;
+        self.value.unparse(out)
+    }
+}
+
+let mut assign = Assign::parse(&mut ParserString::from("x   =   1")).unwrap();
+assign.value = "2".to_owned();
+let text = assign.unparsed();
+assert_eq!(text, "x = 2");
+assert_eq!(Assign::parse(&mut ParserString::from(text)).unwrap(), assign);
+```
+*/
+pub trait Unparse {
+    ///Write this value as text.
+    fn unparse(&self, out: &mut dyn Write) -> std::fmt::Result;
+
+    ///Write this value into a new [`String`].
+    fn unparsed(&self) -> String {
+        let mut out = String::new();
+        //writing to a string can't fail
+        let _ = self.unparse(&mut out);
+        out
+    }
+}
+
+macro_rules! display {
+    ($($ty:ty),*) => { $(
+        impl Unparse for $ty {
+            fn unparse(&self, out: &mut dyn Write) -> std::fmt::Result {
+                write!(out, "{self}")
+            }
+        }
+    )* };
+}
+
+display!(str, String, char, bool, u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64);
+
+impl<T: Unparse + ?Sized> Unparse for &T {
+    fn unparse(&self, out: &mut dyn Write) -> std::fmt::Result {
+        (**self).unparse(out)
+    }
+}
+
+impl<T: Unparse + ?Sized> Unparse for Box<T> {
+    fn unparse(&self, out: &mut dyn Write) -> std::fmt::Result {
+        (**self).unparse(out)
+    }
+}
+
+impl<T: Unparse> Unparse for Option<T> {
+    fn unparse(&self, out: &mut dyn Write) -> std::fmt::Result {
+        self.as_ref().map_or(Ok(()), |v| v.unparse(out))
+    }
+}
+
+impl<T: Unparse, U: Unparse> Unparse for (T, U) {
+    fn unparse(&self, out: &mut dyn Write) -> std::fmt::Result {
+        self.0.unparse(out)?;
+        self.1.unparse(out)
+    }
+}